@@ -0,0 +1,348 @@
+use rand::{Rng, SeedableRng};
+
+use crate::prelude::*;
+use crate::cli::{Arguments, Command, Flag};
+use super::prelude::*;
+use super::pages;
+
+pub(crate) const COMMANDS: &[Command] = &[
+    Command {
+        name: "ca-forest-fire",
+        description: "forest fire automaton, number of cells of each kind per step",
+        flags: &[
+            Flag { name: "size", default: "64", description: "side of the lattice" },
+            Flag { name: "lightning_probability", default: "0.0001", description: "probability of a lightning on a tree" },
+            Flag { name: "growing_probability", default: "0.001", description: "probability of a tree growing" },
+            Flag { name: "max_time", default: "32", description: "simulated steps" },
+            Flag { name: "seed", default: "0", description: "random seed" },
+        ],
+        run: forest_fire,
+        chart: Some(forest_fire_chart),
+    },
+    Command {
+        name: "ca-sand-hourglass",
+        description: "falling sand block automaton, number of cells of each kind per step",
+        flags: &[
+            Flag { name: "map", default: "hourglass", description: "hourglass or hourglass_big" },
+            Flag { name: "max_time", default: "256", description: "simulated steps" },
+            Flag { name: "friction_probability", default: "0.198", description: "probability of the sand not falling" },
+            Flag { name: "seed", default: "0", description: "random seed" },
+        ],
+        run: sand_hourglass,
+        chart: Some(sand_hourglass_chart),
+    },
+    Command {
+        name: "ca-game-of-life",
+        description: "Conway's game of life, number of alive cells per step",
+        flags: &[
+            Flag { name: "state", default: "still", description: "still, oscillators, pulsar, glider, lwss, diehard, glider_gun or and_gate" },
+            Flag { name: "max_time", default: "32", description: "simulated steps" },
+        ],
+        run: game_of_life,
+        chart: Some(game_of_life_chart),
+    },
+    Command {
+        name: "ca-maze-solver",
+        description: "maze solving automaton, number of cells of each kind per step until it settles",
+        flags: &[
+            Flag { name: "maze", default: "maze0", description: "maze0 or maze1" },
+        ],
+        run: maze_solver,
+        chart: Some(maze_solver_chart),
+    },
+    Command {
+        name: "ca-elementary-automaton",
+        description: "elementary automaton, cells of each step",
+        flags: &[
+            Flag { name: "max_time", default: "256", description: "simulated steps" },
+            Flag { name: "resolution", default: "256", description: "number of cells" },
+            Flag { name: "boundary", default: "periodic", description: "fixed0, fixed1, periodic or reflective" },
+            Flag { name: "rule", default: "30", description: "Wolfram code of the rule" },
+            Flag { name: "initial_state", default: "single", description: "single, random, full or empty" },
+            Flag { name: "seed", default: "0", description: "random seed of the random initial state" },
+        ],
+        run: elementary_automaton,
+        chart: Some(elementary_automaton_chart),
+    },
+    Command {
+        name: "ca-traffic-jam",
+        description: "traffic jam automaton (rule 184), cells of each step",
+        flags: &[
+            Flag { name: "max_time", default: "256", description: "simulated steps" },
+            Flag { name: "resolution", default: "256", description: "number of cells" },
+            Flag { name: "boundary", default: "periodic", description: "fixed0, fixed1, periodic or reflective" },
+            Flag { name: "congestion", default: "0.3", description: "probability of a cell holding a car" },
+            Flag { name: "seed", default: "0", description: "random seed" },
+        ],
+        run: traffic_jam,
+        chart: Some(traffic_jam_chart),
+    },
+];
+
+fn forest_fire(arguments: &Arguments) -> Result<String, String> {
+    use forest_fire::*;
+
+    let model = ForestFireModel::new(
+        arguments.get("size")?,
+        arguments.get("seed")?,
+        arguments.get("lightning_probability")?,
+        arguments.get("growing_probability")?,
+    )?;
+
+    let counts = count_cells(model.take(arguments.get("max_time")?), |lattice| (
+        count(lattice, |cell| *cell == Cell::GreenTree),
+        count(lattice, |cell| *cell == Cell::BurningTree),
+        count(lattice, |cell| *cell == Cell::Empty),
+    ))?;
+
+    Ok(arguments.export(Simulation::new(counts.into_iter()), &["step", "green", "burning", "empty"]))
+}
+
+// the forest at max_time
+fn forest_fire_chart(arguments: &Arguments) -> Result<String, String> {
+    let (size, seed) = (arguments.get("size")?, arguments.get("seed")?);
+    let (lightning_probability, growing_probability) = (arguments.get("lightning_probability")?, arguments.get("growing_probability")?);
+    // the page reports its errors as JS values, the parameters are checked on the model first
+    forest_fire::ForestFireModel::new(size, seed, lightning_probability, growing_probability)?;
+
+    let params = pages::forest_fire::Params::builder()
+        .size(size)
+        .lightning_probability(lightning_probability)
+        .growing_probability(growing_probability)
+        .max_time(arguments.get("max_time")?)
+        .simulation_seed(seed);
+
+    let model = pages::forest_fire::Model::build(params).map_err(|_| "cannot build the forest".to_owned())?;
+    arguments.render((&model, u32::MAX))
+}
+
+fn sand_hourglass(arguments: &Arguments) -> Result<String, String> {
+    use sand_hourglass::*;
+
+    let map = match arguments.get_string("map")?.as_str() {
+        "hourglass" => maps::HOURGLASS,
+        "hourglass_big" => maps::HOURGLASS_BIG,
+        map => return Err(format!("unknown map '{map}'")),
+    };
+    let model = SandHourglassModel::from_str(map, arguments.get("seed")?, arguments.get("friction_probability")?)?;
+
+    let counts = count_cells(model.take(arguments.get("max_time")?), |lattice| (
+        count(lattice, |cell| *cell == Cell::Sand),
+        count(lattice, |cell| *cell == Cell::Empty),
+        count(lattice, |cell| *cell == Cell::Wall),
+    ))?;
+
+    Ok(arguments.export(Simulation::new(counts.into_iter()), &["step", "sand", "empty", "wall"]))
+}
+
+// the sand at max_time
+fn sand_hourglass_chart(arguments: &Arguments) -> Result<String, String> {
+    let map = arguments.get_string("map")?;
+    let (seed, friction_probability) = (arguments.get("seed")?, arguments.get("friction_probability")?);
+    let checked_map = match map.as_str() {
+        "hourglass" => sand_hourglass::maps::HOURGLASS,
+        "hourglass_big" => sand_hourglass::maps::HOURGLASS_BIG,
+        map => return Err(format!("unknown map '{map}'")),
+    };
+    sand_hourglass::SandHourglassModel::from_str(checked_map, seed, friction_probability)?;
+
+    let params = pages::sand_hourglass::Params::builder()
+        .map(map)
+        .max_time(arguments.get("max_time")?)
+        .friction_probability(friction_probability)
+        .simulation_seed(seed);
+
+    let model = pages::sand_hourglass::Model::build(params).map_err(|_| "cannot build the hourglass".to_owned())?;
+    arguments.render((&model, u32::MAX))
+}
+
+fn game_of_life(arguments: &Arguments) -> Result<String, String> {
+    use game_of_life::*;
+
+    // the same boundary of the page for each pattern
+    let (state, fixed_boundary) = match arguments.get_string("state")?.as_str() {
+        "still" => (states::STILL, true),
+        "oscillators" => (states::OSCILLATORS, true),
+        "pulsar" => (states::PULSAR, true),
+        "glider" => (states::GLIDER, false),
+        "lwss" => (states::LWSS, false),
+        "diehard" => (states::DIEHARD, true),
+        "glider_gun" => (states::GLIDER_GUN, false),
+        "and_gate" => (states::AND_GATE, false),
+        state => return Err(format!("unknown state '{state}'")),
+    };
+
+    let model: Box<dyn Iterator<Item = Result<State, String>>> =
+        if fixed_boundary {
+            Box::new(GameOfLife::<BoundaryFixed>::from_string(state).ok_or("State string parse error")?)
+        } else {
+            Box::new(GameOfLife::<BoundaryPeriodic>::from_string(state).ok_or("State string parse error")?)
+        };
+
+    let counts = count_cells(model.take(arguments.get("max_time")?), |lattice| count(lattice, |alive| *alive))?;
+
+    Ok(arguments.export(Simulation::new(counts.into_iter()), &["step", "alive"]))
+}
+
+// the cells at max_time
+fn game_of_life_chart(arguments: &Arguments) -> Result<String, String> {
+    let state = arguments.get_string("state")?;
+    if !["still", "oscillators", "pulsar", "glider", "lwss", "diehard", "glider_gun", "and_gate"].contains(&state.as_str()) {
+        return Err(format!("unknown state '{state}'"));
+    }
+
+    let params = pages::game_of_life::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .state(&state);
+
+    let model = pages::game_of_life::Model::build(params).map_err(|_| "cannot build the game of life".to_owned())?;
+    arguments.render((&model, u32::MAX))
+}
+
+fn maze_solver(arguments: &Arguments) -> Result<String, String> {
+    use maze::*;
+
+    let maze = match arguments.get_string("maze")?.as_str() {
+        "maze0" => mazes::MAZE0,
+        "maze1" => mazes::MAZE1,
+        maze => return Err(format!("unknown maze '{maze}'")),
+    };
+    let model = MazeSolver::from_string(maze).ok_or("Maze string parse error")?;
+
+    // the solver is run until the maze does not change anymore
+    let mut last_state: Option<Maze> = None;
+    let states = model.take_while(|state| match (state, &last_state) {
+        (Ok(state), Some(last_state)) if state == last_state => false,
+        (Ok(state), _) => {
+            last_state = Some(state.clone());
+            true
+        },
+        (Err(_), _) => true,
+    });
+
+    let counts = count_cells(states, |lattice| (
+        count(lattice, |cell| *cell == Cell::NotVisited),
+        count(lattice, |cell| matches!(cell, Cell::Visited{..})),
+        count(lattice, |cell| matches!(cell, Cell::Backtrace{..})),
+    ))?;
+
+    Ok(arguments.export(Simulation::new(counts.into_iter()), &["step", "not_visited", "visited", "backtrace"]))
+}
+
+// the solved maze
+fn maze_solver_chart(arguments: &Arguments) -> Result<String, String> {
+    let maze = arguments.get_string("maze")?;
+    if !["maze0", "maze1"].contains(&maze.as_str()) {
+        return Err(format!("unknown maze '{maze}'"));
+    }
+
+    let model = pages::maze_solver::Model::build(pages::maze_solver::Params::builder().maze(&maze))
+        .map_err(|_| "cannot build the maze".to_owned())?;
+    arguments.render((&model, u32::MAX))
+}
+
+fn elementary_automaton(arguments: &Arguments) -> Result<String, String> {
+    use elementary::*;
+
+    let resolution: usize = arguments.get("resolution")?;
+    let initial_state = arguments.get_string("initial_state")?;
+    let initial_state =
+        match StartingState::from_str(&initial_state).ok_or(format!("unknown initial state '{initial_state}'"))? {
+            StartingState::SingleCell => {
+                let mut state = Lattice::empty(resolution);
+                state.set(resolution / 2, true);
+
+                state
+            },
+            StartingState::Random => random_lattice(resolution, 0.5, arguments.get("seed")?),
+            StartingState::Full => Lattice::full(resolution),
+            StartingState::Empty => Lattice::empty(resolution),
+        };
+
+    let model = ElementaryAutomaton::new(initial_state, boundary(arguments)?, arguments.get("rule")?);
+
+    let names = cell_names(resolution);
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+    Ok(arguments.export(lattice_rows(model, arguments.get("max_time")?), &names))
+}
+
+fn elementary_automaton_chart(arguments: &Arguments) -> Result<String, String> {
+    arguments.render(pages::elementary_automaton::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .resolution(arguments.get("resolution")?)
+        .boundary(arguments.get_string("boundary")?)
+        .rule(arguments.get("rule")?)
+        .initial_state(arguments.get_string("initial_state")?)
+        .seed(arguments.get("seed")?))
+}
+
+fn traffic_jam(arguments: &Arguments) -> Result<String, String> {
+    use elementary::*;
+
+    let congestion: f64 = arguments.get("congestion")?;
+    if !(0f64..=1f64).contains(&congestion) {
+        return Err(format!("congestion {congestion} is not a probability"));
+    }
+
+    let resolution: usize = arguments.get("resolution")?;
+    let initial_state = random_lattice(resolution, congestion, arguments.get("seed")?);
+    let model = ElementaryAutomaton::new(initial_state, boundary(arguments)?, 184);
+
+    let names = cell_names(resolution);
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+    Ok(arguments.export(lattice_rows(model, arguments.get("max_time")?), &names))
+}
+
+fn traffic_jam_chart(arguments: &Arguments) -> Result<String, String> {
+    arguments.render(pages::traffic_jam::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .resolution(arguments.get("resolution")?)
+        .boundary(arguments.get_string("boundary")?)
+        .congestion(arguments.get("congestion")?)
+        .seed(arguments.get("seed")?))
+}
+
+// counts computed on each state of a 2D automaton, numbered by step
+fn count_cells<C, I, Y, F>(states: I, counts: F) -> Result<Vec<(u32, Y)>, String>
+    where C: Clone + Eq, I: Iterator<Item = Result<Lattice<C>, String>>, F: Fn(&Lattice<C>) -> Y
+{
+    states.enumerate()
+        .map(|(step, state)| state.map(|state| (step as u32, counts(&state))))
+        .collect()
+}
+
+fn count<C: Clone + Eq>(lattice: &Lattice<C>, predicate: impl Fn(&C) -> bool) -> u32 {
+    lattice.cells.iter().filter(|cell| predicate(cell)).count() as u32
+}
+
+// each step of an elementary automaton as a row of 0/1 cells
+fn lattice_rows(model: elementary::ElementaryAutomaton, max_time: u32) -> Simulation<impl Iterator<Item = (u32, Vec<u32>)> + Clone, u32, Vec<u32>> {
+    Simulation::new(model)
+        .time_limit(max_time)
+        .simulation_map(|(step, lattice)| (step, lattice.into_iter().map(u32::from).collect()))
+}
+
+// step, c0, c1, ...
+fn cell_names(size: usize) -> Vec<String> {
+    std::iter::once("step".to_owned())
+        .chain((0..size).map(|idx| format!("c{idx}")))
+        .collect()
+}
+
+fn random_lattice(size: usize, probability: f64, seed: u64) -> elementary::Lattice {
+    let mut state = elementary::Lattice::empty(size);
+    let distribution = rand::distributions::Bernoulli::new(probability).unwrap();
+    let mut rng = rand::rngs::SmallRng::seed_from_u64(seed);
+    for idx in 0..size {
+        state.set(idx, rng.sample(distribution));
+    }
+
+    state
+}
+
+fn boundary(arguments: &Arguments) -> Result<elementary::BoundaryCondition, String> {
+    let boundary = arguments.get_string("boundary")?;
+    elementary::BoundaryCondition::from_str(&boundary).ok_or(format!("unknown boundary '{boundary}'"))
+}
@@ -31,17 +31,72 @@ pub struct PeriodicBoundary;
 pub struct VonNeumannNeighborhood;
 pub struct MooreNeighborhood;
 
+// Relative coordinates of the neighbours of a cell, for the models that move
+// things between cells instead of computing each cell from its neighbourhood.
+pub trait Neighborhood {
+    const OFFSETS: &'static [(i32, i32)];
+}
+
+impl Neighborhood for VonNeumannNeighborhood {
+    const OFFSETS: &'static [(i32, i32)] = &[(0, -1), (1, 0), (0, 1), (-1, 0)];
+}
+
+impl Neighborhood for MooreNeighborhood {
+    const OFFSETS: &'static [(i32, i32)] = &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+}
+
+// Cell reached from coordinates that may lie past the edges of the lattice: the
+// periodic boundary wraps around, past a fixed boundary there is no cell.
+pub trait LatticeBoundary {
+    fn wrap(x: i64, y: i64, size: (u32, u32)) -> Option<(u32, u32)>;
+}
+
+impl LatticeBoundary for PeriodicBoundary {
+    fn wrap(x: i64, y: i64, size: (u32, u32)) -> Option<(u32, u32)> {
+        Some((x.rem_euclid(size.0 as i64) as u32, y.rem_euclid(size.1 as i64) as u32))
+    }
+}
+
+impl<C, B: ToCell<C>> LatticeBoundary for FixedBoundary<C, B> {
+    fn wrap(x: i64, y: i64, size: (u32, u32)) -> Option<(u32, u32)> {
+        let inside = (0..size.0 as i64).contains(&x) && (0..size.1 as i64).contains(&y);
+        inside.then_some((x as u32, y as u32))
+    }
+}
+
+// Cell at coordinates that may lie past the edges of the lattice, where a fixed
+// boundary is made of copies of its constant cell.
+pub trait BoundaryCell<C: Clone> {
+    fn get_cell(lattice: &Lattice<C>, x: i64, y: i64) -> C;
+}
+
+impl<C: Clone> BoundaryCell<C> for PeriodicBoundary {
+    fn get_cell(lattice: &Lattice<C>, x: i64, y: i64) -> C {
+        let (x, y) = Self::wrap(x, y, lattice.size).unwrap();
+        lattice.cells[lattice.get_index(x, y)].clone()
+    }
+}
+
+impl<C: Clone, B: ToCell<C>> BoundaryCell<C> for FixedBoundary<C, B> {
+    fn get_cell(lattice: &Lattice<C>, x: i64, y: i64) -> C {
+        match Self::wrap(x, y, lattice.size) {
+            Some((x, y)) => lattice.cells[lattice.get_index(x, y)].clone(),
+            None => B::to_cell(),
+        }
+    }
+}
+
 #[derive(Clone)]
 #[derive(PartialEq, Eq)]
 pub struct Lattice<C>
-    where C: Clone + PartialEq + Eq
+    where C: Clone
 {
     pub cells: Vec<C>,
     pub size: (u32, u32),
 }
 
 impl<C> Lattice<C>
-    where C: Clone + PartialEq + Eq
+    where C: Clone
 {
     pub fn from_fn(size_x: u32, size_y: u32, mut fun: impl FnMut(u32, u32) -> Result<C, String>) -> Result<Self, String> {
         let mut cells = Vec::with_capacity((size_x * size_y) as usize);
@@ -92,6 +147,14 @@ impl<C> Lattice<C>
         (x + y * self.size.0) as usize
     }
 
+    // coordinates of the neighbours of a cell, in the order of the offsets of `N`
+    pub fn neighbors<N: Neighborhood, B: LatticeBoundary>(&self, x: u32, y: u32) -> Vec<(u32, u32)> {
+        N::OFFSETS.iter()
+            .filter_map(|(dx, dy)| B::wrap(x as i64 + *dx as i64, y as i64 + *dy as i64, self.size))
+            .filter(|neighbor| *neighbor != (x, y))
+            .collect()
+    }
+
     pub fn get_result(&self, x: u32, y: u32) -> Result<C, String> {
         self.get(x, y) .cloned()
             .ok_or_else(|| format!("Lattice get error: {}/{}, {}/{}", x, self.size.0, y, self.size.1))
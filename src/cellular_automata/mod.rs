@@ -1,12 +1,15 @@
 pub mod pages {
-    mod traffic_jam;
-    mod elementary_automaton;
-    mod maze_solver;
-    mod game_of_life;
-    mod forest_fire;
-    mod sand_hourglass;
+    pub mod traffic_jam;
+    pub mod elementary_automaton;
+    pub mod maze_solver;
+    pub mod game_of_life;
+    pub mod forest_fire;
+    pub mod sand_hourglass;
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod commands;
+
 pub mod prelude {
     pub use super::common::*;
     pub use super::elementary_automaton as elementary;
@@ -23,6 +23,13 @@ pub struct Params {
     seed: u64,
 }
 
+// Forest animated on the page, advanced frame by frame with no step limit.
+#[wasm_bindgen(js_name = CA_FF_Stream)]
+pub struct Stream {
+    stream: crate::utils::Stream<u32, Result<ForestLattice, String>>,
+    state: RgbImage,
+}
+
 #[wasm_bindgen(js_class = CA_FF)]
 impl Model {
     pub fn build(params: Params) -> Result<Model, JsValue> {
@@ -50,19 +57,11 @@ impl Model {
         canvas.set_width(self.size.0);
         canvas.set_height(self.size.1);
 
-        let step = usize::min(self.states.len() - 1, step as usize);
-        draw_generic(Self::draw_function)(canvas, &self.states[step])
+        draw_generic(|area, step| (&*self, step).draw(area))(canvas, step)
     }
 
-    fn draw_function(canvas: HtmlCanvasElement, image: &RgbImage) -> MyDrawResult<()> {
-        let image = BitMapElement::with_owned_buffer(
-            (0, 0), (image.width(), image.height()),
-            image.pixels().flat_map(|elem| elem.0.clone().into_iter()).collect()
-        ).unwrap();
-
-        draw_prelude(canvas)?.draw(&image)?;
-
-        Ok(())
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, image: &RgbImage) -> MyDrawResult<()> {
+        draw_image(area, image)
     }
 
     fn maze_to_image(forest: &ForestLattice) -> image::RgbImage {
@@ -87,6 +86,57 @@ impl Model {
     }
 }
 
+impl Chart for (&Model, u32) {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        let (model, step) = self;
+        let step = usize::min(model.states.len() - 1, step as usize);
+
+        Model::draw_function(area, &model.states[step])
+    }
+}
+
+#[wasm_bindgen(js_class = CA_FF_Stream)]
+impl Stream {
+    pub fn build(params: Params) -> Result<Stream, JsValue> {
+        let solver = params.to_model()?;
+        let stream = crate::utils::Stream::new(solver.enumerate().map(|(step, state)| (step as u32, state)));
+
+        let mut stream = Self { stream, state: RgbImage::new(0, 0) };
+        stream.advance_steps(1)?;
+
+        Ok(stream)
+    }
+
+    pub fn advance_steps(&mut self, steps: u32) -> Result<(), JsValue> {
+        let last_state = self.stream.advance(steps as usize).into_iter()
+            .try_fold(None, |_, (_, state)| state.map(Some))
+            .map_err(|err| JsValue::from_str(&err))?;
+
+        if let Some(state) = last_state {
+            self.state = Model::maze_to_image(&state);
+        }
+
+        Ok(())
+    }
+
+    pub fn step(&self) -> u32 {
+        self.stream.steps().saturating_sub(1) as u32
+    }
+
+    pub fn draw(&self, canvas: HtmlCanvasElement) -> Result<(), JsValue> {
+        canvas.set_width(self.state.width());
+        canvas.set_height(self.state.height());
+
+        draw_canvas(canvas, self)
+    }
+}
+
+impl Chart for &Stream {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        Model::draw_function(area, &self.state)
+    }
+}
+
 #[wasm_bindgen(js_class = CA_FF_Params)]
 impl Params {
     pub fn builder() -> Self {
@@ -71,19 +71,11 @@ impl Model {
         canvas.set_width(self.size.0);
         canvas.set_height(self.size.1);
 
-        let step = usize::min(self.states.len() - 1, step as usize);
-        draw_generic(Self::draw_function)(canvas, &self.states[step])
+        draw_generic(|area, step| (&*self, step).draw(area))(canvas, step)
     }
 
-    fn draw_function(canvas: HtmlCanvasElement, image: &RgbImage) -> MyDrawResult<()> {
-        let image = BitMapElement::with_owned_buffer(
-            (0, 0), (image.width(), image.height()),
-            image.pixels().flat_map(|elem| elem.0.clone().into_iter()).collect()
-        ).unwrap();
-
-        draw_prelude(canvas)?.draw(&image)?;
-
-        Ok(())
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, image: &RgbImage) -> MyDrawResult<()> {
+        draw_image(area, image)
     }
 
     fn maze_to_image(maze: &Maze) -> image::RgbImage {
@@ -111,6 +103,15 @@ impl Model {
     }
 }
 
+impl Chart for (&Model, u32) {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        let (model, step) = self;
+        let step = usize::min(model.states.len() - 1, step as usize);
+
+        Model::draw_function(area, &model.states[step])
+    }
+}
+
 #[wasm_bindgen(js_class = CA_MAZE_Params)]
 impl Params {
     pub fn builder() -> Self {
@@ -129,6 +130,6 @@ impl Params {
 
     fn to_model(self) -> Result<MazeSolver, JsValue> {
         MazeSolver::from_string(&self.maze)
-            .ok_or(JsValue::from_str("Maze string parse error"))
+            .ok_or_else(|| JsValue::from_str("Maze string parse error"))
     }
 }
\ No newline at end of file
@@ -22,23 +22,8 @@ pub struct Params {
 #[wasm_bindgen(js_class = CA_TRAF)]
 impl Model {
     pub fn draw(canvas: HtmlCanvasElement, params: Params) -> Result<(), JsValue> {
-        // run the simulation
-        let max_time = params.max_time;
         let resolution = params.resolution;
-        let model = params.to_model();
-        let simulation = Simulation::new(model)
-            .time_limit(max_time);
-
-        // create the image
-        let mut image = image::RgbaImage::new(resolution, resolution);
-
-        image.fill(255);
-        for ((_, lattice), row) in simulation.zip(image.rows_mut()) {
-            for (active, cell) in lattice.into_iter().zip(row) {
-                let color = if active { YELLOW } else { BLUE };                
-                cell.0 = [color.0, color.1, color.2, 255];
-            }
-        }
+        let image = params.into_image();
 
         // fill the canvas
         let image = ImageData::new_with_u8_clamped_array_and_sh(
@@ -55,6 +40,18 @@ impl Model {
 
         Ok(())
     }
+
+    // same image on the other backends, the page fills the canvas with the image data
+    // directly as blitting it through plotters is way slower
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        draw_image(area, &image::DynamicImage::from(params.into_image()).into_rgb8())
+    }
+}
+
+impl Chart for Params {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        Model::draw_function(area, self)
+    }
 }
 
 #[wasm_bindgen(js_class = CA_TRAF_Params)]
@@ -88,6 +85,28 @@ impl Params {
         self
     }
 
+    fn into_image(self) -> image::RgbaImage {
+        // run the simulation
+        let max_time = self.max_time;
+        let resolution = self.resolution;
+        let model = self.to_model();
+        let simulation = Simulation::new(model)
+            .time_limit(max_time);
+
+        // create the image
+        let mut image = image::RgbaImage::new(resolution, resolution);
+
+        image.fill(255);
+        for ((_, lattice), row) in simulation.zip(image.rows_mut()) {
+            for (active, cell) in lattice.into_iter().zip(row) {
+                let color = if active { YELLOW } else { BLUE };                
+                cell.0 = [color.0, color.1, color.2, 255];
+            }
+        }
+
+        image
+    }
+
     fn to_model(self) -> ElementaryAutomaton {
         let mut initial_state = Lattice::empty(self.resolution as usize);
         let distribution = rand::distributions::Bernoulli::new(self.congestion).unwrap();
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use crate::chemical_reactions::prelude::*;
+
+// Well-mixed volume holding a part of the species of a network, e.g. the nucleus and
+// the cytoplasm of a cell. A species in a compartment is a molecule named
+// `species@compartment`, so the same species in two compartments are two molecules.
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct Compartment {
+    name: String,
+    volume: f32,
+}
+
+impl Compartment {
+    pub fn new(name: &str, volume: f32) -> Self {
+        Self { name: name.to_owned(), volume }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_volume(&self) -> f32 {
+        self.volume
+    }
+
+    pub fn molecule(&self, species: &str) -> Molecule {
+        Molecule::new(&format!("{species}@{}", self.name))
+    }
+}
+
+impl Molecule {
+    // compartment of a molecule made by `Compartment::molecule`
+    pub fn get_compartment(&self) -> Option<&str> {
+        self.get_name().rsplit_once('@').map(|(_, compartment)| compartment)
+    }
+}
+
+// Reaction network spread over compartments of different volumes.
+//
+// The kinetic laws of the reactions are given on concentrations, and each reaction
+// takes place in a compartment: its rate is the concentration reacting per unit of
+// time in that volume. Transport reactions move a species between compartments at
+// a rate proportional to its concentration in the source compartment.
+//
+// The network is turned into plain reactions over the amounts of the species
+// (dN/dt = V * rate(N / V)), which are simulated as they are by the SSA engines and
+// integrated by the ODE solvers, and converted back to concentrations.
+#[derive(Clone)]
+pub struct CompartmentalNetwork {
+    compartments: Vec<Compartment>,
+    reactions: Vec<(String, Reaction)>,
+}
+
+impl CompartmentalNetwork {
+    pub fn new<const C: usize>(compartments: [&Compartment; C]) -> Self {
+        Self {
+            compartments: compartments.into_iter().cloned().collect(),
+            reactions: vec![],
+        }
+    }
+
+    pub fn with_reaction(mut self, compartment: &Compartment, reaction: Reaction) -> Self {
+        self.reactions.push((compartment.name.clone(), reaction));
+        self
+    }
+
+    // first order transport: the concentration of `from` decays at rate `transport_rate`,
+    // the molecules reappear as `to` diluted in the volume of its compartment
+    pub fn with_transport(mut self, transport_rate: f32, from: &Molecule, to: &Molecule) -> Self {
+        let compartment = from.get_compartment().unwrap_or_default().to_owned();
+        self.reactions.push((compartment, Reaction::new(transport_rate, [(1, from)], [(1, to)])));
+        self
+    }
+
+    pub fn get_compartments(&self) -> &[Compartment] {
+        &self.compartments
+    }
+
+    pub fn get_volume(&self, molecule: &Molecule) -> Result<f32, String> {
+        let compartment = molecule.get_compartment()
+            .ok_or(format!("molecule '{}' is not in a compartment", molecule.get_name()))?;
+
+        self.compartment_volume(compartment)
+    }
+
+    // reactions over the amounts of the species, for ODESimulation and StochasticSimulation
+    pub fn reactions(&self) -> Result<Vec<Reaction>, String> {
+        let volumes: HashMap<Molecule, f32> = self.reactions.iter()
+            .flat_map(|(_, reaction)| reaction.get_species())
+            .map(|molecule| Ok((molecule.clone(), self.get_volume(molecule)?)))
+            .collect::<Result<_, String>>()?;
+
+        self.reactions.iter()
+            .map(|(compartment, reaction)| {
+                let volume = self.compartment_volume(compartment)?;
+                let kinetic_law = reaction.get_kinetic_law().over_amounts(reaction.get_reactants(), volume, &|molecule| volumes[molecule]);
+
+                Ok(reaction.clone().with_kinetic_law(kinetic_law))
+            })
+            .collect()
+    }
+
+    // molecule counts of the given concentrations, rounded to the nearest integer
+    pub fn to_counts(&self, concentrations: &HashMap<Molecule, f32>) -> Result<HashMap<Molecule, u32>, String> {
+        concentrations.iter()
+            .map(|(molecule, concentration)| Ok((molecule.clone(), (concentration * self.get_volume(molecule)?).round().max(0f32) as u32)))
+            .collect()
+    }
+
+    pub fn to_concentrations<T: Into<f64> + Copy>(&self, state: &[(Molecule, T)]) -> Result<Vec<(Molecule, f32)>, String> {
+        state.iter()
+            .map(|(molecule, amount)| Ok((molecule.clone(), ((*amount).into() / self.get_volume(molecule)? as f64) as f32)))
+            .collect()
+    }
+
+    fn compartment_volume(&self, name: &str) -> Result<f32, String> {
+        self.compartments.iter()
+            .find(|compartment| compartment.name == name)
+            .map(|compartment| compartment.volume)
+            .ok_or(format!("unknown compartment '{name}'"))
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+use crate::chemical_reactions::prelude::*;
+
+// Rate of a reaction as a function of the amounts of the species.
+//
+// Mass action is evaluated as k * prod(x^c) by the ODE derivation and as
+// k * prod(binomial(n, c)) by the stochastic simulators. All the other laws are
+// evaluated with the same formula on concentrations (ODE) and molecule counts (SSA).
+#[derive(Clone)]
+pub enum KineticLaw {
+    MassAction(f32),
+    // max_rate * s / (michaelis_constant + s)
+    MichaelisMenten { substrate: Molecule, max_rate: f32, michaelis_constant: f32 },
+    // max_rate * a^n / (half_saturation^n + a^n)
+    HillActivation { activator: Molecule, max_rate: f32, half_saturation: f32, hill_coefficient: f32 },
+    // max_rate * half_saturation^n / (half_saturation^n + r^n)
+    HillRepression { repressor: Molecule, max_rate: f32, half_saturation: f32, hill_coefficient: f32 },
+    // user supplied rate, reading only the listed species
+    Custom { species: Vec<Molecule>, rate_function: Rc<dyn Fn(&SpeciesAmounts) -> f32> },
+}
+
+// Read-only view over the amounts of the species, given to custom rate functions.
+pub struct SpeciesAmounts<'a> {
+    lookup: &'a dyn Fn(&Molecule) -> f32,
+}
+
+impl SpeciesAmounts<'_> {
+    pub fn get(&self, molecule: &Molecule) -> f32 {
+        (self.lookup)(molecule)
+    }
+}
+
+impl KineticLaw {
+    pub fn michaelis_menten(substrate: &Molecule, max_rate: f32, michaelis_constant: f32) -> Self {
+        Self::MichaelisMenten { substrate: substrate.clone(), max_rate, michaelis_constant }
+    }
+
+    pub fn hill_activation(activator: &Molecule, max_rate: f32, half_saturation: f32, hill_coefficient: f32) -> Self {
+        Self::HillActivation { activator: activator.clone(), max_rate, half_saturation, hill_coefficient }
+    }
+
+    pub fn hill_repression(repressor: &Molecule, max_rate: f32, half_saturation: f32, hill_coefficient: f32) -> Self {
+        Self::HillRepression { repressor: repressor.clone(), max_rate, half_saturation, hill_coefficient }
+    }
+
+    pub fn custom<const C: usize, F: Fn(&SpeciesAmounts) -> f32 + 'static>(species: [&Molecule; C], rate_function: F) -> Self {
+        Self::Custom {
+            species: species.into_iter().cloned().collect(),
+            rate_function: Rc::new(rate_function),
+        }
+    }
+
+    pub fn is_mass_action(&self) -> bool {
+        matches!(self, Self::MassAction(_))
+    }
+
+    // species, besides the reactants, whose amount changes the rate
+    pub fn get_modifiers(&self) -> Vec<&Molecule> {
+        match self {
+            Self::MassAction(_) => vec![],
+            Self::MichaelisMenten { substrate, .. } => vec![substrate],
+            Self::HillActivation { activator, .. } => vec![activator],
+            Self::HillRepression { repressor, .. } => vec![repressor],
+            Self::Custom { species, .. } => species.iter().collect(),
+        }
+    }
+
+    // deterministic rate, as used by the ODE derivation
+    pub fn get_rate(&self, reactants: &HashSet<(u32, Molecule)>, amount: &dyn Fn(&Molecule) -> f32) -> f32 {
+        match self {
+            Self::MassAction(kinetic_constant) => {
+                let reactants: f32 = reactants.iter()
+                    .map(|(stochiometric_coeff, reactant)| f32::powi(amount(reactant), *stochiometric_coeff as i32))
+                    .product();
+
+                kinetic_constant * reactants
+            },
+            law => law.get_non_mass_action_rate(amount),
+        }
+    }
+
+    // stochastic propensity over molecule counts
+    pub fn get_propensity(&self, reactants: &HashSet<(u32, Molecule)>, amount: &dyn Fn(&Molecule) -> u32) -> f32 {
+        match self {
+            Self::MassAction(kinetic_constant) => {
+                let distinct_reactant_combinations: u32 = reactants.iter()
+                    .map(|(stochiometric_coeff, molecule)| num::integer::binomial(amount(molecule), *stochiometric_coeff))
+                    .product();
+
+                kinetic_constant * (distinct_reactant_combinations as f32)
+            },
+            // the other laws do not vanish with the reactants, which must be there to fire
+            _ if reactants.iter().any(|(stochiometric_coeff, molecule)| amount(molecule) < *stochiometric_coeff) => 0f32,
+            law => law.get_non_mass_action_rate(&|molecule| amount(molecule) as f32),
+        }
+    }
+
+    // partial derivatives of `get_rate` with respect to the species it depends on
+    pub fn get_rate_gradient(&self, reactants: &HashSet<(u32, Molecule)>, amount: &dyn Fn(&Molecule) -> f32) -> Vec<(Molecule, f32)> {
+        let hill_derivative = |x: f32, max_rate: f32, half_saturation: f32, hill_coefficient: f32| {
+            let x = x.max(0f32);
+            let half_saturation = half_saturation.powf(hill_coefficient);
+            let derivative = max_rate * hill_coefficient * half_saturation * x.powf(hill_coefficient - 1f32)
+                / (half_saturation + x.powf(hill_coefficient)).powi(2);
+
+            if derivative.is_finite() { derivative } else { 0f32 }
+        };
+
+        match self {
+            Self::MassAction(kinetic_constant) => {
+                reactants.iter()
+                    .map(|(stochiometric_coeff, reactant)| {
+                        let others: f32 = reactants.iter()
+                            .filter(|(_, other)| other != reactant)
+                            .map(|(stochiometric_coeff, other)| f32::powi(amount(other), *stochiometric_coeff as i32))
+                            .product();
+
+                        let derivative = *stochiometric_coeff as f32 * f32::powi(amount(reactant), *stochiometric_coeff as i32 - 1);
+                        (reactant.clone(), kinetic_constant * derivative * others)
+                    })
+                    .collect()
+            },
+            Self::MichaelisMenten { substrate, max_rate, michaelis_constant } => {
+                let s = amount(substrate);
+                let derivative = if s < 0f32 { 0f32 } else { max_rate * michaelis_constant / (michaelis_constant + s).powi(2) };
+
+                vec![(substrate.clone(), if derivative.is_finite() { derivative } else { 0f32 })]
+            },
+            Self::HillActivation { activator, max_rate, half_saturation, hill_coefficient } =>
+                vec![(activator.clone(), hill_derivative(amount(activator), *max_rate, *half_saturation, *hill_coefficient))],
+            Self::HillRepression { repressor, max_rate, half_saturation, hill_coefficient } =>
+                vec![(repressor.clone(), -hill_derivative(amount(repressor), *max_rate, *half_saturation, *hill_coefficient))],
+            Self::Custom { species, .. } => {
+                // forward differences, the closure is opaque
+                let rate = self.get_non_mass_action_rate(amount);
+
+                species.iter()
+                    .map(|molecule| {
+                        let step = f32::EPSILON.sqrt() * amount(molecule).abs().max(1f32);
+                        let perturbed = |other: &Molecule| if other == molecule { amount(other) + step } else { amount(other) };
+
+                        (molecule.clone(), (self.get_non_mass_action_rate(&perturbed) - rate) / step)
+                    })
+                    .collect()
+            },
+        }
+    }
+
+    // The same law over the amounts of the species, for a reaction taking place in a
+    // compartment of volume `volume` whose rate is given on the concentrations of
+    // species living in compartments of volume `species_volume(molecule)`.
+    pub(crate) fn over_amounts(&self, reactants: &HashSet<(u32, Molecule)>, volume: f32, species_volume: &dyn Fn(&Molecule) -> f32) -> Self {
+        match self {
+            Self::MassAction(kinetic_constant) => {
+                let concentrations: f32 = reactants.iter()
+                    .map(|(stochiometric_coeff, reactant)| f32::powi(species_volume(reactant), -(*stochiometric_coeff as i32)))
+                    .product();
+
+                Self::MassAction(kinetic_constant * volume * concentrations)
+            },
+            Self::MichaelisMenten { substrate, max_rate, michaelis_constant } =>
+                Self::michaelis_menten(substrate, max_rate * volume, michaelis_constant * species_volume(substrate)),
+            Self::HillActivation { activator, max_rate, half_saturation, hill_coefficient } =>
+                Self::hill_activation(activator, max_rate * volume, half_saturation * species_volume(activator), *hill_coefficient),
+            Self::HillRepression { repressor, max_rate, half_saturation, hill_coefficient } =>
+                Self::hill_repression(repressor, max_rate * volume, half_saturation * species_volume(repressor), *hill_coefficient),
+            Self::Custom { species, rate_function } => {
+                let volumes: Vec<(Molecule, f32)> = species.iter().map(|molecule| (molecule.clone(), species_volume(molecule))).collect();
+                let rate_function = rate_function.clone();
+
+                Self::Custom {
+                    species: species.clone(),
+                    rate_function: Rc::new(move |amounts| {
+                        let concentration = |molecule: &Molecule| volumes.iter()
+                            .find(|(other, _)| other == molecule)
+                            .map(|(_, species_volume)| amounts.get(molecule) / species_volume)
+                            .unwrap_or(0f32);
+
+                        volume * rate_function(&SpeciesAmounts { lookup: &concentration })
+                    }),
+                }
+            },
+        }
+    }
+
+    pub(crate) fn get_non_mass_action_rate(&self, amount: &dyn Fn(&Molecule) -> f32) -> f32 {
+        let hill = |x: f32, half_saturation: f32, hill_coefficient: f32| {
+            let x = x.max(0f32).powf(hill_coefficient);
+            x / (half_saturation.powf(hill_coefficient) + x)
+        };
+
+        let rate =
+            match self {
+                Self::MassAction(_) => unreachable!(),
+                Self::MichaelisMenten { substrate, max_rate, michaelis_constant } => {
+                    let substrate = amount(substrate).max(0f32);
+                    max_rate * substrate / (michaelis_constant + substrate)
+                },
+                Self::HillActivation { activator, max_rate, half_saturation, hill_coefficient } =>
+                    max_rate * hill(amount(activator), *half_saturation, *hill_coefficient),
+                Self::HillRepression { repressor, max_rate, half_saturation, hill_coefficient } =>
+                    max_rate * (1f32 - hill(amount(repressor), *half_saturation, *hill_coefficient)),
+                Self::Custom { rate_function, .. } =>
+                    rate_function(&SpeciesAmounts { lookup: amount }),
+            };
+
+        if rate.is_finite() { rate.max(0f32) } else { 0f32 }
+    }
+}
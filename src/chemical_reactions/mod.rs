@@ -4,8 +4,18 @@ mod pages {
 
 pub mod prelude {
     pub use super::reaction::*;
+    pub use super::kinetic_law::*;
     pub use super::ode_simulation::*;
+    pub use super::network_analysis::*;
+    pub use super::parser::*;
+    pub use super::sbml::*;
+    pub use super::compartment::*;
 }
 
 mod reaction;
-mod ode_simulation;
\ No newline at end of file
+mod kinetic_law;
+mod ode_simulation;
+mod network_analysis;
+mod parser;
+mod sbml;
+mod compartment;
\ No newline at end of file
@@ -0,0 +1,232 @@
+use nalgebra::DMatrix;
+use num::rational::Ratio;
+use num::{Integer, Signed, Zero};
+use crate::chemical_reactions::prelude::*;
+
+// Structural analysis of a reaction network, independent of the kinetic laws.
+//
+// Conserved moieties are the vectors y of the left null space of the stoichiometry
+// matrix N (y^T N = 0): the weighted sum of their species never changes.
+#[derive(Clone)]
+pub struct NetworkAnalysis {
+    pub species: Vec<Molecule>,
+    pub conserved_moieties: Vec<ConservedMoiety>,
+    pub only_produced: Vec<Molecule>,
+    pub only_consumed: Vec<Molecule>,
+    pub unchanged: Vec<Molecule>,
+}
+
+#[derive(Clone, PartialEq)]
+#[derive(Debug)]
+pub struct ConservedMoiety(pub Vec<(i64, Molecule)>);
+
+impl ChemicalReactionODE {
+    // rows follow the species ids of the state vector, columns the reactions
+    pub fn stoichiometry_matrix(&self) -> DMatrix<i64> {
+        let species: Vec<Molecule> = (0..self.num_species())
+            .map(|id| self.get_species_from_id(&id).cloned().unwrap())
+            .collect();
+
+        Self::stoichiometry_of(&species, self.get_reactions())
+    }
+
+    pub fn analyze(&self) -> NetworkAnalysis {
+        let mut species: Vec<Molecule> = (0..self.num_species())
+            .map(|id| self.get_species_from_id(&id).cloned().unwrap())
+            .collect();
+        species.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+
+        let stoichiometry = Self::stoichiometry_of(&species, self.get_reactions());
+
+        let species_with = |predicate: &dyn Fn(&[i64]) -> bool| -> Vec<Molecule> {
+            species.iter().enumerate()
+                .filter(|(row, _)| predicate(&stoichiometry.row(*row).iter().cloned().collect::<Vec<_>>()))
+                .map(|(_, molecule)| molecule.clone())
+                .collect()
+        };
+
+        let only_produced = species_with(&|changes| changes.iter().all(|c| *c >= 0) && changes.iter().any(|c| *c > 0));
+        let only_consumed = species_with(&|changes| changes.iter().all(|c| *c <= 0) && changes.iter().any(|c| *c < 0));
+        let unchanged = species_with(&|changes| changes.iter().all(|c| *c == 0));
+
+        let conserved_moieties = Self::left_null_space(&stoichiometry).into_iter()
+            .map(|vector| ConservedMoiety(
+                vector.into_iter().zip(species.iter())
+                    .filter(|(coefficient, _)| *coefficient != 0)
+                    .map(|(coefficient, molecule)| (coefficient, molecule.clone()))
+                    .collect()
+            ))
+            .collect();
+
+        NetworkAnalysis { species, conserved_moieties, only_produced, only_consumed, unchanged }
+    }
+
+    fn stoichiometry_of(species: &[Molecule], reactions: &[Reaction]) -> DMatrix<i64> {
+        DMatrix::from_fn(species.len(), reactions.len(), |row, column| {
+            let reaction = &reactions[column];
+            let molecule = &species[row];
+
+            let consumed: i64 = reaction.get_reactants().iter()
+                .filter(|(_, reactant)| reactant == molecule)
+                .map(|(coefficient, _)| *coefficient as i64).sum();
+            let produced: i64 = reaction.get_products().iter()
+                .filter(|(_, product)| product == molecule)
+                .map(|(coefficient, _)| *coefficient as i64).sum();
+
+            produced - consumed
+        })
+    }
+
+    // integer basis of { y : y^T N = 0 }, from the reduced row echelon form of N^T
+    fn left_null_space(stoichiometry: &DMatrix<i64>) -> Vec<Vec<i64>> {
+        let (num_species, num_reactions) = stoichiometry.shape();
+        let mut matrix: Vec<Vec<Ratio<i64>>> = (0..num_reactions)
+            .map(|reaction| (0..num_species)
+                .map(|species| Ratio::from_integer(stoichiometry[(species, reaction)]))
+                .collect())
+            .collect();
+
+        let mut pivots: Vec<usize> = Vec::new();
+        for column in 0..num_species {
+            let row = pivots.len();
+            let Some(pivot_row) = (row..num_reactions).find(|r| !matrix[*r][column].is_zero()) else {
+                continue;
+            };
+
+            matrix.swap(row, pivot_row);
+            let pivot = matrix[row][column];
+            for value in matrix[row].iter_mut() {
+                *value /= pivot;
+            }
+
+            let pivot_values = matrix[row].clone();
+            for (other, values) in matrix.iter_mut().enumerate() {
+                let factor = values[column];
+                if other == row || factor.is_zero() {
+                    continue;
+                }
+
+                for (value, pivot_value) in values.iter_mut().zip(pivot_values.iter()) {
+                    *value -= factor * pivot_value;
+                }
+            }
+
+            pivots.push(column);
+        }
+
+        let basis = (0..num_species)
+            .filter(|column| !pivots.contains(column))
+            .map(|free| {
+                let mut vector = vec![Ratio::zero(); num_species];
+                vector[free] = Ratio::from_integer(1);
+                for (row, pivot) in pivots.iter().enumerate() {
+                    vector[*pivot] = -matrix[row][free];
+                }
+
+                let denominator = vector.iter().fold(1, |lcm, value| lcm.lcm(value.denom()));
+                let vector: Vec<i64> = vector.iter().map(|value| (value * denominator).to_integer()).collect();
+                Self::normalize(vector)
+            })
+            .collect();
+
+        Self::prefer_non_negative(basis)
+    }
+
+    // combine the basis vectors to remove negative coefficients where possible, so
+    // that moieties read as sums of species (e.g. `s + es + p` instead of `s + p - e`)
+    fn prefer_non_negative(mut basis: Vec<Vec<i64>>) -> Vec<Vec<i64>> {
+        let score = |vector: &[i64]| (
+            vector.iter().filter(|value| **value < 0).count(),
+            vector.iter().map(|value| value.abs()).sum::<i64>(),
+        );
+
+        let mut improved = true;
+        while improved {
+            improved = false;
+
+            for i in 0..basis.len() {
+                for j in 0..basis.len() {
+                    if i == j {
+                        continue;
+                    }
+
+                    for multiplier in [1, -1, 2, -2] {
+                        let candidate: Vec<i64> = basis[i].iter().zip(basis[j].iter())
+                            .map(|(a, b)| a + multiplier * b)
+                            .collect();
+                        let candidate = Self::normalize(candidate);
+
+                        if score(&candidate) < score(&basis[i]) {
+                            basis[i] = candidate;
+                            improved = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        basis
+    }
+
+    fn normalize(mut vector: Vec<i64>) -> Vec<i64> {
+        let divisor = vector.iter().fold(0, |gcd, value| gcd.gcd(value));
+        if divisor > 1 {
+            vector.iter_mut().for_each(|value| *value /= divisor);
+        }
+
+        let negatives = vector.iter().filter(|value| value.is_negative()).count();
+        let positives = vector.iter().filter(|value| value.is_positive()).count();
+        if negatives > positives {
+            vector.iter_mut().for_each(|value| *value = -*value);
+        }
+
+        vector
+    }
+}
+
+impl ConservedMoiety {
+    pub fn get_total(&self, state: &[(Molecule, f32)]) -> f32 {
+        self.0.iter()
+            .map(|(coefficient, molecule)| {
+                let amount = state.iter().find(|(other, _)| other == molecule).map(|(_, amount)| *amount).unwrap_or(0f32);
+                *coefficient as f32 * amount
+            })
+            .sum()
+    }
+}
+
+impl std::fmt::Display for ConservedMoiety {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (position, (coefficient, molecule)) in self.0.iter().enumerate() {
+            let sign = match (position, coefficient.is_negative()) {
+                (0, false) => "",
+                (0, true) => "-",
+                (_, false) => " + ",
+                (_, true) => " - ",
+            };
+
+            match coefficient.abs() {
+                1 => write!(f, "{sign}{}", molecule.get_name())?,
+                c => write!(f, "{sign}{c} {}", molecule.get_name())?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for NetworkAnalysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = |species: &[Molecule]| species.iter()
+            .map(|molecule| molecule.get_name()).collect::<Vec<_>>().join(", ");
+
+        writeln!(f, "species: {}", names(&self.species))?;
+        writeln!(f, "conserved moieties:")?;
+        for moiety in self.conserved_moieties.iter() {
+            writeln!(f, "  {moiety}")?;
+        }
+        writeln!(f, "only produced: {}", names(&self.only_produced))?;
+        writeln!(f, "only consumed: {}", names(&self.only_consumed))?;
+        write!(f, "unchanged: {}", names(&self.unchanged))
+    }
+}
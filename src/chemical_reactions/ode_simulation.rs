@@ -1,7 +1,8 @@
 use std::collections::{HashMap, HashSet};
 use ode_solvers::*;
+use ode_solvers::dop_shared::SolverResult;
 use crate::chemical_reactions::prelude::*;
-use crate::continuous_dynamical_systems::ODESolver;
+use crate::continuous_dynamical_systems::{ODESolver, Jacobian, Ros23, DelaySystem, DelayRk4};
 
 #[derive(Clone)]
 pub struct ChemicalReactionODE {
@@ -40,16 +41,77 @@ impl ChemicalReactionODE {
     pub fn num_species(&self) -> usize {
         self.species.len()
     }
+
+    pub fn get_reactions(&self) -> &[Reaction] {
+        &self.reactions
+    }
 }
 
 impl ode_solvers::System<f32, ode_solvers::DVector<f32>> for ChemicalReactionODE {
     fn system(&self, _: f32, y: &ode_solvers::DVector<f32>, dy: &mut ode_solvers::DVector<f32>) {
+        // the solvers reuse their buffers between stages, the reactions only add their contribution
+        dy.fill(0f32);
         for reaction in self.reactions.iter() {
             reaction.apply_ode(&self.species, y, dy);
         }
     }
 }
 
+impl Jacobian<ode_solvers::DVector<f32>> for ChemicalReactionODE {
+    fn jacobian(&self, _: f32, y: &ode_solvers::DVector<f32>, jacobian: &mut nalgebra::DMatrix<f32>) {
+        jacobian.fill(0f32);
+        for reaction in self.reactions.iter() {
+            reaction.apply_jacobian(&self.species, y, jacobian);
+        }
+    }
+}
+
+// The reaction rate equations of a network whose reactions release their products a
+// fixed delay after firing, none before the first delay has elapsed.
+#[derive(Clone)]
+pub struct DelayedChemicalReactionODE {
+    ode: ChemicalReactionODE,
+    // `reaction_delays[i]` is the position of the delay of the i-th reaction in `delays`
+    reaction_delays: Vec<Option<usize>>,
+    delays: Vec<f32>,
+}
+
+impl DelayedChemicalReactionODE {
+    // `delays[i]` is the delay of the i-th reaction, `None` for the instantaneous ones
+    pub fn new(reactions: Vec<Reaction>, delays: &[Option<f32>]) -> Self {
+        let mut distinct_delays: Vec<f32> = Vec::new();
+        let reaction_delays = (0..reactions.len())
+            .map(|reaction_id| {
+                let delay = delays.get(reaction_id).cloned().flatten().filter(|delay| *delay > 0f32)?;
+                let position = distinct_delays.iter().position(|other| *other == delay)
+                    .unwrap_or_else(|| { distinct_delays.push(delay); distinct_delays.len() - 1 });
+                Some(position)
+            })
+            .collect();
+
+        Self { ode: ChemicalReactionODE::new(reactions), reaction_delays, delays: distinct_delays }
+    }
+}
+
+impl DelaySystem<DVector<f32>> for DelayedChemicalReactionODE {
+    fn delays(&self) -> Vec<f32> {
+        self.delays.clone()
+    }
+
+    fn system(&self, x: f32, y: &DVector<f32>, delayed: &[DVector<f32>], dy: &mut DVector<f32>) {
+        dy.fill(0f32);
+        for (reaction, delay_id) in self.ode.reactions.iter().zip(self.reaction_delays.iter()) {
+            match delay_id {
+                Some(delay_id) => {
+                    let delayed_y = Some(&delayed[*delay_id]).filter(|_| x >= self.delays[*delay_id]);
+                    reaction.apply_delayed_ode(&self.ode.species, y, delayed_y, dy);
+                },
+                None => reaction.apply_ode(&self.ode.species, y, dy),
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ODESimulation {
     data: Vec<(f32, Vec<(Molecule, f32)>)>
@@ -78,9 +140,33 @@ impl ODESimulation {
                     stepper.integrate().map_err(|err| err.to_string())?;
                     stepper.results().to_owned()
                 },
+                ODESolver::ROS23 => {
+                    let mut stepper = Ros23::new(ode.clone(), 0f32, max_time, step_size, initial_state, 1.0e-2, 1.0e-6);
+                    stepper.integrate().map_err(|err| err.to_string())?;
+                    stepper.results().to_owned()
+                },
             };
 
-        let (time, molecules) = data.get();
+        Ok(Self::from_results(&ode, &data))
+    }
+
+    // The products of the delayed reactions are released a fixed delay after firing,
+    // `delays[i]` is the delay of the i-th reaction. Integrated by the classic Runge-Kutta
+    // method of order 4 for delay differential equations, nothing fired before time zero.
+    pub fn new_delayed(reactions: Vec<Reaction>, delays: &[Option<f32>], initial_state: HashMap<Molecule, u32>, max_time: f32) -> Result<Self, String> {
+        // explicit only, so a shorter step for the stiff networks
+        let step_size = 0.001f32;
+        let ode = DelayedChemicalReactionODE::new(reactions, delays);
+        let initial_state = Self::initial_state_to_vector(&ode.ode, &initial_state);
+
+        let mut stepper = DelayRk4::new(ode.clone(), 0f32, max_time, step_size, |_| initial_state.clone());
+        stepper.integrate().map_err(|err| err.to_string())?;
+
+        Ok(Self::from_results(&ode.ode, stepper.results()))
+    }
+
+    fn from_results(ode: &ChemicalReactionODE, results: &SolverResult<f32, DVector<f32>>) -> Self {
+        let (time, molecules) = results.get();
         let data = time.iter().cloned()
             .zip(
                 molecules.iter().map(|molecules| molecules.iter().enumerate()
@@ -92,7 +178,19 @@ impl ODESimulation {
             )
             .collect();
 
-        Ok(Self { data })
+        Self { data }
+    }
+
+    // the same trajectory with each amount transformed, e.g. into a concentration
+    pub fn map_amounts(self, function: impl Fn(&Molecule, f32) -> f32) -> Self {
+        let data = self.data.into_iter()
+            .map(|(time, molecules)| (time, molecules.into_iter().map(|(molecule, amount)| {
+                let amount = function(&molecule, amount);
+                (molecule, amount)
+            }).collect()))
+            .collect();
+
+        Self { data }
     }
 
     fn initial_state_to_vector(ode: &ChemicalReactionODE, initial_state: &HashMap<Molecule, u32>) -> DVector<f32> {
@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+use crate::chemical_reactions::prelude::*;
+
+// Parses reaction networks written as text, e.g.:
+//
+//   e = 100; s = 50
+//   e + s <-> es @ 0.01, 0.5
+//   es -> e + p @ 0.1
+//   p -> @ 0.001            # degradation, products may be empty
+//
+// Statements are separated by newlines or `;`, comments start with `#`.
+// Stoichiometric coefficients prefix the species name (`2 a` or `2a`).
+#[derive(Clone)]
+pub struct ReactionNetwork {
+    pub reactions: Vec<Reaction>,
+    pub initial_state: HashMap<Molecule, u32>,
+}
+
+impl ReactionNetwork {
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let tokens = Lexer::new(text).tokenize()?;
+        Parser::new(tokens).parse_network()
+    }
+}
+
+impl std::str::FromStr for ReactionNetwork {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse(text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(position: (usize, usize), message: String) -> Self {
+        Self { line: position.0, column: position.1, message }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError { }
+
+#[derive(Clone, PartialEq)]
+enum TokenKind {
+    Identifier(String),
+    Number(String),
+    Plus,
+    Arrow,
+    ReversibleArrow,
+    At,
+    Comma,
+    Equals,
+    Separator,
+    End,
+}
+
+impl TokenKind {
+    fn describe(&self) -> String {
+        match self {
+            TokenKind::Identifier(name) => format!("species '{name}'"),
+            TokenKind::Number(number) => format!("number '{number}'"),
+            TokenKind::Plus => "'+'".to_owned(),
+            TokenKind::Arrow => "'->'".to_owned(),
+            TokenKind::ReversibleArrow => "'<->'".to_owned(),
+            TokenKind::At => "'@'".to_owned(),
+            TokenKind::Comma => "','".to_owned(),
+            TokenKind::Equals => "'='".to_owned(),
+            TokenKind::Separator => "end of statement".to_owned(),
+            TokenKind::End => "end of input".to_owned(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Token {
+    kind: TokenKind,
+    position: (usize, usize),
+}
+
+struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { chars: text.chars().peekable(), line: 1, column: 1 }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(ch)
+    }
+
+    fn take_while(&mut self, buffer: &mut String, predicate: impl Fn(char) -> bool) {
+        while let Some(&ch) = self.chars.peek() {
+            if !predicate(ch) {
+                break;
+            }
+
+            buffer.push(ch);
+            self.bump();
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token>, ParseError> {
+        let mut tokens = Vec::new();
+
+        while let Some(&ch) = self.chars.peek() {
+            let position = (self.line, self.column);
+            let kind =
+                match ch {
+                    '\n' | ';' => { self.bump(); TokenKind::Separator },
+                    '#' => {
+                        while self.chars.peek().is_some_and(|ch| *ch != '\n') {
+                            self.bump();
+                        }
+                        continue;
+                    },
+                    ch if ch.is_whitespace() => { self.bump(); continue; },
+                    '+' => { self.bump(); TokenKind::Plus },
+                    '@' => { self.bump(); TokenKind::At },
+                    ',' => { self.bump(); TokenKind::Comma },
+                    '=' => { self.bump(); TokenKind::Equals },
+                    '-' => {
+                        self.bump();
+                        self.expect_char('>', position, "'->'")?;
+                        TokenKind::Arrow
+                    },
+                    '<' => {
+                        self.bump();
+                        self.expect_char('-', position, "'<->'")?;
+                        self.expect_char('>', position, "'<->'")?;
+                        TokenKind::ReversibleArrow
+                    },
+                    ch if ch.is_ascii_digit() || ch == '.' => TokenKind::Number(self.number()),
+                    ch if ch.is_alphabetic() || ch == '_' => {
+                        let mut name = String::new();
+                        self.take_while(&mut name, |ch| ch.is_alphanumeric() || ch == '_');
+                        TokenKind::Identifier(name)
+                    },
+                    ch => {
+                        return Err(ParseError::new(position, format!("unexpected character '{ch}'")));
+                    },
+                };
+
+            tokens.push(Token { kind, position });
+        }
+
+        tokens.push(Token { kind: TokenKind::End, position: (self.line, self.column) });
+        Ok(tokens)
+    }
+
+    fn expect_char(&mut self, expected: char, position: (usize, usize), token: &str) -> Result<(), ParseError> {
+        if self.chars.peek() == Some(&expected) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(ParseError::new(position, format!("expected {token}")))
+        }
+    }
+
+    fn number(&mut self) -> String {
+        let mut number = String::new();
+        self.take_while(&mut number, |ch| ch.is_ascii_digit() || ch == '.');
+
+        // the exponent is consumed only when followed by digits, so that `2e` is read as two `e` molecules
+        let mut lookahead = self.chars.clone();
+        if matches!(lookahead.next(), Some('e' | 'E')) {
+            let after_exponent = match lookahead.next() {
+                Some('+' | '-') => lookahead.next(),
+                other => other,
+            };
+
+            if after_exponent.is_some_and(|ch| ch.is_ascii_digit()) {
+                number.push(self.bump().unwrap());
+                if let Some(&sign @ ('+' | '-')) = self.chars.peek() {
+                    number.push(sign);
+                    self.bump();
+                }
+                self.take_while(&mut number, |ch| ch.is_ascii_digit());
+            }
+        }
+
+        number
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Self {
+        Self { tokens, position: 0 }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position]
+    }
+
+    fn peek_next(&self) -> &Token {
+        &self.tokens[usize::min(self.position + 1, self.tokens.len() - 1)]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.position].clone();
+        if token.kind != TokenKind::End {
+            self.position += 1;
+        }
+
+        token
+    }
+
+    fn unexpected(token: &Token, expected: &str) -> ParseError {
+        ParseError::new(token.position, format!("expected {expected}, found {}", token.kind.describe()))
+    }
+
+    fn parse_network(mut self) -> Result<ReactionNetwork, ParseError> {
+        let mut reactions = Vec::new();
+        let mut initial_state = HashMap::new();
+        let mut assignments = Vec::new();
+
+        loop {
+            match (&self.peek().kind, &self.peek_next().kind) {
+                (TokenKind::End, _) => break,
+                (TokenKind::Separator, _) => { self.advance(); continue; },
+                (TokenKind::Identifier(_), TokenKind::Equals) => {
+                    let (molecule, amount, position) = self.parse_assignment()?;
+                    if initial_state.insert(molecule.clone(), amount).is_some() {
+                        return Err(ParseError::new(position, format!("initial amount of '{}' is defined twice", molecule.get_name())));
+                    }
+                    assignments.push((molecule, position));
+                },
+                _ => reactions.extend(self.parse_reaction()?),
+            }
+
+            let token = self.advance();
+            match token.kind {
+                TokenKind::Separator | TokenKind::End => (),
+                _ => return Err(Self::unexpected(&token, "';' or a new line")),
+            }
+        }
+
+        // the simulators only track species that take part in some reaction
+        for (molecule, position) in assignments {
+            let used = reactions.iter()
+                .any(|reaction: &Reaction| reaction.get_species().contains(&molecule));
+
+            if !used {
+                return Err(ParseError::new(position, format!("species '{}' has an initial amount but appears in no reaction", molecule.get_name())));
+            }
+        }
+
+        Ok(ReactionNetwork { reactions, initial_state })
+    }
+
+    fn parse_assignment(&mut self) -> Result<(Molecule, u32, (usize, usize)), ParseError> {
+        let token = self.advance();
+        let TokenKind::Identifier(name) = token.kind else { unreachable!() };
+        self.advance();
+
+        let amount_token = self.advance();
+        let TokenKind::Number(amount) = &amount_token.kind else {
+            return Err(Self::unexpected(&amount_token, "an initial amount"));
+        };
+        let amount = amount.parse::<u32>()
+            .map_err(|_| ParseError::new(amount_token.position, format!("initial amount '{amount}' is not a non-negative integer")))?;
+
+        Ok((Molecule::new(&name), amount, token.position))
+    }
+
+    fn parse_reaction(&mut self) -> Result<Vec<Reaction>, ParseError> {
+        let reactants = self.parse_side()?;
+
+        let arrow = self.advance();
+        let reversible = match arrow.kind {
+            TokenKind::Arrow => false,
+            TokenKind::ReversibleArrow => true,
+            _ => return Err(Self::unexpected(&arrow, "'->' or '<->'")),
+        };
+
+        let products = self.parse_side()?;
+
+        let at = self.advance();
+        if at.kind != TokenKind::At {
+            return Err(Self::unexpected(&at, "'@' followed by the kinetic constant"));
+        }
+
+        let forward_constant = self.parse_kinetic_constant()?;
+        if !reversible {
+            return Ok(vec![Reaction::from_vec(forward_constant, reactants, products)]);
+        }
+
+        let comma = self.advance();
+        if comma.kind != TokenKind::Comma {
+            return Err(Self::unexpected(&comma, "',' followed by the backward kinetic constant"));
+        }
+
+        let backward_constant = self.parse_kinetic_constant()?;
+        Ok(vec![
+            Reaction::from_vec(forward_constant, reactants.clone(), products.clone()),
+            Reaction::from_vec(backward_constant, products, reactants),
+        ])
+    }
+
+    fn parse_side(&mut self) -> Result<Vec<(u32, Molecule)>, ParseError> {
+        let mut side: Vec<(u32, Molecule)> = Vec::new();
+
+        if !matches!(self.peek().kind, TokenKind::Identifier(_) | TokenKind::Number(_)) {
+            return Ok(side);
+        }
+
+        loop {
+            let (coefficient, molecule) = self.parse_term()?;
+            match side.iter_mut().find(|(_, other)| other == &molecule) {
+                Some((other_coefficient, _)) => *other_coefficient += coefficient,
+                None => side.push((coefficient, molecule)),
+            }
+
+            if self.peek().kind != TokenKind::Plus {
+                return Ok(side);
+            }
+            self.advance();
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<(u32, Molecule), ParseError> {
+        let mut token = self.advance();
+        let mut coefficient = 1;
+
+        if let TokenKind::Number(number) = &token.kind {
+            coefficient = number.parse::<u32>()
+                .ok().filter(|coefficient| *coefficient > 0)
+                .ok_or_else(|| ParseError::new(token.position, format!("stoichiometric coefficient '{number}' is not a positive integer")))?;
+            token = self.advance();
+        }
+
+        match token.kind {
+            TokenKind::Identifier(name) => Ok((coefficient, Molecule::new(&name))),
+            _ => Err(Self::unexpected(&token, "a species name")),
+        }
+    }
+
+    fn parse_kinetic_constant(&mut self) -> Result<f32, ParseError> {
+        let token = self.advance();
+        let TokenKind::Number(number) = &token.kind else {
+            return Err(Self::unexpected(&token, "a kinetic constant"));
+        };
+
+        number.parse::<f32>()
+            .ok().filter(|constant| constant.is_finite())
+            .ok_or_else(|| ParseError::new(token.position, format!("kinetic constant '{number}' is not a valid number")))
+    }
+}
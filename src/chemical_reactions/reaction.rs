@@ -1,19 +1,35 @@
 use std::collections::{HashMap, HashSet};
+use crate::prelude::*;
+use crate::chemical_reactions::prelude::*;
 
 #[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Debug)]
 pub struct Molecule(String);
 
 impl Molecule {
-    pub fn new(str: &'static str) -> Self {
+    pub fn new(str: &str) -> Self {
         Self(str.to_owned())
     }
+
+    pub fn get_name(&self) -> &str {
+        &self.0
+    }
+}
+
+// amounts of the species, exported in columns named after the molecules
+impl<T: Columns> Columns for (Molecule, T) {
+    fn columns(&self) -> Vec<(Option<String>, f64)> {
+        self.1.columns().into_iter()
+            .map(|(_, value)| (Some(self.0.get_name().to_owned()), value))
+            .collect()
+    }
 }
 
 #[derive(Clone)]
 pub struct Reaction {
     reactants: HashSet<(u32, Molecule)>,
     products: HashSet<(u32, Molecule)>,
-    kinetic_constant: f32
+    kinetic_law: KineticLaw,
 }
 
 impl Reaction {
@@ -21,33 +37,73 @@ impl Reaction {
         Self {
             reactants: reactants.iter().map(|(k, mol)| (*k, (*mol).clone())).collect(),
             products: products.iter().map(|(k, mol)| (*k, (*mol).clone())).collect(),
-            kinetic_constant,
+            kinetic_law: KineticLaw::MassAction(kinetic_constant),
+        }
+    }
+
+    pub fn from_vec(kinetic_constant: f32, reactants: Vec<(u32, Molecule)>, products: Vec<(u32, Molecule)>) -> Self {
+        Self {
+            reactants: reactants.into_iter().collect(),
+            products: products.into_iter().collect(),
+            kinetic_law: KineticLaw::MassAction(kinetic_constant),
         }
     }
 
+    pub fn with_kinetic_law(mut self, kinetic_law: KineticLaw) -> Self {
+        self.kinetic_law = kinetic_law;
+        self
+    }
+
     pub fn get_propensity(&self, molecules: &HashMap<Molecule, u32>) -> f32 {
-        let distinct_reactant_combinations: u32 =
-            self.reactants.iter()
-            .map(|(stochiometric_coeff, molecule)| {
-                let molecule_amount = molecules.get(molecule).cloned().unwrap_or(0u32);
-                num::integer::binomial(molecule_amount, *stochiometric_coeff)
-            })
-            .product();
-
-        self.kinetic_constant * (distinct_reactant_combinations as f32)
+        self.kinetic_law.get_propensity(&self.reactants, &|molecule| molecules.get(molecule).cloned().unwrap_or(0u32))
     }
 
     pub fn apply_ode(&self, species: &HashMap<Molecule, usize>, y: &ode_solvers::DVector<f32>, dy: &mut ode_solvers::DVector<f32>) {
-        let reactants: f32 = self.reactants.iter()
-            .map(|(stochiometric_coeff, reactant)| f32::powi(y[species[reactant]], *stochiometric_coeff as i32))
-            .product();
+        let rate = self.kinetic_law.get_rate(&self.reactants, &|molecule| species.get(molecule).map(|id| y[*id]).unwrap_or(0f32));
 
         for (stochiometric_coeff, reactant) in self.reactants.iter() {
-            dy[species[reactant]] -= (*stochiometric_coeff as f32) * self.kinetic_constant * reactants;
+            dy[species[reactant]] -= (*stochiometric_coeff as f32) * rate;
         }
         
         for (stochiometric_coeff, product) in self.products.iter() {
-            dy[species[product]] += (*stochiometric_coeff as f32) * self.kinetic_constant * reactants;
+            dy[species[product]] += (*stochiometric_coeff as f32) * rate;
+        }
+    }
+
+    // A delayed reaction takes its reactants at the current rate and releases its products
+    // at the rate it had one delay before, `delayed_y` is `None` while nothing fired yet.
+    // Only the net changes are delayed, the species on both sides are never taken.
+    pub fn apply_delayed_ode(&self, species: &HashMap<Molecule, usize>, y: &ode_solvers::DVector<f32>, delayed_y: Option<&ode_solvers::DVector<f32>>, dy: &mut ode_solvers::DVector<f32>) {
+        let rate_at = |y: &ode_solvers::DVector<f32>| self.kinetic_law.get_rate(&self.reactants, &|molecule| species.get(molecule).map(|id| y[*id]).unwrap_or(0f32));
+        let rate = rate_at(y);
+        let delayed_rate = delayed_y.map(rate_at).unwrap_or(0f32);
+
+        let mut changes: HashMap<usize, i64> = HashMap::new();
+        for (stochiometric_coeff, reactant) in self.reactants.iter() {
+            *changes.entry(species[reactant]).or_insert(0) -= *stochiometric_coeff as i64;
+        }
+        for (stochiometric_coeff, product) in self.products.iter() {
+            *changes.entry(species[product]).or_insert(0) += *stochiometric_coeff as i64;
+        }
+
+        for (molecule, change) in changes {
+            dy[molecule] += change as f32 * if change < 0 { rate } else { delayed_rate };
+        }
+    }
+
+    pub fn apply_jacobian(&self, species: &HashMap<Molecule, usize>, y: &ode_solvers::DVector<f32>, jacobian: &mut nalgebra::DMatrix<f32>) {
+        let gradient = self.kinetic_law.get_rate_gradient(&self.reactants, &|molecule| species.get(molecule).map(|id| y[*id]).unwrap_or(0f32));
+
+        for (molecule, derivative) in gradient {
+            let column = species[&molecule];
+
+            for (stochiometric_coeff, reactant) in self.reactants.iter() {
+                jacobian[(species[reactant], column)] -= (*stochiometric_coeff as f32) * derivative;
+            }
+
+            for (stochiometric_coeff, product) in self.products.iter() {
+                jacobian[(species[product], column)] += (*stochiometric_coeff as f32) * derivative;
+            }
         }
     }
 
@@ -63,9 +119,22 @@ impl Reaction {
         }
     }
 
+    pub fn get_reactants(&self) -> &HashSet<(u32, Molecule)> {
+        &self.reactants
+    }
+
+    pub fn get_products(&self) -> &HashSet<(u32, Molecule)> {
+        &self.products
+    }
+
+    pub fn get_kinetic_law(&self) -> &KineticLaw {
+        &self.kinetic_law
+    }
+
     pub fn get_species(&self) -> HashSet<&Molecule> {
         let reactants = self.reactants.iter().map(|(_, molecule)| molecule);
         let products = self.products.iter().map(|(_, molecule)| molecule);
-        reactants.chain(products).collect()
+        let modifiers = self.kinetic_law.get_modifiers();
+        reactants.chain(products).chain(modifiers).collect()
     }
 }
@@ -0,0 +1,537 @@
+use std::collections::{BTreeSet, HashMap};
+use crate::chemical_reactions::prelude::*;
+
+// SBML Level 3 Core import/export restricted to what `Reaction` can express:
+// mass-action kinetic laws with integer stoichiometries. Species are exported
+// as amounts (hasOnlySubstanceUnits) in a single unit-volume compartment, and
+// imported concentrations are converted to amounts using the compartment size.
+
+const SBML_NAMESPACE: &str = "http://www.sbml.org/sbml/level3/version2/core";
+const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SbmlError {
+    Xml(String),
+    InvalidDocument(String),
+    Unsupported(Vec<SbmlUnsupported>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SbmlUnsupported {
+    FunctionDefinition(String),
+    InitialAssignment(String),
+    Rule { kind: String, variable: String },
+    Constraint,
+    Event(String),
+    BoundarySpecies(String),
+    NonIntegerAmount(String),
+    UnusedSpecies(String),
+    NonIntegerStoichiometry { reaction: String, species: String },
+    KineticLaw { reaction: String, reason: String },
+}
+
+impl std::fmt::Display for SbmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SbmlError::Xml(error) => write!(f, "malformed XML: {error}"),
+            SbmlError::InvalidDocument(error) => write!(f, "invalid SBML document: {error}"),
+            SbmlError::Unsupported(constructs) => {
+                write!(f, "unsupported SBML constructs:")?;
+                for construct in constructs {
+                    write!(f, "\n - {construct}")?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for SbmlUnsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SbmlUnsupported::FunctionDefinition(id) => write!(f, "function definition '{id}'"),
+            SbmlUnsupported::InitialAssignment(symbol) => write!(f, "initial assignment to '{symbol}'"),
+            SbmlUnsupported::Rule { kind, variable } => write!(f, "{kind} for '{variable}'"),
+            SbmlUnsupported::Constraint => write!(f, "constraint"),
+            SbmlUnsupported::Event(id) => write!(f, "event '{id}'"),
+            SbmlUnsupported::BoundarySpecies(id) => write!(f, "boundary or constant species '{id}'"),
+            SbmlUnsupported::NonIntegerAmount(id) => write!(f, "non-integer initial amount of species '{id}'"),
+            SbmlUnsupported::UnusedSpecies(id) => write!(f, "species '{id}' appears in no reaction"),
+            SbmlUnsupported::NonIntegerStoichiometry { reaction, species } =>
+                write!(f, "non-integer stoichiometry of '{species}' in reaction '{reaction}'"),
+            SbmlUnsupported::KineticLaw { reaction, reason } =>
+                write!(f, "kinetic law of reaction '{reaction}': {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for SbmlError { }
+
+impl ReactionNetwork {
+    pub fn from_sbml(text: &str) -> Result<Self, SbmlError> {
+        let document = roxmltree::Document::parse(text)
+            .map_err(|err| SbmlError::Xml(err.to_string()))?;
+
+        let sbml = document.root_element();
+        if sbml.tag_name().name() != "sbml" {
+            return Err(SbmlError::InvalidDocument(format!("root element is '{}', expected 'sbml'", sbml.tag_name().name())));
+        }
+
+        if sbml.attribute("level") != Some("3") {
+            return Err(SbmlError::InvalidDocument(format!("only SBML Level 3 is supported, found level {}", sbml.attribute("level").unwrap_or("?"))));
+        }
+
+        let model = child(sbml, "model")
+            .ok_or_else(|| SbmlError::InvalidDocument("missing 'model' element".to_owned()))?;
+
+        SbmlReader::new(model)?.read()
+    }
+
+    pub fn to_sbml(&self, model_id: &str) -> Result<String, SbmlError> {
+        check_identifier(model_id)?;
+
+        let species: BTreeSet<&str> = self.reactions.iter()
+            .flat_map(|reaction| reaction.get_species())
+            .chain(self.initial_state.keys())
+            .map(|molecule| molecule.get_name())
+            .collect();
+
+        for species in species.iter() {
+            check_identifier(species)?;
+        }
+
+        let unsupported: Vec<_> = self.reactions.iter().enumerate()
+            .filter(|(_, reaction)| !reaction.get_kinetic_law().is_mass_action())
+            .map(|(id, _)| SbmlUnsupported::KineticLaw {
+                reaction: format!("r{}", id + 1),
+                reason: "only mass-action kinetic laws can be exported".to_owned(),
+            })
+            .collect();
+
+        if !unsupported.is_empty() {
+            return Err(SbmlError::Unsupported(unsupported));
+        }
+
+        let compartment = unique_identifier("compartment", &species);
+        let parameter = unique_identifier("k", &species);
+
+        let mut sbml = String::new();
+        sbml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        sbml.push_str(&format!("<sbml xmlns=\"{SBML_NAMESPACE}\" level=\"3\" version=\"2\">\n"));
+        sbml.push_str(&format!("  <model id=\"{model_id}\">\n"));
+
+        sbml.push_str("    <listOfCompartments>\n");
+        sbml.push_str(&format!("      <compartment id=\"{compartment}\" spatialDimensions=\"3\" size=\"1\" constant=\"true\"/>\n"));
+        sbml.push_str("    </listOfCompartments>\n");
+
+        if !species.is_empty() {
+            sbml.push_str("    <listOfSpecies>\n");
+            for species in species.iter() {
+                let amount = self.initial_state.get(&Molecule::new(species)).cloned().unwrap_or(0);
+                sbml.push_str(&format!(
+                    "      <species id=\"{species}\" compartment=\"{compartment}\" initialAmount=\"{amount}\" hasOnlySubstanceUnits=\"true\" boundaryCondition=\"false\" constant=\"false\"/>\n"
+                ));
+            }
+            sbml.push_str("    </listOfSpecies>\n");
+        }
+
+        if !self.reactions.is_empty() {
+            sbml.push_str("    <listOfReactions>\n");
+            for (id, reaction) in self.reactions.iter().enumerate() {
+                Self::write_reaction(&mut sbml, &format!("r{}", id + 1), &parameter, reaction);
+            }
+            sbml.push_str("    </listOfReactions>\n");
+        }
+
+        sbml.push_str("  </model>\n");
+        sbml.push_str("</sbml>\n");
+
+        Ok(sbml)
+    }
+
+    fn write_reaction(sbml: &mut String, id: &str, parameter: &str, reaction: &Reaction) {
+        let mut reactants: Vec<_> = reaction.get_reactants().iter().collect();
+        let mut products: Vec<_> = reaction.get_products().iter().collect();
+        reactants.sort_by(|a, b| a.1.get_name().cmp(b.1.get_name()));
+        products.sort_by(|a, b| a.1.get_name().cmp(b.1.get_name()));
+
+        sbml.push_str(&format!("      <reaction id=\"{id}\" reversible=\"false\">\n"));
+
+        for (list, references) in [("listOfReactants", &reactants), ("listOfProducts", &products)] {
+            if references.is_empty() {
+                continue;
+            }
+
+            sbml.push_str(&format!("        <{list}>\n"));
+            for (stoichiometry, molecule) in references.iter() {
+                sbml.push_str(&format!(
+                    "          <speciesReference species=\"{}\" stoichiometry=\"{stoichiometry}\" constant=\"true\"/>\n",
+                    molecule.get_name()
+                ));
+            }
+            sbml.push_str(&format!("        </{list}>\n"));
+        }
+
+        // mass action: k * product(species ^ stoichiometry)
+        let KineticLaw::MassAction(kinetic_constant) = reaction.get_kinetic_law() else {
+            unreachable!()
+        };
+
+        let mut factors = vec![format!("<ci> {parameter} </ci>")];
+        for (stoichiometry, molecule) in reactants.iter() {
+            let name = molecule.get_name();
+            factors.push(match stoichiometry {
+                1 => format!("<ci> {name} </ci>"),
+                n => format!("<apply><power/><ci> {name} </ci><cn type=\"integer\"> {n} </cn></apply>"),
+            });
+        }
+
+        let law = match factors.len() {
+            1 => factors.pop().unwrap(),
+            _ => format!("<apply><times/>{}</apply>", factors.concat()),
+        };
+
+        sbml.push_str("        <kineticLaw>\n");
+        sbml.push_str(&format!("          <math xmlns=\"{MATHML_NAMESPACE}\">{law}</math>\n"));
+        sbml.push_str("          <listOfLocalParameters>\n");
+        sbml.push_str(&format!("            <localParameter id=\"{parameter}\" value=\"{kinetic_constant}\"/>\n"));
+        sbml.push_str("          </listOfLocalParameters>\n");
+        sbml.push_str("        </kineticLaw>\n");
+        sbml.push_str("      </reaction>\n");
+    }
+}
+
+struct SbmlSpecies {
+    initial_amount: f64,
+    compartment_size: f64,
+    only_substance_units: bool,
+}
+
+struct SbmlReader<'a, 'input> {
+    model: roxmltree::Node<'a, 'input>,
+    compartments: HashMap<&'a str, f64>,
+    parameters: HashMap<&'a str, f64>,
+    species: HashMap<&'a str, SbmlSpecies>,
+    unsupported: Vec<SbmlUnsupported>,
+}
+
+enum Factor<'a> {
+    Number(f64),
+    Symbol(&'a str, u32),
+}
+
+impl<'a, 'input> SbmlReader<'a, 'input> {
+    fn new(model: roxmltree::Node<'a, 'input>) -> Result<Self, SbmlError> {
+        let mut compartments = HashMap::new();
+        for compartment in list(model, "listOfCompartments", "compartment") {
+            compartments.insert(required(compartment, "id")?, optional_number(compartment, "size")?.unwrap_or(1f64));
+        }
+
+        let mut parameters = HashMap::new();
+        for parameter in list(model, "listOfParameters", "parameter") {
+            // non constant parameters can only be changed by rules or events, which are reported separately
+            if let Some(value) = optional_number(parameter, "value")? {
+                parameters.insert(required(parameter, "id")?, value);
+            }
+        }
+
+        let mut species = HashMap::new();
+        let mut unsupported = Vec::new();
+        for element in list(model, "listOfSpecies", "species") {
+            let id = required(element, "id")?;
+            let compartment = required(element, "compartment")?;
+            let compartment_size = *compartments.get(compartment)
+                .ok_or_else(|| SbmlError::InvalidDocument(format!("species '{id}' refers to unknown compartment '{compartment}'")))?;
+
+            let initial_amount =
+                match (optional_number(element, "initialAmount")?, optional_number(element, "initialConcentration")?) {
+                    (Some(amount), _) => amount,
+                    (None, Some(concentration)) => concentration * compartment_size,
+                    (None, None) => 0f64,
+                };
+
+            if !(initial_amount >= 0f64 && initial_amount <= u32::MAX as f64) {
+                return Err(SbmlError::InvalidDocument(format!("species '{id}' has invalid initial amount {initial_amount}")));
+            }
+
+            if initial_amount.fract() != 0f64 {
+                unsupported.push(SbmlUnsupported::NonIntegerAmount(id.to_owned()));
+            }
+
+            if element.attribute("boundaryCondition") == Some("true") || element.attribute("constant") == Some("true") {
+                unsupported.push(SbmlUnsupported::BoundarySpecies(id.to_owned()));
+            }
+
+            let only_substance_units = element.attribute("hasOnlySubstanceUnits") == Some("true");
+            species.insert(id, SbmlSpecies { initial_amount, compartment_size, only_substance_units });
+        }
+
+        Ok(Self { model, compartments, parameters, species, unsupported })
+    }
+
+    fn read(mut self) -> Result<ReactionNetwork, SbmlError> {
+        for function in list(self.model, "listOfFunctionDefinitions", "functionDefinition") {
+            self.unsupported.push(SbmlUnsupported::FunctionDefinition(function.attribute("id").unwrap_or("?").to_owned()));
+        }
+
+        for assignment in list(self.model, "listOfInitialAssignments", "initialAssignment") {
+            self.unsupported.push(SbmlUnsupported::InitialAssignment(assignment.attribute("symbol").unwrap_or("?").to_owned()));
+        }
+
+        for rule in elements(child(self.model, "listOfRules")) {
+            self.unsupported.push(SbmlUnsupported::Rule {
+                kind: rule.tag_name().name().to_owned(),
+                variable: rule.attribute("variable").unwrap_or("-").to_owned(),
+            });
+        }
+
+        for _ in list(self.model, "listOfConstraints", "constraint") {
+            self.unsupported.push(SbmlUnsupported::Constraint);
+        }
+
+        for event in list(self.model, "listOfEvents", "event") {
+            self.unsupported.push(SbmlUnsupported::Event(event.attribute("id").unwrap_or("?").to_owned()));
+        }
+
+        let mut reactions = Vec::new();
+        for reaction in list(self.model, "listOfReactions", "reaction") {
+            if let Some(reaction) = self.read_reaction(reaction)? {
+                reactions.push(reaction);
+            }
+        }
+
+        // the simulators only track species that take part in some reaction, checked only when
+        // no reaction was skipped, whose species would otherwise all be reported as well
+        if self.unsupported.is_empty() {
+            let mut unused: Vec<_> = self.species.keys()
+                .filter(|id| !reactions.iter().any(|reaction: &Reaction| reaction.get_species().contains(&Molecule::new(id))))
+                .collect();
+            unused.sort();
+            self.unsupported.extend(unused.into_iter().map(|id| SbmlUnsupported::UnusedSpecies(id.to_string())));
+        }
+
+        if !self.unsupported.is_empty() {
+            return Err(SbmlError::Unsupported(self.unsupported));
+        }
+
+        let initial_state = self.species.iter()
+            .map(|(id, species)| (Molecule::new(id), species.initial_amount as u32))
+            .collect();
+
+        Ok(ReactionNetwork { reactions, initial_state })
+    }
+
+    fn read_reaction(&mut self, reaction: roxmltree::Node<'a, 'input>) -> Result<Option<Reaction>, SbmlError> {
+        let id = required(reaction, "id")?;
+
+        let Some(mut reactants) = self.read_references(reaction, id, "listOfReactants")? else { return Ok(None) };
+        let Some(mut products) = self.read_references(reaction, id, "listOfProducts")? else { return Ok(None) };
+
+        let unsupported_law = |reason: &str| SbmlUnsupported::KineticLaw { reaction: id.to_owned(), reason: reason.to_owned() };
+
+        let Some(law) = child(reaction, "kineticLaw") else {
+            self.unsupported.push(unsupported_law("missing kinetic law"));
+            return Ok(None);
+        };
+
+        let Some(math) = child(law, "math").and_then(|math| elements(Some(math)).next()) else {
+            self.unsupported.push(unsupported_law("missing math"));
+            return Ok(None);
+        };
+
+        let mut factors = Vec::new();
+        if let Err(reason) = collect_factors(math, 1, &mut factors) {
+            self.unsupported.push(unsupported_law(&reason));
+            return Ok(None);
+        }
+
+        let mut local_parameters = HashMap::new();
+        for parameter in list(law, "listOfLocalParameters", "localParameter") {
+            if let Some(value) = optional_number(parameter, "value")? {
+                local_parameters.insert(required(parameter, "id")?, value);
+            }
+        }
+
+        // split the product into a constant part and the species powers
+        let mut kinetic_constant = 1f64;
+        let mut powers: HashMap<&str, u32> = HashMap::new();
+        for factor in factors {
+            match factor {
+                Factor::Number(value) => kinetic_constant *= value,
+                Factor::Symbol(symbol, power) => {
+                    if let Some(value) = local_parameters.get(symbol).or_else(|| self.parameters.get(symbol)).or_else(|| self.compartments.get(symbol)) {
+                        kinetic_constant *= value.powi(power as i32);
+                    } else if let Some(species) = self.species.get(symbol) {
+                        // concentration symbols are converted to amounts
+                        if !species.only_substance_units {
+                            kinetic_constant /= species.compartment_size.powi(power as i32);
+                        }
+                        *powers.entry(symbol).or_insert(0) += power;
+                    } else {
+                        self.unsupported.push(unsupported_law(&format!("unknown symbol '{symbol}'")));
+                        return Ok(None);
+                    }
+                },
+            }
+        }
+
+        for (species, stoichiometry) in reactants.iter() {
+            if powers.get(species.get_name()) != Some(stoichiometry) {
+                self.unsupported.push(unsupported_law("not mass action with respect to the reactants"));
+                return Ok(None);
+            }
+            powers.remove(species.get_name());
+        }
+
+        // remaining species act as catalysts: consumed and produced by the same amount,
+        // on top of the products they may already be (e.g. `-> B` at rate `k*B` is `B -> 2B`)
+        for (species, power) in powers {
+            add_to_side(&mut reactants, Molecule::new(species), power);
+            add_to_side(&mut products, Molecule::new(species), power);
+        }
+
+        if !(kinetic_constant.is_finite() && kinetic_constant >= 0f64) {
+            self.unsupported.push(unsupported_law(&format!("invalid kinetic constant {kinetic_constant}")));
+            return Ok(None);
+        }
+
+        let swap = |side: Vec<(Molecule, u32)>| side.into_iter().map(|(molecule, coefficient)| (coefficient, molecule)).collect();
+        Ok(Some(Reaction::from_vec(kinetic_constant as f32, swap(reactants), swap(products))))
+    }
+
+    fn read_references(&mut self, reaction: roxmltree::Node, reaction_id: &str, list_name: &str) -> Result<Option<Vec<(Molecule, u32)>>, SbmlError> {
+        let mut side: Vec<(Molecule, u32)> = Vec::new();
+
+        for reference in list(reaction, list_name, "speciesReference") {
+            let species = required(reference, "species")?;
+            if !self.species.contains_key(species) {
+                return Err(SbmlError::InvalidDocument(format!("reaction '{reaction_id}' refers to unknown species '{species}'")));
+            }
+
+            let stoichiometry = optional_number(reference, "stoichiometry")?.unwrap_or(1f64);
+            if stoichiometry.fract() != 0f64 || stoichiometry < 0f64 {
+                self.unsupported.push(SbmlUnsupported::NonIntegerStoichiometry { reaction: reaction_id.to_owned(), species: species.to_owned() });
+                return Ok(None);
+            }
+
+            if stoichiometry == 0f64 {
+                continue;
+            }
+
+            add_to_side(&mut side, Molecule::new(species), stoichiometry as u32);
+        }
+
+        Ok(Some(side))
+    }
+}
+
+// a species appears once on each side of a reaction, its coefficients are summed
+fn add_to_side(side: &mut Vec<(Molecule, u32)>, molecule: Molecule, coefficient: u32) {
+    match side.iter_mut().find(|(other, _)| other == &molecule) {
+        Some((_, other_coefficient)) => *other_coefficient += coefficient,
+        None => side.push((molecule, coefficient)),
+    }
+}
+
+fn collect_factors<'a>(node: roxmltree::Node<'a, '_>, power: u32, factors: &mut Vec<Factor<'a>>) -> Result<(), String> {
+    match node.tag_name().name() {
+        "ci" => {
+            factors.push(Factor::Symbol(node.text().unwrap_or("").trim(), power));
+            Ok(())
+        },
+        "cn" => {
+            let value = read_cn(node)?;
+            factors.push(Factor::Number(value.powi(power as i32)));
+            Ok(())
+        },
+        "apply" => {
+            let mut operands = elements(Some(node));
+            let operator = operands.next().ok_or("empty apply")?;
+
+            match operator.tag_name().name() {
+                "times" => operands.try_for_each(|operand| collect_factors(operand, power, factors)),
+                "power" => {
+                    let (Some(base), Some(exponent), None) = (operands.next(), operands.next(), operands.next()) else {
+                        return Err("malformed power".to_owned());
+                    };
+
+                    let exponent = (exponent.tag_name().name() == "cn").then(|| read_cn(exponent)).transpose()?
+                        .filter(|exponent| exponent.fract() == 0f64 && *exponent >= 0f64)
+                        .ok_or("only non-negative integer exponents are mass action")?;
+
+                    collect_factors(base, power * exponent as u32, factors)
+                },
+                other => Err(format!("operator '{other}' is not mass action")),
+            }
+        },
+        other => Err(format!("element '{other}' is not mass action")),
+    }
+}
+
+fn read_cn(node: roxmltree::Node) -> Result<f64, String> {
+    let parts: Vec<_> = node.children()
+        .filter(|child| child.is_text())
+        .filter_map(|child| child.text())
+        .map(|text| text.trim())
+        .filter(|text| !text.is_empty())
+        .collect();
+
+    let parse = |text: &str| text.parse::<f64>().map_err(|_| format!("invalid number '{text}'"));
+
+    match (node.attribute("type").unwrap_or("real"), parts.as_slice()) {
+        ("real" | "integer", [value]) => parse(value),
+        ("e-notation", [mantissa, exponent]) => Ok(parse(mantissa)? * 10f64.powf(parse(exponent)?)),
+        ("rational", [numerator, denominator]) => Ok(parse(numerator)? / parse(denominator)?),
+        (kind, _) => Err(format!("unsupported number of type '{kind}'")),
+    }
+}
+
+fn child<'a, 'input>(node: roxmltree::Node<'a, 'input>, name: &str) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|child| child.is_element() && child.tag_name().name() == name)
+}
+
+fn elements<'a, 'input>(node: Option<roxmltree::Node<'a, 'input>>) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+    node.into_iter()
+        .flat_map(|node| node.children())
+        .filter(|child| child.is_element())
+        .filter(|child| !matches!(child.tag_name().name(), "notes" | "annotation"))
+}
+
+fn list<'a, 'input>(node: roxmltree::Node<'a, 'input>, list_name: &str, element_name: &'static str) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+    elements(child(node, list_name))
+        .filter(move |child| child.tag_name().name() == element_name)
+}
+
+fn required<'a>(node: roxmltree::Node<'a, '_>, attribute: &str) -> Result<&'a str, SbmlError> {
+    node.attribute(attribute)
+        .ok_or_else(|| SbmlError::InvalidDocument(format!("'{}' element without '{attribute}' attribute", node.tag_name().name())))
+}
+
+fn optional_number(node: roxmltree::Node, attribute: &str) -> Result<Option<f64>, SbmlError> {
+    node.attribute(attribute)
+        .map(|value| value.trim().parse::<f64>()
+            .map_err(|_| SbmlError::InvalidDocument(format!("attribute '{attribute}' of '{}' is not a number: '{value}'", node.tag_name().name())))
+        )
+        .transpose()
+}
+
+fn check_identifier(id: &str) -> Result<(), SbmlError> {
+    let mut chars = id.chars();
+    let valid = chars.next().is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(SbmlError::InvalidDocument(format!("'{id}' is not a valid SBML identifier")))
+    }
+}
+
+fn unique_identifier(base: &str, taken: &BTreeSet<&str>) -> String {
+    let mut id = base.to_owned();
+    while taken.contains(id.as_str()) {
+        id.push('_');
+    }
+
+    id
+}
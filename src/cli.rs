@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::str::FromStr;
+
+use crate::prelude::*;
+
+// Native command line runner: every model is a subcommand whose flags mirror the
+// fields of the `Params` of its page, with the defaults of the page, e.g.
+//
+//   cmcs cds-sir-model --infection_coefficient 2.5 --format json --output sir.json
+//
+// Trajectories are printed as CSV (default) or JSON, to stdout or to `--output`, the
+// chart of the page is drawn instead as PNG (to `--output` only) or SVG.
+pub struct Command {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub flags: &'static [Flag],
+    pub run: Runner,
+    // draws the chart of the page with `Arguments::render`, if the model has a page
+    pub chart: Option<Runner>,
+}
+
+pub type Runner = fn(&Arguments) -> Result<String, String>;
+
+pub struct Flag {
+    pub name: &'static str,
+    pub default: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Csv,
+    Json,
+    Png,
+    Svg,
+}
+
+pub struct Arguments {
+    values: HashMap<&'static str, String>,
+    format: OutputFormat,
+    output: Option<String>,
+}
+
+// size in pixels of the charts
+const CHART_SIZE: (u32, u32) = (800, 600);
+
+impl OutputFormat {
+    pub fn from_string(str: &str) -> Option<Self> {
+        match str {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "png" => Some(Self::Png),
+            "svg" => Some(Self::Svg),
+            _ => None,
+        }
+    }
+
+    pub fn is_chart(&self) -> bool {
+        matches!(self, Self::Png | Self::Svg)
+    }
+}
+
+impl Arguments {
+    // flags given as `--name value` or `--name=value`, dashes in names read as underscores
+    fn parse(command: &Command, arguments: &[String]) -> Result<Self, String> {
+        let mut values: HashMap<&'static str, String> = command.flags.iter()
+            .map(|flag| (flag.name, flag.default.to_owned()))
+            .collect();
+        let (mut format, mut output) = (OutputFormat::Csv, None);
+
+        let mut arguments = arguments.iter();
+        while let Some(argument) = arguments.next() {
+            let Some(flag) = argument.strip_prefix("--") else {
+                return Err(format!("unexpected argument '{argument}'"));
+            };
+
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name.replace('-', "_"), value.to_owned()),
+                None => {
+                    let value = arguments.next().ok_or(format!("missing value of flag --{flag}"))?;
+                    (flag.replace('-', "_"), value.clone())
+                },
+            };
+
+            match name.as_str() {
+                "format" => format = OutputFormat::from_string(&value).ok_or(format!("unknown format '{value}', expected csv, json, png or svg"))?,
+                "output" => output = Some(value),
+                _ => {
+                    let flag = command.flags.iter()
+                        .find(|flag| flag.name == name)
+                        .ok_or(format!("unknown flag --{name} of command {}", command.name))?;
+                    values.insert(flag.name, value);
+                },
+            }
+        }
+
+        if format == OutputFormat::Png && output.is_none() {
+            return Err("the png format needs an --output file".to_owned());
+        }
+
+        Ok(Self { values, format, output })
+    }
+
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<T, String> {
+        let value = self.values.get(name).ok_or(format!("unknown flag --{name}"))?;
+        value.parse().map_err(|_| format!("invalid value '{value}' of flag --{name}"))
+    }
+
+    pub fn get_string(&self, name: &str) -> Result<String, String> {
+        self.get(name)
+    }
+
+    // comma separated values, e.g. `--initial_state 5,1,3`
+    pub fn get_list<T: FromStr>(&self, name: &str) -> Result<Vec<T>, String> {
+        let value = self.values.get(name).ok_or(format!("unknown flag --{name}"))?;
+        value.split(',')
+            .map(|item| item.trim().parse().map_err(|_| format!("invalid value '{item}' of flag --{name}")))
+            .collect()
+    }
+
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    pub fn export<T, X, Y>(&self, simulation: Simulation<T, X, Y>, names: &[&str]) -> String
+        where T: Iterator<Item = (X, Y)> + Clone, X: Clone + Columns, Y: Clone + Columns
+    {
+        match self.format {
+            OutputFormat::Json => simulation.to_json(names),
+            _ => simulation.to_csv(names),
+        }
+    }
+
+    // Draws a page chart, written to the output file as PNG or returned as SVG.
+    pub fn render<C: Chart>(&self, chart: C) -> Result<String, String> {
+        let rendered = match (self.format, &self.output) {
+            (OutputFormat::Png, Some(path)) => render_png(chart, path, CHART_SIZE).map(|_| String::new()),
+            (OutputFormat::Svg, _) => render_svg(chart, CHART_SIZE),
+            _ => return Err("charts are drawn as png to an --output file or as svg".to_owned()),
+        };
+
+        rendered.map_err(|err| err.to_string())
+    }
+}
+
+pub fn commands() -> Vec<&'static Command> {
+    [
+        crate::continuous_dynamical_systems::commands::COMMANDS,
+        crate::discrete_dynamical_systems::commands::COMMANDS,
+        crate::stochastic_simulation::commands::COMMANDS,
+        crate::cellular_automata::commands::COMMANDS,
+        crate::discrete_event_simulation::commands::COMMANDS,
+        crate::multiset_rewriting::commands::COMMANDS,
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+// Runs the command line, returning the process exit code.
+pub fn main(arguments: &[String]) -> i32 {
+    match run(arguments) {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("error: {error}");
+            1
+        },
+    }
+}
+
+fn run(arguments: &[String]) -> Result<(), String> {
+    let commands = commands();
+
+    let Some(name) = arguments.first() else {
+        print!("{}", usage(&commands));
+        return Ok(());
+    };
+
+    if name == "help" || name == "--help" {
+        match arguments.get(1) {
+            Some(name) => print!("{}", command_usage(find_command(&commands, name)?)),
+            None => print!("{}", usage(&commands)),
+        }
+        return Ok(());
+    }
+
+    let command = find_command(&commands, name)?;
+    if arguments[1..].iter().any(|argument| argument == "--help") {
+        print!("{}", command_usage(command));
+        return Ok(());
+    }
+
+    let arguments = Arguments::parse(command, &arguments[1..])?;
+    let result =
+        if arguments.format.is_chart() {
+            let chart = command.chart.ok_or(format!("command {} has no chart, expected csv or json", command.name))?;
+            chart(&arguments)?
+        } else {
+            (command.run)(&arguments)?
+        };
+
+    // the png is already written by the chart
+    if arguments.format == OutputFormat::Png {
+        return Ok(());
+    }
+
+    match arguments.output {
+        Some(path) => std::fs::write(&path, result).map_err(|err| format!("cannot write {path}: {err}")),
+        // a closed pipe (e.g. `| head`) is not an error
+        None => match std::io::stdout().write_all(result.as_bytes()) {
+            Err(err) if err.kind() != std::io::ErrorKind::BrokenPipe => Err(format!("cannot write the output: {err}")),
+            _ => Ok(()),
+        },
+    }
+}
+
+fn find_command<'a>(commands: &[&'a Command], name: &str) -> Result<&'a Command, String> {
+    commands.iter()
+        .find(|command| command.name == name)
+        .cloned()
+        .ok_or(format!("unknown command '{name}', run with 'help' for the list of commands"))
+}
+
+fn usage(commands: &[&Command]) -> String {
+    let width = commands.iter().map(|command| command.name.len()).max().unwrap_or(0);
+    let list: String = commands.iter()
+        .map(|command| format!("  {:width$}  {}\n", command.name, command.description))
+        .collect();
+
+    format!("usage: cmcs <command> [--flag value]... [--format csv|json|png|svg] [--output file]\n       cmcs help <command>\n\ncommands:\n{list}")
+}
+
+fn command_usage(command: &Command) -> String {
+    let width = command.flags.iter().map(|flag| flag.name.len()).max().unwrap_or(0);
+    let flags: String = command.flags.iter()
+        .map(|flag| format!("  --{:width$}  {} (default: {})\n", flag.name, flag.description, flag.default))
+        .collect();
+
+    format!("{}: {}\n\nflags:\n{flags}  --format csv|json|png|svg, --output file\n", command.name, command.description)
+}
@@ -0,0 +1,454 @@
+use nalgebra::{Dim, DefaultAllocator, OVector, allocator::Allocator};
+use ode_solvers::System;
+
+use crate::prelude::*;
+use crate::cli::{Arguments, Command, Flag};
+use super::prelude::*;
+use super::{ReactionDiffusion, ReactionDiffusionModel, TimeStepping, ConcentrationLattice, BoundaryFixed, BoundaryPeriodic};
+use super::pages;
+
+pub(crate) const COMMANDS: &[Command] = &[
+    Command {
+        name: "cds-linear-birth-model",
+        description: "continuous linear birth model",
+        flags: &[
+            Flag { name: "max_time", default: "10", description: "simulated time" },
+            Flag { name: "initial_population", default: "1", description: "initial population" },
+            Flag { name: "offsprings_per_individual", default: "1", description: "offsprings per individual per period" },
+            Flag { name: "reproduction_period", default: "1", description: "reproduction period" },
+        ],
+        run: linear_birth_model,
+        chart: Some(linear_birth_model_chart),
+    },
+    Command {
+        name: "cds-radioactive-decay",
+        description: "radioactive decay",
+        flags: &[
+            Flag { name: "max_time", default: "20", description: "simulated time" },
+            Flag { name: "initial_population", default: "100", description: "initial amount of atoms" },
+            Flag { name: "decay_rate", default: "0.5", description: "decay rate" },
+        ],
+        run: radioactive_decay,
+        chart: Some(radioactive_decay_chart),
+    },
+    Command {
+        name: "cds-logistic-equation",
+        description: "continuous logistic equation",
+        flags: &[
+            Flag { name: "max_time", default: "20", description: "simulated time" },
+            Flag { name: "initial_population", default: "50", description: "initial population" },
+            Flag { name: "birth_rate", default: "0.5", description: "birth rate" },
+            Flag { name: "carrying_capacity", default: "100", description: "carrying capacity" },
+        ],
+        run: logistic_equation,
+        chart: Some(logistic_equation_chart),
+    },
+    Command {
+        name: "cds-male-female-fish",
+        description: "fish population with males and females",
+        flags: &[
+            Flag { name: "solver", default: "rk4", description: "ODE solver: dop853, dopri5, rk4 or ros23" },
+            Flag { name: "max_time", default: "50", description: "simulated time" },
+            Flag { name: "initial_female_pop", default: "16", description: "initial female population" },
+            Flag { name: "initial_male_pop", default: "23", description: "initial male population" },
+            Flag { name: "birth_rate", default: "1.3", description: "birth rate" },
+            Flag { name: "carrying_capacity", default: "200", description: "carrying capacity" },
+            Flag { name: "male_death_rate", default: "0.5", description: "death rate of the males" },
+        ],
+        run: male_female_fish,
+        chart: Some(male_female_fish_chart),
+    },
+    Command {
+        name: "cds-lotka-volterra",
+        description: "Lotka-Volterra predator-prey model",
+        flags: &[
+            Flag { name: "solver", default: "rk4", description: "ODE solver: dop853, dopri5, rk4 or ros23" },
+            Flag { name: "max_time", default: "10", description: "simulated time" },
+            Flag { name: "initial_prey_pop", default: "800", description: "initial prey population" },
+            Flag { name: "initial_predator_pop", default: "600", description: "initial predator population" },
+            Flag { name: "prey_birth_rate", default: "10", description: "birth rate of the preys" },
+            Flag { name: "predator_death_rate", default: "10", description: "death rate of the predators" },
+            Flag { name: "hunting_meetings", default: "0.01", description: "rate of the hunting meetings" },
+            Flag { name: "hunt_offsprings", default: "1", description: "predator offsprings per hunt" },
+            Flag { name: "graph", default: "normal", description: "chart of the png and svg formats: normal or phase or continuation" },
+        ],
+        run: lotka_volterra,
+        chart: Some(lotka_volterra_chart),
+    },
+    Command {
+        name: "cds-sir-model",
+        description: "SIR epidemic model",
+        flags: &[
+            Flag { name: "solver", default: "rk4", description: "ODE solver: dop853, dopri5, rk4 or ros23" },
+            Flag { name: "max_time", default: "10", description: "simulated time" },
+            Flag { name: "initial_susceptible_pop", default: "0.99", description: "initial susceptible fraction" },
+            Flag { name: "initial_infected_pop", default: "0.01", description: "initial infected fraction" },
+            Flag { name: "initial_recovered_pop", default: "0", description: "initial recovered fraction" },
+            Flag { name: "infection_coefficient", default: "3", description: "infection coefficient" },
+            Flag { name: "recovery_coefficient", default: "1", description: "recovery coefficient" },
+        ],
+        run: sir_model,
+        chart: Some(sir_model_chart),
+    },
+    Command {
+        name: "cds-sir-birth-death",
+        description: "SIR epidemic model with births and deaths",
+        flags: &[
+            Flag { name: "solver", default: "rk4", description: "ODE solver: dop853, dopri5, rk4 or ros23" },
+            Flag { name: "max_time", default: "10", description: "simulated time" },
+            Flag { name: "initial_susceptible_pop", default: "0.99", description: "initial susceptible fraction" },
+            Flag { name: "initial_infected_pop", default: "0.01", description: "initial infected fraction" },
+            Flag { name: "initial_recovered_pop", default: "0", description: "initial recovered fraction" },
+            Flag { name: "infection_coefficient", default: "6", description: "infection coefficient" },
+            Flag { name: "recovery_coefficient", default: "2", description: "recovery coefficient" },
+            Flag { name: "birth_rate", default: "2", description: "birth and death rate" },
+            Flag { name: "graph", default: "normal", description: "chart of the png and svg formats: normal or phase" },
+        ],
+        run: sir_birth_death,
+        chart: Some(sir_birth_death_chart),
+    },
+    Command {
+        name: "cds-sir-vaccination",
+        description: "SIR epidemic model with births, deaths and vaccination",
+        flags: &[
+            Flag { name: "solver", default: "rk4", description: "ODE solver: dop853, dopri5, rk4 or ros23" },
+            Flag { name: "max_time", default: "10", description: "simulated time" },
+            Flag { name: "initial_susceptible_pop", default: "0.7", description: "initial susceptible fraction" },
+            Flag { name: "initial_infected_pop", default: "0.3", description: "initial infected fraction" },
+            Flag { name: "initial_recovered_pop", default: "0", description: "initial recovered fraction" },
+            Flag { name: "infection_coefficient", default: "6", description: "infection coefficient" },
+            Flag { name: "recovery_coefficient", default: "2", description: "recovery coefficient" },
+            Flag { name: "birth_rate", default: "2", description: "birth and death rate" },
+            Flag { name: "vaccination_coefficient", default: "0.35", description: "vaccination coefficient" },
+            Flag { name: "graph", default: "normal", description: "chart of the png and svg formats: normal or phase" },
+        ],
+        run: sir_vaccination,
+        chart: Some(sir_vaccination_chart),
+    },
+    Command {
+        name: "cds-sir-delay",
+        description: "SIR epidemic model with an incubation period",
+        flags: &[
+            Flag { name: "max_time", default: "20", description: "simulated time" },
+            Flag { name: "initial_susceptible_pop", default: "0.99", description: "initial susceptible fraction" },
+            Flag { name: "initial_infected_pop", default: "0.01", description: "initial infected fraction" },
+            Flag { name: "initial_recovered_pop", default: "0", description: "initial recovered fraction" },
+            Flag { name: "infection_coefficient", default: "3", description: "infection coefficient" },
+            Flag { name: "recovery_coefficient", default: "1", description: "recovery coefficient" },
+            Flag { name: "incubation_delay", default: "1", description: "incubation period" },
+        ],
+        run: sir_delay,
+        chart: Some(sir_delay_chart),
+    },
+    Command {
+        name: "cds-reaction-diffusion",
+        description: "reaction-diffusion Turing patterns on a grid",
+        flags: &[
+            Flag { name: "model", default: "gray_scott", description: "reaction: gray_scott, brusselator or fitzhugh_nagumo" },
+            Flag { name: "parameters", default: "0.035,0.065", description: "reaction parameters: feed,kill or a,b or epsilon,a0,a1" },
+            Flag { name: "stepping", default: "explicit", description: "time stepping: explicit or adi" },
+            Flag { name: "boundary", default: "periodic", description: "boundary condition: periodic or fixed" },
+            Flag { name: "size", default: "64", description: "side of the grid" },
+            Flag { name: "max_time", default: "2000", description: "simulated time" },
+            Flag { name: "time_step", default: "1", description: "integration time step" },
+            Flag { name: "sample_interval", default: "100", description: "time between two samples" },
+            Flag { name: "diffusion_u", default: "0.16", description: "diffusion coefficient of u" },
+            Flag { name: "diffusion_v", default: "0.08", description: "diffusion coefficient of v" },
+            Flag { name: "seed", default: "0", description: "seed of the initial perturbations" },
+        ],
+        run: reaction_diffusion,
+        chart: Some(reaction_diffusion_chart),
+    },
+];
+
+// output step of the integrated trajectories, the one of the pages
+const TIME_STEP: f32 = 0.01;
+
+fn linear_birth_model(arguments: &Arguments) -> Result<String, String> {
+    let birth_rate = arguments.get::<f32>("offsprings_per_individual")? / arguments.get::<f32>("reproduction_period")?;
+    let model = LinearBirthModel::new(arguments.get("initial_population")?, birth_rate);
+
+    Ok(arguments.export(Simulation::new(model).time_limit(arguments.get("max_time")?), &["t", "N"]))
+}
+
+fn linear_birth_model_chart(arguments: &Arguments) -> Result<String, String> {
+    arguments.render(pages::linear_birth_model::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .initial_population(arguments.get("initial_population")?)
+        .offsprings_per_individual(arguments.get("offsprings_per_individual")?)
+        .reproduction_period(arguments.get("reproduction_period")?))
+}
+
+fn radioactive_decay(arguments: &Arguments) -> Result<String, String> {
+    let model = LinearBirthModel::new(arguments.get("initial_population")?, - arguments.get::<f32>("decay_rate")?);
+
+    Ok(arguments.export(Simulation::new(model).time_limit(arguments.get("max_time")?), &["t", "N"]))
+}
+
+fn radioactive_decay_chart(arguments: &Arguments) -> Result<String, String> {
+    arguments.render(pages::radioactive_decay::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .initial_population(arguments.get("initial_population")?)
+        .decay_rate(arguments.get("decay_rate")?))
+}
+
+fn logistic_equation(arguments: &Arguments) -> Result<String, String> {
+    let model = LogisticEquation::new(
+        arguments.get("initial_population")?,
+        arguments.get("birth_rate")?,
+        arguments.get("carrying_capacity")?,
+    );
+
+    Ok(arguments.export(Simulation::new(model).time_limit(arguments.get("max_time")?), &["t", "N"]))
+}
+
+fn logistic_equation_chart(arguments: &Arguments) -> Result<String, String> {
+    arguments.render(pages::logistic_equation::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .initial_population(arguments.get("initial_population")?)
+        .birth_rate(arguments.get("birth_rate")?)
+        .carrying_capacity(arguments.get("carrying_capacity")?))
+}
+
+fn male_female_fish(arguments: &Arguments) -> Result<String, String> {
+    let model = MaleFemaleFishPopulation::new(
+        (arguments.get("initial_female_pop")?, arguments.get("initial_male_pop")?),
+        arguments.get("birth_rate")?,
+        arguments.get("carrying_capacity")?,
+        arguments.get("male_death_rate")?,
+        arguments.get("max_time")?,
+    );
+
+    let trajectory = integrate(model.ode, model.initial_state, model.max_time, solver(arguments)?)?
+        .map(|(time, state)| (time, (state[0], state[1])));
+
+    Ok(arguments.export(Simulation::new(trajectory), &["t", "female", "male"]))
+}
+
+fn male_female_fish_chart(arguments: &Arguments) -> Result<String, String> {
+    arguments.render(pages::male_female_fish_population::Params::builder()
+        .solver(arguments.get_string("solver")?)
+        .max_time(arguments.get("max_time")?)
+        .initial_female_population(arguments.get("initial_female_pop")?)
+        .initial_male_population(arguments.get("initial_male_pop")?)
+        .birth_rate(arguments.get("birth_rate")?)
+        .carrying_capacity(arguments.get("carrying_capacity")?)
+        .male_death_rate(arguments.get("male_death_rate")?))
+}
+
+fn lotka_volterra(arguments: &Arguments) -> Result<String, String> {
+    let model = LotkaVolterra::new(
+        (arguments.get("initial_prey_pop")?, arguments.get("initial_predator_pop")?),
+        arguments.get("prey_birth_rate")?,
+        arguments.get("predator_death_rate")?,
+        arguments.get("hunting_meetings")?,
+        arguments.get("hunt_offsprings")?,
+        arguments.get("max_time")?,
+    );
+
+    let trajectory = integrate(model.ode, model.initial_state, model.max_time, solver(arguments)?)?
+        .map(|(time, state)| (time, (state[0], state[1])));
+
+    Ok(arguments.export(Simulation::new(trajectory), &["t", "prey", "predator"]))
+}
+
+fn lotka_volterra_chart(arguments: &Arguments) -> Result<String, String> {
+    let params = pages::lotka_volterra::Params::builder()
+        .solver(arguments.get_string("solver")?)
+        .max_time(arguments.get("max_time")?)
+        .initial_prey_population(arguments.get("initial_prey_pop")?)
+        .initial_predator_population(arguments.get("initial_predator_pop")?)
+        .prey_birth_rate(arguments.get("prey_birth_rate")?)
+        .predator_death_rate(arguments.get("predator_death_rate")?)
+        .hunting_meetings(arguments.get("hunting_meetings")?)
+        .hunt_offsprings(arguments.get("hunt_offsprings")?)
+        .show_steady_states(true);
+
+    arguments.render((arguments.get_string("graph")?.as_str(), params))
+}
+
+fn sir_model(arguments: &Arguments) -> Result<String, String> {
+    sir(arguments, 0f32, 0f32)
+}
+
+fn sir_model_chart(arguments: &Arguments) -> Result<String, String> {
+    arguments.render(pages::sir_model::Params::builder()
+        .solver(arguments.get_string("solver")?)
+        .max_time(arguments.get("max_time")?)
+        .initial_susceptible_population(arguments.get("initial_susceptible_pop")?)
+        .initial_infected_population(arguments.get("initial_infected_pop")?)
+        .initial_recovered_population(arguments.get("initial_recovered_pop")?)
+        .infection_coefficient(arguments.get("infection_coefficient")?)
+        .recovery_coefficient(arguments.get("recovery_coefficient")?))
+}
+
+fn sir_birth_death(arguments: &Arguments) -> Result<String, String> {
+    sir(arguments, arguments.get("birth_rate")?, 0f32)
+}
+
+fn sir_birth_death_chart(arguments: &Arguments) -> Result<String, String> {
+    let params = pages::sir_model_birth_deaths::Params::builder()
+        .solver(arguments.get_string("solver")?)
+        .max_time(arguments.get("max_time")?)
+        .initial_susceptible_population(arguments.get("initial_susceptible_pop")?)
+        .initial_infected_population(arguments.get("initial_infected_pop")?)
+        .initial_recovered_population(arguments.get("initial_recovered_pop")?)
+        .infection_coefficient(arguments.get("infection_coefficient")?)
+        .recovery_coefficient(arguments.get("recovery_coefficient")?)
+        .birth_rate(arguments.get("birth_rate")?)
+        .show_steady_states(true);
+
+    arguments.render((arguments.get_string("graph")?.as_str(), params))
+}
+
+fn sir_vaccination(arguments: &Arguments) -> Result<String, String> {
+    sir(arguments, arguments.get("birth_rate")?, arguments.get("vaccination_coefficient")?)
+}
+
+fn sir_vaccination_chart(arguments: &Arguments) -> Result<String, String> {
+    let params = pages::sir_model_vaccination::Params::builder()
+        .solver(arguments.get_string("solver")?)
+        .max_time(arguments.get("max_time")?)
+        .initial_susceptible_population(arguments.get("initial_susceptible_pop")?)
+        .initial_infected_population(arguments.get("initial_infected_pop")?)
+        .initial_recovered_population(arguments.get("initial_recovered_pop")?)
+        .infection_coefficient(arguments.get("infection_coefficient")?)
+        .recovery_coefficient(arguments.get("recovery_coefficient")?)
+        .birth_rate(arguments.get("birth_rate")?)
+        .vaccination_coefficient(arguments.get("vaccination_coefficient")?);
+
+    arguments.render((arguments.get_string("graph")?.as_str(), params))
+}
+
+fn sir(arguments: &Arguments, birth_rate: f32, vaccination_rate: f32) -> Result<String, String> {
+    let model = SIRModel::new(
+        (arguments.get("initial_susceptible_pop")?, arguments.get("initial_infected_pop")?, arguments.get("initial_recovered_pop")?),
+        arguments.get("infection_coefficient")?,
+        arguments.get("recovery_coefficient")?,
+        birth_rate,
+        vaccination_rate,
+        arguments.get("max_time")?,
+    );
+
+    let trajectory = integrate(model.ode, model.initial_state, model.max_time, solver(arguments)?)?
+        .map(|(time, state)| (time, (state[0], state[1], state[2])));
+
+    Ok(arguments.export(Simulation::new(trajectory), &["t", "S", "I", "R"]))
+}
+
+fn sir_delay(arguments: &Arguments) -> Result<String, String> {
+    let model = DelayedSIRModel::new(
+        (arguments.get("initial_susceptible_pop")?, arguments.get("initial_infected_pop")?, arguments.get("initial_recovered_pop")?),
+        arguments.get("infection_coefficient")?,
+        arguments.get("recovery_coefficient")?,
+        arguments.get("incubation_delay")?,
+        arguments.get("max_time")?,
+    );
+
+    let history = model.history();
+    let mut stepper = DelayRk4::new(model.ode, 0f32, model.max_time, TIME_STEP, history);
+    stepper.integrate().map_err(|err| err.to_string())?;
+
+    let (times, states) = stepper.results().get();
+    let trajectory = times.iter().zip(states.iter())
+        .map(|(time, state)| (*time, (state[0], state[1], state[2])));
+
+    Ok(arguments.export(Simulation::new(trajectory), &["t", "S", "I", "R"]))
+}
+
+fn sir_delay_chart(arguments: &Arguments) -> Result<String, String> {
+    arguments.render(pages::sir_model_delay::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .initial_susceptible_population(arguments.get("initial_susceptible_pop")?)
+        .initial_infected_population(arguments.get("initial_infected_pop")?)
+        .initial_recovered_population(arguments.get("initial_recovered_pop")?)
+        .infection_coefficient(arguments.get("infection_coefficient")?)
+        .recovery_coefficient(arguments.get("recovery_coefficient")?)
+        .incubation_delay(arguments.get("incubation_delay")?))
+}
+
+fn solver(arguments: &Arguments) -> Result<ODESolver, String> {
+    let solver = arguments.get_string("solver")?;
+    ODESolver::from_string(solver.clone()).ok_or(format!("unknown solver '{solver}'"))
+}
+
+// states of the ODE every TIME_STEP, up to max_time
+fn integrate<D: Dim, F>(ode: F, initial_state: OVector<f32, D>, max_time: f32, solver: ODESolver) -> Result<impl Iterator<Item = (f32, OVector<f32, D>)> + Clone, String>
+    where F: System<f32, OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+{
+    let steps = (max_time / TIME_STEP).round().max(0f32) as usize;
+    let times: Vec<f32> = (0..=steps).map(|step| step as f32 * TIME_STEP).collect();
+    let states = solve_at(ode, initial_state, &times, solver)?;
+
+    Ok(times.into_iter().zip(states))
+}
+
+// mean concentrations and standard deviation of v, the amplitude of the pattern
+fn reaction_diffusion(arguments: &Arguments) -> Result<String, String> {
+    let parameters: Vec<f32> = arguments.get_list("parameters")?;
+    let parameter = |index: usize| parameters.get(index).cloned().ok_or(format!("missing reaction parameter {}", index + 1));
+    let model = match arguments.get_string("model")?.as_str() {
+        "gray_scott" => ReactionDiffusionModel::GrayScott { feed: parameter(0)?, kill: parameter(1)? },
+        "brusselator" => ReactionDiffusionModel::Brusselator { a: parameter(0)?, b: parameter(1)? },
+        "fitzhugh_nagumo" => ReactionDiffusionModel::FitzHughNagumo { epsilon: parameter(0)?, a0: parameter(1)?, a1: parameter(2)? },
+        model => return Err(format!("unknown model '{model}'")),
+    };
+
+    let stepping = arguments.get_string("stepping")?;
+    let stepping = TimeStepping::from_string(stepping.clone()).ok_or(format!("unknown time stepping '{stepping}'"))?;
+
+    let size = arguments.get("size")?;
+    let initial_state = model.initial_state((size, size), arguments.get("seed")?);
+    let diffusion = [arguments.get("diffusion_u")?, arguments.get("diffusion_v")?];
+    let (time_step, sample_interval): (f32, f32) = (arguments.get("time_step")?, arguments.get("sample_interval")?);
+    let samples = (arguments.get::<f32>("max_time")? / sample_interval).round().max(0f32) as usize + 1;
+
+    let statistics = |(time, lattice): (f32, ConcentrationLattice)| {
+        let cells = lattice.cells.len() as f32;
+        let mean = |species: usize| lattice.cells.iter().map(|cell| cell[species]).sum::<f32>() / cells;
+        let (mean_u, mean_v) = (mean(0), mean(1));
+        let deviation = (lattice.cells.iter().map(|cell| (cell[1] - mean_v).powi(2)).sum::<f32>() / cells).sqrt();
+
+        (time, (mean_u, mean_v, deviation))
+    };
+
+    let trajectory: Vec<_> = match arguments.get_string("boundary")?.as_str() {
+        "periodic" => ReactionDiffusion::<BoundaryPeriodic>::new(model, initial_state, diffusion, time_step, 1f32, stepping, sample_interval)?
+            .take(samples).map(statistics).collect(),
+        "fixed" => ReactionDiffusion::<BoundaryFixed>::new(model, initial_state, diffusion, time_step, 1f32, stepping, sample_interval)?
+            .take(samples).map(statistics).collect(),
+        boundary => return Err(format!("unknown boundary '{boundary}'")),
+    };
+
+    Ok(arguments.export(Simulation::new(trajectory.into_iter()), &["t", "u", "v", "v_deviation"]))
+}
+
+// the pattern of v at max_time
+fn reaction_diffusion_chart(arguments: &Arguments) -> Result<String, String> {
+    let parameters: Vec<f32> = arguments.get_list("parameters")?;
+    let parameter = |index: usize| parameters.get(index).cloned().ok_or(format!("missing reaction parameter {}", index + 1));
+    let model = arguments.get_string("model")?;
+    let params = pages::reaction_diffusion::Params::builder().model(model.clone());
+    let params = match model.as_str() {
+        "gray_scott" => params.feed(parameter(0)?).kill(parameter(1)?),
+        "brusselator" => params.a(parameter(0)?).b(parameter(1)?),
+        "fitzhugh_nagumo" => params.epsilon(parameter(0)?).a0(parameter(1)?).a1(parameter(2)?),
+        model => return Err(format!("unknown model '{model}'")),
+    };
+
+    let fixed_boundary = match arguments.get_string("boundary")?.as_str() {
+        "periodic" => false,
+        "fixed" => true,
+        boundary => return Err(format!("unknown boundary '{boundary}'")),
+    };
+
+    arguments.render(params
+        .size(arguments.get("size")?)
+        .max_time(arguments.get("max_time")?)
+        .fixed_boundary(fixed_boundary)
+        .stepping(arguments.get_string("stepping")?)
+        .time_step(arguments.get("time_step")?)
+        .diffusion_u(arguments.get("diffusion_u")?)
+        .diffusion_v(arguments.get("diffusion_v")?)
+        .seed(arguments.get("seed")?))
+}
@@ -0,0 +1,415 @@
+use nalgebra::{allocator::Allocator, Complex, DefaultAllocator, Dim, DMatrix, DVector, OVector, U1};
+use crate::continuous_dynamical_systems::{Jacobian, Stability};
+use super::steady_state::eigenvalues;
+
+// Pseudo-arclength continuation of the equilibria of a system depending on one
+// parameter p: the curve F(y, p) = 0 is followed in (y, p) space, so that branches
+// can turn back at folds. Every step predicts along the tangent of the curve and
+// corrects with Newton iteration on F(y, p) = 0 plus the arclength condition.
+//
+// Each point is classified by the eigenvalues of the Jacobian F_y, and the changes
+// along the branch are reported as special points:
+// - fold: the parameter component of the tangent changes sign;
+// - Hopf: a pair of complex eigenvalues crosses the imaginary axis;
+// - branch point: a real eigenvalue crosses zero without a fold (e.g. transcritical).
+#[derive(Clone)]
+pub struct Continuation {
+    parameter_range: (f32, f32),
+    step_size: f32,
+    max_step_size: f32,
+    max_points: usize,
+    tolerance: f32,
+    max_corrector_iterations: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Branch {
+    pub points: Vec<BranchPoint>,
+    pub special_points: Vec<SpecialPoint>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BranchPoint {
+    pub parameter: f32,
+    pub state: Vec<f32>,
+    pub eigenvalues: Vec<Complex<f32>>,
+    pub stability: Stability,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SpecialPointKind {
+    Fold,
+    Hopf,
+    BranchPoint,
+}
+
+// special point located between the branch points `index - 1` and `index`, with
+// parameter and state interpolated where the test function vanishes
+#[derive(Clone, Debug)]
+pub struct SpecialPoint {
+    pub kind: SpecialPointKind,
+    pub index: usize,
+    pub parameter: f32,
+    pub state: Vec<f32>,
+}
+
+// solution of the extended system (y, p) with the test functions used for detection
+struct ContinuationStep {
+    solution: DVector<f32>,
+    tangent: DVector<f32>,
+    eigenvalues: Vec<Complex<f32>>,
+}
+
+impl Continuation {
+    // special points are located within this fraction of the initial step size
+    const SPECIAL_POINT_RESOLUTION: f32 = 1.0e-2;
+
+    pub fn new(parameter_range: (f32, f32), step_size: f32) -> Self {
+        Self {
+            parameter_range,
+            step_size,
+            max_step_size: step_size * 100f32,
+            max_points: 1000,
+            tolerance: 1.0e-4,
+            max_corrector_iterations: 10,
+        }
+    }
+
+    pub fn max_step_size(mut self, max_step_size: f32) -> Self {
+        self.max_step_size = max_step_size;
+        self
+    }
+
+    // maximum number of points in each direction
+    pub fn max_points(mut self, max_points: usize) -> Self {
+        self.max_points = max_points;
+        self
+    }
+
+    pub fn tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    // Follows the branch through the equilibrium closest to `initial_state` at
+    // `initial_parameter`, in both directions until the ends of the parameter range.
+    // `system_at` builds the system for a given value of the parameter.
+    pub fn run<D: Dim, F, S>(&self, system_at: F, initial_state: OVector<f32, D>, initial_parameter: f32) -> Result<Branch, String>
+        where F: Fn(f32) -> S, S: Jacobian<OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+    {
+        let extended = ExtendedSystem { system_at: &system_at, dim: initial_state.shape_generic().0 };
+
+        let state = self.correct_state(&extended, &initial_state, initial_parameter)
+            .ok_or("The initial state is not close to an equilibrium")?;
+
+        let n = state.len();
+        let start = DVector::from_iterator(n + 1, state.iter().cloned().chain(std::iter::once(initial_parameter)));
+        let towards_increasing = DVector::from_fn(n + 1, |i, _| if i == n { 1f32 } else { 0f32 });
+
+        let forward = self.follow(&extended, start.clone(), towards_increasing.clone());
+        let backward = self.follow(&extended, start, -towards_increasing);
+
+        // the backward half is reversed, so the whole branch goes towards increasing parameters
+        let steps: Vec<ContinuationStep> = backward.into_iter().skip(1).rev()
+            .map(|step| ContinuationStep { tangent: -step.tangent, ..step })
+            .chain(forward)
+            .collect();
+
+        Ok(self.make_branch(steps))
+    }
+
+    // Branches through several equilibria at the same parameter value (e.g. the ones
+    // found by `SteadyStateFinder`), skipping those already on a computed branch.
+    pub fn run_all<D: Dim, F, S>(&self, system_at: F, initial_states: &[OVector<f32, D>], initial_parameter: f32) -> Vec<Branch>
+        where F: Fn(f32) -> S, S: Jacobian<OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+    {
+        let mut branches: Vec<Branch> = Vec::new();
+
+        for initial_state in initial_states {
+            let scale = self.tolerance.sqrt() * (1f32 + initial_state.amax());
+            let on_branch = branches.iter()
+                .flat_map(|branch| branch.points.iter())
+                .any(|point| (point.parameter - initial_parameter).abs() <= scale
+                    && point.state.iter().zip(initial_state.iter()).all(|(a, b)| (a - b).abs() <= scale));
+
+            if on_branch {
+                continue;
+            }
+
+            if let Ok(branch) = self.run(&system_at, initial_state.clone(), initial_parameter) {
+                branches.push(branch);
+            }
+        }
+
+        branches
+    }
+
+    fn follow<D: Dim, F, S>(&self, extended: &ExtendedSystem<'_, D, F>, start: DVector<f32>, direction: DVector<f32>) -> Vec<ContinuationStep>
+        where F: Fn(f32) -> S, S: Jacobian<OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+    {
+        let min_step_size = self.step_size * 1.0e-3;
+        let mut step_size = self.step_size;
+
+        let Some(first) = self.make_step(extended, start, &direction) else {
+            return Vec::new();
+        };
+        let mut steps = vec![first];
+
+        while steps.len() < self.max_points {
+            let last = steps.last().unwrap();
+            let predicted = &last.solution + &last.tangent * step_size;
+
+            let Some((solution, iterations)) = self.correct(extended, &predicted, &last.tangent) else {
+                step_size /= 2f32;
+                if step_size < min_step_size {
+                    break;
+                }
+
+                continue;
+            };
+
+            // shorten the last step to end on the boundary of the parameter range
+            let (last_parameter, parameter) = (last.solution[last.solution.len() - 1], solution[solution.len() - 1]);
+            if parameter < self.parameter_range.0 || parameter > self.parameter_range.1 {
+                let boundary = parameter.clamp(self.parameter_range.0, self.parameter_range.1);
+                step_size *= (boundary - last_parameter) / (parameter - last_parameter);
+                if step_size.is_nan() || step_size < min_step_size {
+                    break;
+                }
+
+                continue;
+            }
+
+            let Some(step) = self.make_step(extended, solution, &last.tangent) else {
+                break;
+            };
+
+            // bisect the step length until special points are located with the required resolution
+            let crossing = last.test_functions(self.tolerance).iter().zip(step.test_functions(self.tolerance))
+                .any(|((_, before), (_, after))| matches!((before, after), (Some(before), Some(after)) if before * after < 0f32));
+            if crossing && step_size > self.step_size * Self::SPECIAL_POINT_RESOLUTION {
+                step_size /= 2f32;
+                continue;
+            }
+
+            steps.push(step);
+
+            if iterations <= 3 {
+                step_size = f32::min(step_size * 1.5f32, self.max_step_size);
+            }
+        }
+
+        steps
+    }
+
+    fn make_step<D: Dim, F, S>(&self, extended: &ExtendedSystem<'_, D, F>, solution: DVector<f32>, previous_tangent: &DVector<f32>) -> Option<ContinuationStep>
+        where F: Fn(f32) -> S, S: Jacobian<OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+    {
+        let n = solution.len() - 1;
+        let (_, jacobian) = extended.evaluate(&solution);
+
+        // [F_y F_p; t_prev^T] t = e_{n+1}, keeps the orientation of the previous tangent
+        let mut matrix = jacobian.clone().insert_row(n, 0f32);
+        matrix.row_mut(n).copy_from(&previous_tangent.transpose());
+        let rhs = DVector::from_fn(n + 1, |i, _| if i == n { 1f32 } else { 0f32 });
+        let tangent = solve_linear(matrix, rhs)?.normalize();
+
+        let state_jacobian = jacobian.columns(0, n).into_owned();
+        Some(ContinuationStep {
+            solution,
+            tangent,
+            eigenvalues: eigenvalues(state_jacobian),
+        })
+    }
+
+    // Newton iteration on F(y, p) = 0 with the equilibrium constrained on the hyperplane
+    // orthogonal to the tangent through the predicted point
+    fn correct<D: Dim, F, S>(&self, extended: &ExtendedSystem<'_, D, F>, predicted: &DVector<f32>, tangent: &DVector<f32>) -> Option<(DVector<f32>, u32)>
+        where F: Fn(f32) -> S, S: Jacobian<OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+    {
+        let n = predicted.len() - 1;
+        let mut solution = predicted.clone();
+
+        for iteration in 1..=self.max_corrector_iterations {
+            let (f, jacobian) = extended.evaluate(&solution);
+
+            let mut matrix = jacobian.insert_row(n, 0f32);
+            matrix.row_mut(n).copy_from(&tangent.transpose());
+            let rhs = -f.push(tangent.dot(&(&solution - predicted)));
+
+            let delta = solve_linear(matrix, rhs)?;
+            solution += &delta;
+
+            if !solution.iter().all(|value| value.is_finite()) {
+                return None;
+            }
+
+            if delta.amax() <= self.tolerance * (1f32 + solution.amax()) {
+                return Some((solution, iteration));
+            }
+        }
+
+        None
+    }
+
+    fn correct_state<D: Dim, F, S>(&self, extended: &ExtendedSystem<'_, D, F>, state: &OVector<f32, D>, parameter: f32) -> Option<DVector<f32>>
+        where F: Fn(f32) -> S, S: Jacobian<OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+    {
+        let n = state.len();
+        let mut solution = DVector::from_iterator(n + 1, state.iter().cloned().chain(std::iter::once(parameter)));
+
+        for _ in 0..self.max_corrector_iterations * 5 {
+            let (f, jacobian) = extended.evaluate(&solution);
+            let delta = solve_linear(jacobian.columns(0, n).into_owned(), -f)?;
+            let mut state = solution.rows_mut(0, n);
+            state += &delta;
+
+            if delta.amax() <= self.tolerance * (1f32 + solution.amax()) {
+                return Some(solution.rows(0, n).into_owned());
+            }
+        }
+
+        None
+    }
+
+    fn make_branch(&self, steps: Vec<ContinuationStep>) -> Branch {
+        let n = steps.first().map(|step| step.solution.len() - 1).unwrap_or(0);
+
+        let mut special_points: Vec<SpecialPoint> = Vec::new();
+        for test in 0..3 {
+            // last point where the test function had a definite sign
+            let mut last: Option<(usize, f32)> = None;
+
+            for (index, step) in steps.iter().enumerate() {
+                let (kind, value) = step.test_functions(self.tolerance)[test];
+                let Some(value) = value else {
+                    last = None;
+                    continue;
+                };
+
+                if value == 0f32 {
+                    continue;
+                }
+
+                if let Some((last_index, last_value)) = last.filter(|(_, last_value)| last_value.signum() != value.signum()) {
+                    // a real eigenvalue crosses zero at folds too
+                    let at_fold = special_points.iter()
+                        .any(|point| point.kind == SpecialPointKind::Fold && point.index > last_index && point.index <= index);
+
+                    if kind != SpecialPointKind::BranchPoint || !at_fold {
+                        let weight = last_value / (last_value - value);
+                        let (from, to) = (&steps[last_index].solution, &step.solution);
+                        let interpolate = |i: usize| from[i] + (to[i] - from[i]) * weight;
+
+                        special_points.push(SpecialPoint {
+                            kind,
+                            index,
+                            parameter: interpolate(n),
+                            state: (0..n).map(interpolate).collect(),
+                        });
+                    }
+                }
+
+                last = Some((index, value));
+            }
+        }
+
+        special_points.sort_by_key(|point| point.index);
+
+        let points = steps.into_iter()
+            .map(|step| {
+                let stability = Stability::from_eigenvalues(&step.eigenvalues);
+                BranchPoint {
+                    parameter: step.solution[n],
+                    state: step.solution.rows(0, n).iter().cloned().collect(),
+                    eigenvalues: step.eigenvalues,
+                    stability,
+                }
+            })
+            .collect();
+
+        Branch { points, special_points }
+    }
+}
+
+impl ContinuationStep {
+    // Test functions, whose sign changes between points locate the special points:
+    // `None` where the test is not defined, zero where its sign is not reliable.
+    fn test_functions(&self, tolerance: f32) -> [(SpecialPointKind, Option<f32>); 3] {
+        let n = self.solution.len() - 1;
+        let epsilon = eigenvalue_epsilon(&self.eigenvalues);
+        let definite = |value: f32, epsilon: f32| if value.abs() <= epsilon { 0f32 } else { value };
+
+        // parameter component of the tangent
+        let fold = definite(self.tangent[n], tolerance);
+
+        // sign of the determinant of F_y, as the product of the real eigenvalues
+        let real_eigenvalues = self.eigenvalues.iter().filter(|eigenvalue| eigenvalue.im.abs() <= epsilon);
+        let branch_point =
+            if real_eigenvalues.clone().any(|eigenvalue| eigenvalue.re.abs() <= epsilon) {
+                0f32
+            } else {
+                real_eigenvalues.map(|eigenvalue| eigenvalue.re).product()
+            };
+
+        // largest real part among the complex eigenvalues
+        let hopf = self.eigenvalues.iter()
+            .filter(|eigenvalue| eigenvalue.im.abs() > epsilon)
+            .map(|eigenvalue| eigenvalue.re)
+            .reduce(f32::max)
+            .map(|value| definite(value, epsilon));
+
+        [
+            (SpecialPointKind::Fold, Some(fold)),
+            (SpecialPointKind::BranchPoint, Some(branch_point)),
+            (SpecialPointKind::Hopf, hopf),
+        ]
+    }
+}
+
+// F(y, p) with its Jacobian [F_y F_p], over the vector (y, p)
+struct ExtendedSystem<'a, D: Dim, F> {
+    system_at: &'a F,
+    dim: D,
+}
+
+impl<D: Dim, F, S> ExtendedSystem<'_, D, F>
+    where F: Fn(f32) -> S, S: Jacobian<OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+{
+    fn evaluate(&self, solution: &DVector<f32>) -> (DVector<f32>, DMatrix<f32>) {
+        let n = solution.len() - 1;
+        let parameter = solution[n];
+        let state = OVector::<f32, D>::from_iterator_generic(self.dim, U1, solution.iter().take(n).cloned());
+
+        let system = (self.system_at)(parameter);
+        let f = Self::residual(&system, &state);
+
+        let mut jacobian = DMatrix::<f32>::zeros(n, n + 1);
+        let mut state_jacobian = DMatrix::<f32>::zeros(n, n);
+        system.jacobian(0f32, &state, &mut state_jacobian);
+        jacobian.columns_mut(0, n).copy_from(&state_jacobian);
+
+        // forward difference on the parameter
+        let step = f32::EPSILON.sqrt() * parameter.abs().max(1f32);
+        let perturbed = Self::residual(&(self.system_at)(parameter + step), &state);
+        jacobian.column_mut(n).copy_from(&((perturbed - &f) / step));
+
+        (f, jacobian)
+    }
+
+    fn residual(system: &S, state: &OVector<f32, D>) -> DVector<f32> {
+        let mut dy = state.clone();
+        system.system(0f32, state, &mut dy);
+        DVector::from_iterator(dy.len(), dy.iter().cloned())
+    }
+}
+
+// the eigenvalues come from a finite precision Jacobian, compare them to the largest one
+fn eigenvalue_epsilon(eigenvalues: &[Complex<f32>]) -> f32 {
+    1.0e-3 * eigenvalues.iter().map(|eigenvalue| eigenvalue.norm()).fold(1.0e-3, f32::max)
+}
+
+// kept outside of the generic functions, whose allocator bound on D confuses the type inference of nalgebra's solvers
+fn solve_linear(matrix: DMatrix<f32>, rhs: DVector<f32>) -> Option<DVector<f32>> {
+    matrix.lu().solve(&rhs).filter(|solution| solution.iter().all(|value| value.is_finite()))
+}
@@ -0,0 +1,123 @@
+use nalgebra::{allocator::Allocator, DefaultAllocator, Dim, OVector};
+use ode_solvers::dop_shared::{IntegrationError, SolverResult, Stats};
+
+// Delay differential equations y'(x) = f(x, y(x), y(x - tau_1), ..., y(x - tau_k)) with
+// constant delays tau_i > 0, whose states before the initial time are given by a
+// history function.
+pub trait DelaySystem<V> {
+    fn delays(&self) -> Vec<f32>;
+
+    // `delayed[i]` is the state at x - delays()[i]
+    fn system(&self, x: f32, y: &V, delayed: &[V], dy: &mut V);
+}
+
+// Classic Runge-Kutta method of order 4 with fixed step size, extended to DDEs by the
+// method of steps: the step size never exceeds the shortest delay, so that every
+// delayed state lies before the current step, either on the history or on the steps
+// already computed, interpolated by cubic Hermite polynomials.
+pub struct DelayRk4<D: Dim, F, H>
+    where DefaultAllocator: Allocator<f32, D>
+{
+    f: F,
+    history: H,
+    x: f32,
+    x_end: f32,
+    step_size: f32,
+    results: SolverResult<f32, OVector<f32, D>>,
+    derivatives: Vec<OVector<f32, D>>,
+    stats: Stats,
+}
+
+impl<D: Dim, F, H> DelayRk4<D, F, H>
+    where F: DelaySystem<OVector<f32, D>>, H: Fn(f32) -> OVector<f32, D>, DefaultAllocator: Allocator<f32, D>
+{
+    const MAX_STEPS: u32 = 1000000;
+
+    // the initial state is the one of the history at x
+    pub fn new(f: F, x: f32, x_end: f32, step_size: f32, history: H) -> Self {
+        Self {
+            f, history, x, x_end, step_size,
+            results: SolverResult::default(),
+            derivatives: Vec::new(),
+            stats: Stats { num_eval: 0, accepted_steps: 0, rejected_steps: 0 },
+        }
+    }
+
+    pub fn results(&self) -> &SolverResult<f32, OVector<f32, D>> {
+        &self.results
+    }
+
+    pub fn integrate(&mut self) -> Result<Stats, IntegrationError> {
+        let delays = self.f.delays();
+        let step_size = delays.iter().cloned().fold(self.step_size, f32::min);
+        if step_size <= 0f32 {
+            return Err(IntegrationError::StepSizeUnderflow { x: self.x as f64 });
+        }
+
+        let x_start = self.x;
+        let y = (self.history)(self.x);
+        let dy = self.eval(&delays, self.x, &y);
+        self.results.push(self.x, y);
+        self.derivatives.push(dy);
+
+        while self.x < self.x_end {
+            if self.stats.accepted_steps >= Self::MAX_STEPS {
+                return Err(IntegrationError::MaxNumStepReached { x: self.x as f64, n_step: Self::MAX_STEPS });
+            }
+
+            // the times of the steps are not accumulated, to keep them on the grid
+            let next_x = f32::min(x_start + (self.stats.accepted_steps + 1) as f32 * step_size, self.x_end);
+            let (x, h) = (self.x, next_x - self.x);
+            let y = self.results.get().1.last().unwrap().clone();
+
+            let k1 = self.derivatives.last().unwrap().clone();
+            let k2 = self.eval(&delays, x + h / 2f32, &(&y + &k1 * (h / 2f32)));
+            let k3 = self.eval(&delays, x + h / 2f32, &(&y + &k2 * (h / 2f32)));
+            let k4 = self.eval(&delays, x + h, &(&y + &k3 * h));
+            let new_y = &y + (k1 + k2 * 2f32 + k3 * 2f32 + k4) * (h / 6f32);
+
+            self.x = next_x;
+            let new_dy = self.eval(&delays, self.x, &new_y);
+            self.results.push(self.x, new_y);
+            self.derivatives.push(new_dy);
+            self.stats.accepted_steps += 1;
+        }
+
+        Ok(self.stats)
+    }
+
+    fn eval(&mut self, delays: &[f32], x: f32, y: &OVector<f32, D>) -> OVector<f32, D> {
+        let delayed: Vec<_> = delays.iter().map(|delay| self.state_at(x - delay)).collect();
+        let mut dy = y.clone();
+        self.f.system(x, y, &delayed, &mut dy);
+        self.stats.num_eval += 1;
+
+        dy
+    }
+
+    // state at a time before the current step
+    fn state_at(&self, x: f32) -> OVector<f32, D> {
+        let (xs, ys) = self.results.get();
+        if xs.is_empty() || x <= xs[0] {
+            return (self.history)(x);
+        }
+
+        let after = usize::min(xs.partition_point(|step_x| *step_x < x), xs.len() - 1);
+        // on the first step x - delay can round slightly past the initial time
+        if after == 0 {
+            return ys[0].clone();
+        }
+        let (x0, x1) = (xs[after - 1], xs[after]);
+        let (y0, y1) = (&ys[after - 1], &ys[after]);
+        let (dy0, dy1) = (&self.derivatives[after - 1], &self.derivatives[after]);
+
+        let h = x1 - x0;
+        let t = (x - x0) / h;
+        let (t2, t3) = (t * t, t * t * t);
+
+        y0 * (2f32 * t3 - 3f32 * t2 + 1f32)
+            + dy0 * (h * (t3 - 2f32 * t2 + t))
+            + y1 * (3f32 * t2 - 2f32 * t3)
+            + dy1 * (h * (t3 - t2))
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+use nalgebra::{allocator::Allocator, DefaultAllocator, Dim, DMatrix, DVector, OVector};
+use ode_solvers::{Dop853, Dopri5, Rk4, System};
+use crate::chemical_reactions::prelude::*;
+use crate::continuous_dynamical_systems::{NumericalJacobian, ODESolver, Ros23};
+use crate::utils::parameter_sweep::ParameterSpace;
+
+// Observed time series: one row per observation time and one column per observed
+// quantity, with missing observations stored as NaN.
+#[derive(Clone, Debug)]
+pub struct TimeSeries {
+    pub names: Vec<String>,
+    pub times: Vec<f32>,
+    pub values: Vec<Vec<f32>>,
+}
+
+// Least squares estimation of the parameters of a model against a time series, by
+// Levenberg-Marquardt iteration projected on the parameter bounds of the space.
+//
+// The model maps the parameter values and the observation times to its predictions,
+// laid out as the values of the time series; `ode_model` and `reaction_network_model`
+// build such models from the ODE systems and from reaction networks.
+#[derive(Clone)]
+pub struct ModelFit {
+    space: ParameterSpace,
+    max_iterations: u32,
+    tolerance: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct FittedParameter {
+    pub name: String,
+    pub value: f32,
+    pub standard_error: f32,
+    // asymptotic 95% confidence interval
+    pub confidence_interval: (f32, f32),
+}
+
+#[derive(Clone, Debug)]
+pub struct FitResult {
+    pub parameters: Vec<FittedParameter>,
+    // prediction minus observation, NaN where the observation is missing
+    pub residuals: Vec<Vec<f32>>,
+    pub sum_of_squares: f32,
+    pub iterations: u32,
+    pub converged: bool,
+}
+
+impl TimeSeries {
+    // The first line holds the column names and the first column the observation
+    // times; empty fields are missing observations, lines starting with '#' comments.
+    pub fn from_csv(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines()
+            .enumerate()
+            .map(|(number, line)| (number + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let (_, header) = lines.next().ok_or("the time series is empty")?;
+        let names: Vec<String> = header.split(',').skip(1).map(|name| name.trim().to_owned()).collect();
+        if names.is_empty() {
+            return Err("the time series has no observed columns".to_owned());
+        }
+
+        let (mut times, mut values) = (Vec::new(), Vec::new());
+        for (number, line) in lines {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields.len() != names.len() + 1 {
+                return Err(format!("line {number}: expected {} fields, found {}", names.len() + 1, fields.len()));
+            }
+
+            let parse = |field: &str| -> Result<f32, String> {
+                if field.is_empty() {
+                    Ok(f32::NAN)
+                } else {
+                    field.parse().map_err(|_| format!("line {number}: invalid number '{field}'"))
+                }
+            };
+
+            let time = parse(fields[0])?;
+            if !time.is_finite() {
+                return Err(format!("line {number}: missing time"));
+            }
+            times.push(time);
+            values.push(fields[1..].iter().map(|field| parse(field)).collect::<Result<_, _>>()?);
+        }
+
+        Ok(Self { names, times, values })
+    }
+
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|column| column == name)
+    }
+
+    pub fn num_observations(&self) -> usize {
+        self.values.iter().flatten().filter(|value| !value.is_nan()).count()
+    }
+}
+
+impl ModelFit {
+    // relative step of the forward differences approximating the Jacobian of the residuals
+    const DIFFERENCE_STEP: f32 = 1.0e-3;
+    const MAX_DAMPING: f32 = 1.0e10;
+
+    pub fn new(space: ParameterSpace) -> Self {
+        Self { space, max_iterations: 100, tolerance: 1.0e-6 }
+    }
+
+    pub fn max_iterations(mut self, max_iterations: u32) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    pub fn tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn fit<M>(&self, data: &TimeSeries, initial_guess: &[f32], model: M) -> Result<FitResult, String>
+        where M: Fn(&[f32], &[f32]) -> Result<Vec<Vec<f32>>, String>
+    {
+        let parameters = self.space.get_parameters();
+        if initial_guess.len() != parameters.len() {
+            return Err(format!("expected {} initial values, found {}", parameters.len(), initial_guess.len()));
+        }
+
+        let clamp = |values: DVector<f32>| -> DVector<f32> {
+            DVector::from_iterator(values.len(), values.iter().zip(parameters.iter())
+                .map(|(value, parameter)| value.clamp(parameter.min, parameter.max)))
+        };
+        let residuals = |values: &DVector<f32>| -> Result<DVector<f32>, String> {
+            let predictions = model(values.as_slice(), &data.times)?;
+            observed_residuals(data, &predictions)
+        };
+
+        let mut values = clamp(DVector::from_column_slice(initial_guess));
+        let mut current = residuals(&values)?;
+        let mut cost = current.norm_squared();
+        let mut damping = 1.0e-3f32;
+        let (mut iterations, mut converged) = (0, false);
+
+        while iterations < self.max_iterations && !converged {
+            iterations += 1;
+
+            let jacobian = self.residuals_jacobian(&values, &current, &residuals)?;
+            let mut gradient = jacobian.tr_mul(&current);
+            let mut normal_matrix = jacobian.tr_mul(&jacobian);
+
+            // parameters on a bound which the descent direction points out of stay fixed in this step
+            for (i, parameter) in parameters.iter().enumerate() {
+                if (values[i] <= parameter.min && gradient[i] > 0f32) || (values[i] >= parameter.max && gradient[i] < 0f32) {
+                    gradient[i] = 0f32;
+                    normal_matrix.row_mut(i).fill(0f32);
+                    normal_matrix.column_mut(i).fill(0f32);
+                    normal_matrix[(i, i)] = 1f32;
+                }
+            }
+
+            if gradient.amax() <= self.tolerance * cost.max(f32::MIN_POSITIVE) {
+                converged = true;
+                break;
+            }
+
+            loop {
+                // Marquardt's scaling of the damping by the diagonal of J^T J
+                let mut damped = normal_matrix.clone();
+                for i in 0..damped.nrows() {
+                    damped[(i, i)] += damping * normal_matrix[(i, i)].max(f32::EPSILON);
+                }
+
+                let candidate = solve_linear(damped, -&gradient)
+                    .map(|step| clamp(&values + step))
+                    .and_then(|candidate| residuals(&candidate).ok().map(|residuals| (candidate, residuals)))
+                    .filter(|(_, residuals)| residuals.iter().all(|value| value.is_finite()));
+
+                match candidate {
+                    Some((candidate, candidate_residuals)) if candidate_residuals.norm_squared() < cost => {
+                        let step_norm = (&candidate - &values).norm();
+                        let candidate_cost = candidate_residuals.norm_squared();
+                        converged = step_norm <= self.tolerance * (values.norm() + self.tolerance)
+                            || cost - candidate_cost <= self.tolerance * cost;
+
+                        values = candidate;
+                        current = candidate_residuals;
+                        cost = candidate_cost;
+                        damping = (damping / 10f32).max(1.0e-7);
+                        break;
+                    },
+                    _ => {
+                        damping *= 10f32;
+                        // no descent direction left within the bounds
+                        if damping > Self::MAX_DAMPING {
+                            converged = true;
+                            break;
+                        }
+                    },
+                }
+            }
+        }
+
+        // covariance s^2 (J^T J)^-1 of the linearized model at the optimum
+        let jacobian = self.residuals_jacobian(&values, &current, &residuals)?;
+        let degrees_of_freedom = current.len().saturating_sub(values.len()).max(1);
+        let variance = cost / degrees_of_freedom as f32;
+        let covariance = pseudo_inverse(jacobian.tr_mul(&jacobian)) * variance;
+
+        let fitted = parameters.iter().enumerate()
+            .map(|(i, parameter)| {
+                let value = values[i];
+                let standard_error = covariance[(i, i)].max(0f32).sqrt();
+                FittedParameter {
+                    name: parameter.name.clone(),
+                    value,
+                    standard_error,
+                    confidence_interval: (value - 1.96f32 * standard_error, value + 1.96f32 * standard_error),
+                }
+            })
+            .collect();
+
+        let predictions = model(values.as_slice(), &data.times)?;
+        let residuals = data.values.iter().zip(predictions.iter())
+            .map(|(observed, predicted)| observed.iter().zip(predicted.iter())
+                .map(|(observed, predicted)| predicted - observed)
+                .collect())
+            .collect();
+
+        Ok(FitResult { parameters: fitted, residuals, sum_of_squares: cost, iterations, converged })
+    }
+
+    fn residuals_jacobian<R>(&self, values: &DVector<f32>, current: &DVector<f32>, residuals: &R) -> Result<DMatrix<f32>, String>
+        where R: Fn(&DVector<f32>) -> Result<DVector<f32>, String>
+    {
+        let parameters = self.space.get_parameters();
+        let mut jacobian = DMatrix::zeros(current.len(), values.len());
+
+        for (j, parameter) in parameters.iter().enumerate() {
+            let range = parameter.max - parameter.min;
+            let mut step = Self::DIFFERENCE_STEP * values[j].abs().max(Self::DIFFERENCE_STEP * range).max(f32::EPSILON);
+            // step backwards at the upper bound
+            if values[j] + step > parameter.max {
+                step = -step;
+            }
+
+            let mut perturbed = values.clone();
+            perturbed[j] += step;
+            let column = (residuals(&perturbed)? - current) / step;
+            jacobian.set_column(j, &column);
+        }
+
+        Ok(jacobian)
+    }
+}
+
+impl FitResult {
+    pub fn values(&self) -> Vec<f32> {
+        self.parameters.iter().map(|parameter| parameter.value).collect()
+    }
+}
+
+// Integrates the system from time 0 and interpolates linearly its state at the given
+// (non negative) times. Tolerances are tighter than the pages' ones, since fitting
+// differentiates the solution with respect to the parameters.
+pub fn solve_at<D: Dim, F>(ode: F, initial_state: OVector<f32, D>, times: &[f32], solver: ODESolver) -> Result<Vec<OVector<f32, D>>, String>
+    where F: System<f32, OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+{
+    let (rtol, atol) = (1.0e-5f32, 1.0e-7f32);
+    let max_time = times.iter().cloned().fold(0f32, f32::max);
+    if max_time <= 0f32 {
+        return Ok(vec![initial_state; times.len()]);
+    }
+    let step_size = max_time / 1000f32;
+
+    let results =
+        match solver {
+            ODESolver::DOP853 => {
+                let mut stepper = Dop853::new(ode, 0f32, max_time, step_size, initial_state, rtol, atol);
+                stepper.integrate().map_err(|err| err.to_string())?;
+                stepper.results().to_owned()
+            },
+            ODESolver::DOPRI5 => {
+                let mut stepper = Dopri5::new(ode, 0f32, max_time, step_size, initial_state, rtol, atol);
+                stepper.integrate().map_err(|err| err.to_string())?;
+                stepper.results().to_owned()
+            },
+            ODESolver::RK4 => {
+                let mut stepper = Rk4::new(ode, 0f32, initial_state, max_time, step_size);
+                stepper.integrate().map_err(|err| err.to_string())?;
+                stepper.results().to_owned()
+            },
+            ODESolver::ROS23 => {
+                let mut stepper = Ros23::new(NumericalJacobian(ode), 0f32, max_time, step_size, initial_state, rtol, atol);
+                stepper.integrate().map_err(|err| err.to_string())?;
+                stepper.results().to_owned()
+            },
+        };
+
+    let (solution_times, states) = results.get();
+    let states_at = times.iter()
+        .map(|time| {
+            let after = solution_times.partition_point(|solution_time| solution_time < time);
+            if after == 0 {
+                states[0].clone()
+            } else if after == solution_times.len() {
+                states[after - 1].clone()
+            } else {
+                let (t0, t1) = (solution_times[after - 1], solution_times[after]);
+                let weight = if t1 > t0 { (time - t0) / (t1 - t0) } else { 1f32 };
+                &states[after - 1] * (1f32 - weight) + &states[after] * weight
+            }
+        })
+        .collect();
+
+    Ok(states_at)
+}
+
+// Model predicting the `observed` state components of the system built from the
+// parameter values, together with its initial state.
+pub fn ode_model<D: Dim, F, B>(build: B, observed: Vec<usize>, solver: ODESolver) -> impl Fn(&[f32], &[f32]) -> Result<Vec<Vec<f32>>, String>
+    where B: Fn(&[f32]) -> (F, OVector<f32, D>), F: System<f32, OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+{
+    move |values, times| {
+        let (ode, initial_state) = build(values);
+        let states = solve_at(ode, initial_state, times, solver)?;
+
+        Ok(states.iter()
+            .map(|state| observed.iter().map(|component| state[*component]).collect())
+            .collect())
+    }
+}
+
+// Model predicting the concentrations of the `observed` species of the network built
+// from the parameter values, by the ODE derived with mass action (or the reactions'
+// kinetic laws).
+pub fn reaction_network_model<B>(build: B, initial_state: HashMap<Molecule, f32>, observed: Vec<Molecule>, solver: ODESolver) -> impl Fn(&[f32], &[f32]) -> Result<Vec<Vec<f32>>, String>
+    where B: Fn(&[f32]) -> Vec<Reaction>
+{
+    move |values, times| {
+        let ode = ChemicalReactionODE::new(build(values));
+
+        let mut state = DVector::from_element(ode.num_species(), 0f32);
+        for (molecule, quantity) in initial_state.iter() {
+            if let Some(id) = ode.get_species_id(molecule) {
+                state[id] = *quantity;
+            }
+        }
+
+        let observed_ids = observed.iter()
+            .map(|molecule| ode.get_species_id(molecule)
+                .ok_or_else(|| format!("species {} is not in the network", molecule.get_name())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let states = solve_at(ode, state, times, solver)?;
+
+        Ok(states.iter()
+            .map(|state| observed_ids.iter().map(|id| state[*id]).collect())
+            .collect())
+    }
+}
+
+fn observed_residuals(data: &TimeSeries, predictions: &[Vec<f32>]) -> Result<DVector<f32>, String> {
+    if predictions.len() != data.values.len() || predictions.iter().any(|row| row.len() != data.names.len()) {
+        return Err("the model predictions do not match the time series".to_owned());
+    }
+
+    let residuals: Vec<f32> = data.values.iter().zip(predictions.iter())
+        .flat_map(|(observed, predicted)| observed.iter().zip(predicted.iter()))
+        .filter(|(observed, _)| !observed.is_nan())
+        .map(|(observed, predicted)| predicted - observed)
+        .collect();
+
+    Ok(DVector::from_vec(residuals))
+}
+
+fn solve_linear(matrix: DMatrix<f32>, rhs: DVector<f32>) -> Option<DVector<f32>> {
+    matrix.lu().solve(&rhs).filter(|solution| solution.iter().all(|value| value.is_finite()))
+}
+
+fn pseudo_inverse(matrix: DMatrix<f32>) -> DMatrix<f32> {
+    let size = matrix.nrows();
+    matrix.pseudo_inverse(f32::EPSILON).unwrap_or_else(|_| DMatrix::from_element(size, size, f32::NAN))
+}
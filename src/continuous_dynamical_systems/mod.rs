@@ -1,20 +1,32 @@
-mod pages {
-    mod linear_birth_model;
-    mod radioactive_decay;
-    mod logistic_equation;
-    mod male_female_fish_population;
-    mod lotka_volterra;
-    mod sir_model;
-    mod sir_model_birth_deaths;
-    mod sir_model_vaccination;
+pub mod pages {
+    pub mod linear_birth_model;
+    pub mod radioactive_decay;
+    pub mod logistic_equation;
+    pub mod male_female_fish_population;
+    pub mod lotka_volterra;
+    pub mod sir_model;
+    pub mod sir_model_birth_deaths;
+    pub mod sir_model_vaccination;
+    pub mod sir_model_delay;
+    pub mod reaction_diffusion;
+    mod phase_plane;
+    mod bifurcation_curve;
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod commands;
+
 mod prelude {
     pub use super::linear_birth_model::*;
     pub use super::logistic_equation::*;
     pub use super::male_female_fish_population::*;
     pub use super::lotka_volterra::*;
     pub use super::sir_model::*;
+    pub use super::rosenbrock::*;
+    pub use super::steady_state::*;
+    pub use super::continuation::*;
+    pub use super::fitting::*;
+    pub use super::delay::*;
     pub use super::ODESolver;
 }
 
@@ -23,6 +35,19 @@ mod logistic_equation;
 mod male_female_fish_population;
 mod lotka_volterra;
 mod sir_model;
+mod rosenbrock;
+mod steady_state;
+mod continuation;
+mod fitting;
+mod reaction_diffusion;
+mod delay;
+
+pub use rosenbrock::{Jacobian, NumericalJacobian, Ros23};
+pub use steady_state::{SteadyStateFinder, SteadyState, Stability};
+pub use continuation::{Continuation, Branch, BranchPoint, SpecialPoint, SpecialPointKind};
+pub use fitting::{TimeSeries, ModelFit, FitResult, FittedParameter, solve_at, ode_model, reaction_network_model};
+pub use delay::{DelaySystem, DelayRk4};
+pub use reaction_diffusion::{ReactionDiffusion, ReactionDiffusionModel, TimeStepping, Concentrations, ConcentrationLattice, Depleted, BoundaryFixed, BoundaryPeriodic};
 
 #[derive(Clone, Copy)]
 #[derive(Default)]
@@ -30,7 +55,8 @@ pub enum ODESolver {
     DOP853,
     DOPRI5,
     #[default]
-    RK4
+    RK4,
+    ROS23,
 }
 
 impl ODESolver {
@@ -39,6 +65,7 @@ impl ODESolver {
             "dop853" => Some(Self::DOP853),
             "dopri5" => Some(Self::DOPRI5),
             "rk4" => Some(Self::RK4),
+            "ros23" => Some(Self::ROS23),
             _ => None,
         }
     }
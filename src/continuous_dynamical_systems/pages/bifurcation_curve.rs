@@ -0,0 +1,78 @@
+use plotters::prelude::*;
+
+use crate::prelude::*;
+use crate::continuous_dynamical_systems::prelude::*;
+
+// Draws one of the state components of the continued equilibria against the
+// parameter, green where the equilibria are stable and red otherwise, marking the
+// special points found along the branches.
+pub fn draw_branches<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, (x_desc, y_desc): (&str, &str), parameter_range: (f32, f32), branches: Vec<Branch>, component: usize) -> MyDrawResult<()> {
+    let values = || branches.iter().flat_map(|branch| branch.points.iter().map(|point| point.state[component]));
+    let min_value = values().fold(0f32, f32::min);
+    let max_value = values().fold(0f32, f32::max);
+    let margin = (max_value - min_value).max(f32::EPSILON) * 0.1f32;
+
+    area.fill(&WHITE)?;
+
+    let mut chart = ChartBuilder::on(area)
+        .margin(20u32)
+        .x_label_area_size(40u32)
+        .y_label_area_size(60u32)
+        .build_cartesian_2d(parameter_range.0..parameter_range.1, (min_value - margin)..(max_value + margin))?;
+
+    chart.configure_mesh()
+        .x_desc(x_desc)
+        .y_desc(y_desc)
+        .x_labels(10)
+        .y_labels(10)
+        .draw()?;
+
+    let (mut stable_label, mut unstable_label) = (true, true);
+    for branch in branches.iter() {
+        // split the branch where the stability changes, sharing the boundary point
+        let mut start = 0;
+        while start + 1 < branch.points.len() {
+            let stable = branch.points[start].stability.is_stable();
+            let end = (start + 1..branch.points.len())
+                .find(|i| branch.points[*i].stability.is_stable() != stable)
+                .unwrap_or(branch.points.len() - 1);
+
+            let color = if stable { GREEN } else { RED };
+            let series = chart.draw_series(LineSeries::new(
+                branch.points[start..=end].iter().map(|point| (point.parameter, point.state[component])),
+                color.stroke_width(2)
+            ))?;
+
+            let label = if stable { &mut stable_label } else { &mut unstable_label };
+            if *label {
+                series
+                    .label(if stable { "stable" } else { "unstable" })
+                    .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], color));
+                *label = false;
+            }
+
+            start = end;
+        }
+
+        for special_point in branch.special_points.iter() {
+            let name = match special_point.kind {
+                SpecialPointKind::Fold => "fold",
+                SpecialPointKind::Hopf => "Hopf",
+                SpecialPointKind::BranchPoint => "branch point",
+            };
+
+            chart.draw_series(std::iter::once(
+                EmptyElement::at((special_point.parameter, special_point.state[component]))
+                    + Circle::new((0, 0), 5, BLACK.filled())
+                    + Text::new(format!("{name} ({:.3})", special_point.parameter), (8, -16), ("sans-serif", 14).into_font())
+            ))?;
+        }
+    }
+
+    // draw legend
+    chart.configure_series_labels()
+        .background_style(WHITE)
+        .draw()?;
+
+    Ok(())
+}
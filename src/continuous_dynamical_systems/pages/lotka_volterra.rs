@@ -4,6 +4,8 @@ use web_sys::HtmlCanvasElement;
 
 use crate::prelude::*;
 use crate::continuous_dynamical_systems::prelude::*;
+use super::phase_plane::*;
+use super::bifurcation_curve::*;
 
 #[wasm_bindgen(js_name = CDS_SLE_LV)]
 pub struct Model { }
@@ -19,18 +21,16 @@ pub struct Params {
     predator_death_rate: f32,
     hunting_meetings: f32,
     hunt_offsprings: f32,
+    show_steady_states: bool,
 }
 
 #[wasm_bindgen(js_class = CDS_SLE_LV)]
 impl Model {
-    pub fn draw(canvas: HtmlCanvasElement, params: Params) -> Result<(), JsValue> {
-        draw_generic(Self::draw_function)(canvas, params)
+    pub fn draw(canvas: HtmlCanvasElement, typ: String, params: Params) -> Result<(), JsValue> {
+        draw_generic(|area, params| (typ.as_str(), params).draw(area))(canvas, params)
     }
 
-    fn draw_function(canvas: HtmlCanvasElement, params: Params) -> MyDrawResult<()> {
-        let max_time = params.max_time;
-        let solver = params.solver;
-        let model = params.to_model();
+    fn integrate(solver: ODESolver, model: LotkaVolterra) -> MyDrawResult<ode_solvers::dop_shared::SolverResult<f32, ode_solvers::Vector2<f32>>> {
         let step_size = 0.01f32;
 
         use ode_solvers::*;
@@ -51,8 +51,20 @@ impl Model {
                     stepper.integrate()?;
                     stepper.results().to_owned()
                 },
+                ODESolver::ROS23 => {
+                    let mut stepper = Ros23::new(NumericalJacobian(model.ode), 0f32, model.max_time, step_size, model.initial_state, 1.0e-2, 1.0e-6);
+                    stepper.integrate()?;
+                    stepper.results().to_owned()
+                },
             };
 
+        Ok(results)
+    }
+
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        let max_time = params.max_time;
+        let solver = params.solver;
+        let results = Self::integrate(solver, params.to_model())?;
         let (time, population) = results.get();
 
         let max_population_display = (f32::max(
@@ -60,13 +72,12 @@ impl Model {
             population.iter().map(|res| res[1]).reduce(f32::max).unwrap(),
         ) * 1.5f32) as u32;
 
-        let area = draw_prelude(canvas)?;
         area.fill(&WHITE)?;
 
         let x_axis_range = 0f32..max_time;
         let y_axis_range = 0..max_population_display;
     
-        let mut chart = ChartBuilder::on(&area)
+        let mut chart = ChartBuilder::on(area)
             .margin(20u32)
             .x_label_area_size(40u32)
             .y_label_area_size(60u32)
@@ -107,6 +118,76 @@ impl Model {
     
         Ok(())
     }
+
+    fn draw_phase_graph<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        let show_steady_states = params.show_steady_states;
+        let solver = params.solver;
+        let model = params.to_model();
+
+        let steady_states =
+            if show_steady_states {
+                let upper = model.initial_state * 2f32;
+                let starting_points = SteadyStateFinder::grid(&ode_solvers::Vector2::zeros(), &upper, 5);
+
+                SteadyStateFinder::new()
+                    .non_negative(true)
+                    .find(&NumericalJacobian(model.ode.clone()), &starting_points)
+                    .into_iter()
+                    .map(|steady_state| ((steady_state.state[0], steady_state.state[1]), steady_state.stability))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+        let results = Self::integrate(solver, model)?;
+        let (_, population) = results.get();
+        let trajectory = population.iter().map(|res| (res[0], res[1])).collect();
+
+        draw_phase_plane(area, ("V(t)", "P(t)"), trajectory, steady_states)
+    }
+
+    // equilibria continued over the predator death rate, from the ones at the current value
+    fn draw_continuation<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        const PREDATOR_DEATH_RATE_RANGE: (f32, f32) = (1f32, 20f32);
+
+        let predator_death_rate = params.predator_death_rate;
+        let upper = ode_solvers::Vector2::new(params.initial_prey_pop, params.initial_predator_pop) * 2f32;
+        let system_at = |predator_death_rate| NumericalJacobian(
+            Params { predator_death_rate, ..params }.to_model().ode
+        );
+
+        let starting_points = SteadyStateFinder::grid(&ode_solvers::Vector2::zeros(), &upper, 5);
+        let steady_states: Vec<_> = SteadyStateFinder::new()
+            .non_negative(true)
+            .find(&system_at(predator_death_rate), &starting_points)
+            .into_iter()
+            .map(|steady_state| ode_solvers::Vector2::from_column_slice(&steady_state.state))
+            .collect();
+
+        let branches = Continuation::new(PREDATOR_DEATH_RATE_RANGE, 0.1f32)
+            .run_all(system_at, &steady_states, predator_death_rate);
+
+        draw_branches(area, ("s", "V*"), PREDATOR_DEATH_RATE_RANGE, branches, 0)
+    }
+}
+
+impl Chart for (&str, Params) {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        let (typ, params) = self;
+
+        if typ == "continuation" {
+            return Model::draw_continuation(area, params);
+        }
+
+        match GraphType::from_string(typ.to_owned()) {
+            Some(GraphType::Function) =>
+                Model::draw_function(area, params),
+            Some(GraphType::PhaseGraph) =>
+                Model::draw_phase_graph(area, params),
+            None =>
+                Err("Graph type not supported".into())
+        }
+    }
 }
 
 #[wasm_bindgen(js_class = CDS_SLE_LV_Params)]
@@ -155,6 +236,11 @@ impl Params {
         self
     }
 
+    pub fn show_steady_states(mut self, show_steady_states: bool) -> Self {
+        self.show_steady_states = show_steady_states;
+        self
+    }
+
     fn to_model(self) -> LotkaVolterra {
         LotkaVolterra::new(
             (self.initial_prey_pop, self.initial_predator_pop),
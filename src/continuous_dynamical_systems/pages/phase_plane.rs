@@ -0,0 +1,47 @@
+use plotters::prelude::*;
+
+use crate::prelude::*;
+use crate::continuous_dynamical_systems::prelude::*;
+
+// Draws a trajectory projected on two of the state variables, marking the given
+// equilibria (green when stable, red otherwise) together with their classification.
+pub fn draw_phase_plane<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, (x_desc, y_desc): (&str, &str), trajectory: Vec<(f32, f32)>, steady_states: Vec<((f32, f32), Stability)>) -> MyDrawResult<()> {
+    let max_display = |axis: fn(&(f32, f32)) -> f32| trajectory.iter().map(axis)
+        .chain(steady_states.iter().map(|(point, _)| axis(point)))
+        .reduce(f32::max).unwrap_or(0f32).max(f32::EPSILON) * 1.2f32;
+
+    area.fill(&WHITE)?;
+
+    let x_axis_range = 0f32..max_display(|(x, _)| *x);
+    let y_axis_range = 0f32..max_display(|(_, y)| *y);
+
+    let mut chart = ChartBuilder::on(area)
+        .margin(20u32)
+        .x_label_area_size(40u32)
+        .y_label_area_size(60u32)
+        .build_cartesian_2d(x_axis_range, y_axis_range)?;
+
+    chart.configure_mesh()
+        .x_desc(x_desc)
+        .y_desc(y_desc)
+        .x_labels(10)
+        .y_labels(10)
+        .draw()?;
+
+    // trajectory, from the initial state
+    chart.draw_series(trajectory.first().map(|point| Circle::new(*point, 3, BLACK.filled())))?;
+    chart.draw_series(LineSeries::new(trajectory, &BLACK))?;
+
+    // equilibria
+    for (point, stability) in steady_states {
+        let color = if stability.is_stable() { GREEN } else { RED };
+
+        chart.draw_series(std::iter::once(
+            EmptyElement::at(point)
+                + Circle::new((0, 0), 5, color.filled())
+                + Text::new(stability.name(), (8, -16), ("sans-serif", 14).into_font())
+        ))?;
+    }
+
+    Ok(())
+}
@@ -22,8 +22,7 @@ impl Model {
         draw_generic(Self::draw_function)(canvas, params)
     }
 
-    fn draw_function(canvas: HtmlCanvasElement, params: Params) -> MyDrawResult<()> {
-        let area = draw_prelude(canvas)?;
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
         area.fill(&WHITE)?;
     
         let max_population_display =
@@ -32,7 +31,7 @@ impl Model {
         let x_axis_range = 0f32..params.max_time;
         let y_axis_range = 0..max_population_display;
     
-        let mut chart = ChartBuilder::on(&area)
+        let mut chart = ChartBuilder::on(area)
             .margin(20u32)
             .x_label_area_size(40u32)
             .y_label_area_size(60u32)
@@ -61,6 +60,12 @@ impl Model {
     }
 }
 
+impl Chart for Params {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        Model::draw_function(area, self)
+    }
+}
+
 #[wasm_bindgen(js_class = CDS_RD_Params)]
 impl Params {
     pub fn builder() -> Self {
@@ -0,0 +1,197 @@
+use plotters::prelude::*;
+use wasm_bindgen::prelude::*;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, ImageData};
+
+use crate::prelude::*;
+use crate::cellular_automata::prelude::*;
+use crate::continuous_dynamical_systems::*;
+
+#[wasm_bindgen(js_name = CDS_RDF)]
+pub struct Model { }
+
+#[wasm_bindgen(js_name = CDS_RDF_Params)]
+#[derive(Default)]
+pub struct Params {
+    model: String,
+    size: u32,
+    max_time: f32,
+    fixed_boundary: bool,
+    stepping: TimeStepping,
+    time_step: f32,
+    diffusion_u: f32,
+    diffusion_v: f32,
+    feed: f32,
+    kill: f32,
+    a: f32,
+    b: f32,
+    epsilon: f32,
+    a0: f32,
+    a1: f32,
+    seed: u64,
+}
+
+#[wasm_bindgen(js_class = CDS_RDF)]
+impl Model {
+    pub fn draw(canvas: HtmlCanvasElement, params: Params) -> Result<(), JsValue> {
+        let size = params.size;
+        let image = params.into_image()?;
+
+        // fill the canvas
+        let image = ImageData::new_with_u8_clamped_array_and_sh(
+            wasm_bindgen::Clamped(&image),
+            size,
+            size
+        )?;
+
+        canvas.set_width(size);
+        canvas.set_height(size);
+
+        let context: CanvasRenderingContext2d = canvas.get_context("2d")?.ok_or(String::new())?.dyn_into()?;
+        context.put_image_data(&image, 0f64, 0f64)?;
+
+        Ok(())
+    }
+
+    // same image on the other backends, the page fills the canvas with the image data
+    // directly as blitting it through plotters is way slower
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        let image = params.into_image()?;
+        draw_image(area, &image::DynamicImage::from(image).into_rgb8())
+    }
+}
+
+impl Chart for Params {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        Model::draw_function(area, self)
+    }
+}
+
+#[wasm_bindgen(js_class = CDS_RDF_Params)]
+impl Params {
+    pub fn builder() -> Self {
+        Self { ..Default::default() }
+    }
+
+    // "gray_scott", "brusselator" or "fitzhugh_nagumo"
+    pub fn model(mut self, model: String) -> Self {
+        self.model = model;
+        self
+    }
+
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn max_time(mut self, max_time: f32) -> Self {
+        self.max_time = max_time;
+        self
+    }
+
+    pub fn fixed_boundary(mut self, fixed_boundary: bool) -> Self {
+        self.fixed_boundary = fixed_boundary;
+        self
+    }
+
+    pub fn stepping(mut self, stepping: String) -> Self {
+        self.stepping = TimeStepping::from_string(stepping).unwrap_or_default();
+        self
+    }
+
+    pub fn time_step(mut self, time_step: f32) -> Self {
+        self.time_step = time_step;
+        self
+    }
+
+    pub fn diffusion_u(mut self, diffusion_u: f32) -> Self {
+        self.diffusion_u = diffusion_u;
+        self
+    }
+
+    pub fn diffusion_v(mut self, diffusion_v: f32) -> Self {
+        self.diffusion_v = diffusion_v;
+        self
+    }
+
+    pub fn feed(mut self, feed: f32) -> Self {
+        self.feed = feed;
+        self
+    }
+
+    pub fn kill(mut self, kill: f32) -> Self {
+        self.kill = kill;
+        self
+    }
+
+    pub fn a(mut self, a: f32) -> Self {
+        self.a = a;
+        self
+    }
+
+    pub fn b(mut self, b: f32) -> Self {
+        self.b = b;
+        self
+    }
+
+    pub fn epsilon(mut self, epsilon: f32) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    pub fn a0(mut self, a0: f32) -> Self {
+        self.a0 = a0;
+        self
+    }
+
+    pub fn a1(mut self, a1: f32) -> Self {
+        self.a1 = a1;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    // concentrations of v at max_time, from the lowest (dark) to the highest (bright)
+    fn into_image(self) -> Result<image::RgbaImage, String> {
+        let size = self.size;
+        let lattice = if self.fixed_boundary {
+            self.into_model::<BoundaryFixed>()?.nth(1)
+        } else {
+            self.into_model::<BoundaryPeriodic>()?.nth(1)
+        };
+        let (_, lattice) = lattice.unwrap();
+
+        let (min, max) = lattice.cells.iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), [_, v]| (min.min(*v), max.max(*v)));
+        let max = if max > min { max } else { min + 1f32 };
+
+        let mut image = image::RgbaImage::new(size, size);
+        for (cell, pixel) in lattice.cells.iter().zip(image.pixels_mut()) {
+            let color = ViridisRGB.get_color_normalized(cell[1], min, max);
+            pixel.0 = [color.0, color.1, color.2, 255];
+        }
+
+        Ok(image)
+    }
+
+    fn reaction_model(&self) -> Result<ReactionDiffusionModel, String> {
+        match self.model.as_str() {
+            "gray_scott" => Ok(ReactionDiffusionModel::GrayScott { feed: self.feed, kill: self.kill }),
+            "brusselator" => Ok(ReactionDiffusionModel::Brusselator { a: self.a, b: self.b }),
+            "fitzhugh_nagumo" => Ok(ReactionDiffusionModel::FitzHughNagumo { epsilon: self.epsilon, a0: self.a0, a1: self.a1 }),
+            model => Err(format!("Unknown reaction-diffusion model: {model}")),
+        }
+    }
+
+    // sampled at the initial state and at max_time only
+    fn into_model<B>(self) -> Result<ReactionDiffusion<B>, String>
+        where B: BoundaryCell<Concentrations> + LatticeBoundary
+    {
+        let model = self.reaction_model()?;
+        let initial_state = model.initial_state((self.size, self.size), self.seed);
+
+        ReactionDiffusion::new(model, initial_state, [self.diffusion_u, self.diffusion_v], self.time_step, 1f32, self.stepping, self.max_time)
+    }
+}
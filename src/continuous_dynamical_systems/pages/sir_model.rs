@@ -4,6 +4,7 @@ use web_sys::HtmlCanvasElement;
 
 use crate::prelude::*;
 use crate::continuous_dynamical_systems::prelude::*;
+use crate::utils::parameter_sweep::ParameterSpace;
 
 #[wasm_bindgen(js_name = CDS_SLE_SIR)]
 pub struct Model { }
@@ -18,6 +19,7 @@ pub struct Params {
     initial_recovered_pop: f32,
     infection_coefficient: f32,
     recovery_coefficient: f32,
+    observed_data: String,
 }
 
 #[wasm_bindgen(js_class = CDS_SLE_SIR)]
@@ -26,19 +28,44 @@ impl Model {
         draw_generic(Self::draw_function)(canvas, params)
     }
 
-    fn draw_function(canvas: HtmlCanvasElement, params: Params) -> MyDrawResult<()> {
-        let area = draw_prelude(canvas)?;
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, mut params: Params) -> MyDrawResult<()> {
+        // the coefficients are replaced by the ones fitted on the observed data, if any
+        let data =
+            if params.observed_data.trim().is_empty() {
+                None
+            } else {
+                Some(TimeSeries::from_csv(&params.observed_data)?)
+            };
+
+        let fit = data.as_ref().map(|data| Self::fit(&params, data)).transpose()?;
+        if let Some(fit) = fit.as_ref() {
+            params.infection_coefficient = fit.parameters[0].value;
+            params.recovery_coefficient = fit.parameters[1].value;
+        }
+
+        let max_data_time = data.as_ref().map(|data| data.times.iter().cloned().fold(0f32, f32::max)).unwrap_or(0f32);
+        params.max_time = params.max_time.max(max_data_time);
+
         area.fill(&WHITE)?;
 
         let x_axis_range = 0f32..params.max_time;
         let y_axis_range = 0f32..1f32;
-    
-        let mut chart = ChartBuilder::on(&area)
+
+        let mut chart_builder = ChartBuilder::on(area);
+        if let Some(fit) = fit.as_ref() {
+            let caption = fit.parameters.iter()
+                .map(|parameter| format!("{} = {:.3} ± {:.3}", parameter.name, parameter.value, 1.96f32 * parameter.standard_error))
+                .collect::<Vec<_>>()
+                .join(", ");
+            chart_builder.caption(caption, ("sans-serif", 16).into_font());
+        }
+
+        let mut chart = chart_builder
             .margin(20u32)
             .x_label_area_size(40u32)
             .y_label_area_size(60u32)
             .build_cartesian_2d(x_axis_range, y_axis_range)?;
-    
+
         chart.configure_mesh()
             .x_desc("t")
             .y_desc("N(t)")
@@ -68,6 +95,11 @@ impl Model {
                     stepper.integrate()?;
                     stepper.results().to_owned()
                 },
+                ODESolver::ROS23 => {
+                    let mut stepper = Ros23::new(NumericalJacobian(model.ode), 0f32, model.max_time, step_size, model.initial_state, 1.0e-2, 1.0e-6);
+                    stepper.integrate()?;
+                    stepper.results().to_owned()
+                },
             };
 
         let (time, population) = results.get();
@@ -100,7 +132,19 @@ impl Model {
         ))?
         .label("R(t)")
         .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], BLUE));
-    
+
+        // observed data
+        if let Some(data) = data.as_ref() {
+            for (column, name) in data.names.iter().enumerate() {
+                let color = Self::compartment_color(name);
+                chart.draw_series(
+                    data.times.iter().zip(data.values.iter())
+                        .filter(|(_, values)| !values[column].is_nan())
+                        .map(|(time, values)| Circle::new((*time, values[column]), 3, color.filled()))
+                )?;
+            }
+        }
+
         // draw legend
         chart.configure_series_labels()
             .background_style(WHITE)
@@ -108,6 +152,48 @@ impl Model {
     
         Ok(())
     }
+
+    // least squares estimate of the infection and recovery coefficients, starting from
+    // the current ones, with the columns of the data named after the compartments
+    fn fit(params: &Params, data: &TimeSeries) -> MyDrawResult<FitResult> {
+        let observed = data.names.iter()
+            .map(|name| match name.as_str() {
+                "S" => Ok(0),
+                "I" => Ok(1),
+                "R" => Ok(2),
+                _ => Err(format!("unknown column '{name}', expected S, I or R")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let initial_population = (params.initial_susceptible_pop, params.initial_infected_pop, params.initial_recovered_pop);
+        let model = ode_model(move |values| {
+            let model = SIRModel::new(initial_population, values[0], values[1], 0f32, 0f32, 0f32);
+            (model.ode, model.initial_state)
+        }, observed, params.solver);
+
+        let space = ParameterSpace::new()
+            .parameter("β", (0f32, 5f32))
+            .parameter("γ", (0f32, 5f32));
+
+        let fit = ModelFit::new(space)
+            .fit(data, &[params.infection_coefficient, params.recovery_coefficient], model)?;
+
+        Ok(fit)
+    }
+
+    fn compartment_color(name: &str) -> RGBColor {
+        match name {
+            "S" => GREEN,
+            "I" => RED,
+            _ => BLUE,
+        }
+    }
+}
+
+impl Chart for Params {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        Model::draw_function(area, self)
+    }
 }
 
 #[wasm_bindgen(js_class = CDS_SLE_SIR_Params)]
@@ -151,6 +237,11 @@ impl Params {
         self
     }
 
+    pub fn observed_data(mut self, observed_data: String) -> Self {
+        self.observed_data = observed_data;
+        self
+    }
+
     fn to_model(self) -> SIRModel {
         SIRModel::new(
             (self.initial_susceptible_pop, self.initial_infected_pop, self.initial_recovered_pop),
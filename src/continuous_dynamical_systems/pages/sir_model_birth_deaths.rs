@@ -4,6 +4,7 @@ use web_sys::HtmlCanvasElement;
 
 use crate::prelude::*;
 use crate::continuous_dynamical_systems::prelude::*;
+use super::phase_plane::*;
 
 #[wasm_bindgen(js_name = CDS_SLE_SIR_BD)]
 pub struct Model { }
@@ -19,36 +20,16 @@ pub struct Params {
     infection_coefficient: f32,
     recovery_coefficient: f32,
     birth_rate: f32,
+    show_steady_states: bool,
 }
 
 #[wasm_bindgen(js_class = CDS_SLE_SIR_BD)]
 impl Model {
-    pub fn draw(canvas: HtmlCanvasElement, params: Params) -> Result<(), JsValue> {
-        draw_generic(Self::draw_function)(canvas, params)
+    pub fn draw(canvas: HtmlCanvasElement, typ: String, params: Params) -> Result<(), JsValue> {
+        draw_generic(|area, params| (typ.as_str(), params).draw(area))(canvas, params)
     }
 
-    fn draw_function(canvas: HtmlCanvasElement, params: Params) -> MyDrawResult<()> {
-        let area = draw_prelude(canvas)?;
-        area.fill(&WHITE)?;
-
-        let x_axis_range = 0f32..params.max_time;
-        let y_axis_range = 0f32..1f32;
-    
-        let mut chart = ChartBuilder::on(&area)
-            .margin(20u32)
-            .x_label_area_size(40u32)
-            .y_label_area_size(60u32)
-            .build_cartesian_2d(x_axis_range, y_axis_range)?;
-    
-        chart.configure_mesh()
-            .x_desc("t")
-            .y_desc("N(t)")
-            .x_labels(10)
-            .y_labels(10)
-            .draw()?;
-
-        let solver = params.solver;
-        let model = params.to_model();
+    fn integrate(solver: ODESolver, model: SIRModel) -> MyDrawResult<ode_solvers::dop_shared::SolverResult<f32, ode_solvers::Vector3<f32>>> {
         let step_size = 0.01f32;
 
         use ode_solvers::*;
@@ -69,8 +50,37 @@ impl Model {
                     stepper.integrate()?;
                     stepper.results().to_owned()
                 },
+                ODESolver::ROS23 => {
+                    let mut stepper = Ros23::new(NumericalJacobian(model.ode), 0f32, model.max_time, step_size, model.initial_state, 1.0e-2, 1.0e-6);
+                    stepper.integrate()?;
+                    stepper.results().to_owned()
+                },
             };
 
+        Ok(results)
+    }
+
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        area.fill(&WHITE)?;
+
+        let x_axis_range = 0f32..params.max_time;
+        let y_axis_range = 0f32..1f32;
+    
+        let mut chart = ChartBuilder::on(area)
+            .margin(20u32)
+            .x_label_area_size(40u32)
+            .y_label_area_size(60u32)
+            .build_cartesian_2d(x_axis_range, y_axis_range)?;
+    
+        chart.configure_mesh()
+            .x_desc("t")
+            .y_desc("N(t)")
+            .x_labels(10)
+            .y_labels(10)
+            .draw()?;
+
+        let solver = params.solver;
+        let results = Self::integrate(solver, params.to_model())?;
         let (time, population) = results.get();
 
         let simulation = time.iter().zip(population.iter())
@@ -109,6 +119,47 @@ impl Model {
     
         Ok(())
     }
+
+    fn draw_phase_graph<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        let show_steady_states = params.show_steady_states;
+        let solver = params.solver;
+        let model = params.to_model();
+
+        let steady_states =
+            if show_steady_states {
+                let starting_points = SteadyStateFinder::grid(&ode_solvers::Vector3::zeros(), &ode_solvers::Vector3::repeat(1f32), 4);
+
+                SteadyStateFinder::new()
+                    .non_negative(true)
+                    .find(&NumericalJacobian(model.ode.clone()), &starting_points)
+                    .into_iter()
+                    .map(|steady_state| ((steady_state.state[0], steady_state.state[1]), steady_state.stability))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+        let results = Self::integrate(solver, model)?;
+        let (_, population) = results.get();
+        let trajectory = population.iter().map(|res| (res[0], res[1])).collect();
+
+        draw_phase_plane(area, ("S(t)", "I(t)"), trajectory, steady_states)
+    }
+}
+
+impl Chart for (&str, Params) {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        let (typ, params) = self;
+
+        match GraphType::from_string(typ.to_owned()) {
+            Some(GraphType::Function) =>
+                Model::draw_function(area, params),
+            Some(GraphType::PhaseGraph) =>
+                Model::draw_phase_graph(area, params),
+            None =>
+                Err("Graph type not supported".into())
+        }
+    }
 }
 
 #[wasm_bindgen(js_class = CDS_SLE_SIR_BD_Params)]
@@ -157,6 +208,11 @@ impl Params {
         self
     }
 
+    pub fn show_steady_states(mut self, show_steady_states: bool) -> Self {
+        self.show_steady_states = show_steady_states;
+        self
+    }
+
     fn to_model(self) -> SIRModel {
         SIRModel::new(
             (self.initial_susceptible_pop, self.initial_infected_pop, self.initial_recovered_pop),
@@ -0,0 +1,177 @@
+use plotters::prelude::*;
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlCanvasElement;
+
+use crate::prelude::*;
+use crate::continuous_dynamical_systems::prelude::*;
+
+#[wasm_bindgen(js_name = CDS_SLE_SIR_D)]
+pub struct Model { }
+
+#[wasm_bindgen(js_name = CDS_SLE_SIR_D_Params)]
+#[derive(Default)]
+pub struct Params {
+    max_time: f32,
+    initial_susceptible_pop: f32,
+    initial_infected_pop: f32,
+    initial_recovered_pop: f32,
+    infection_coefficient: f32,
+    recovery_coefficient: f32,
+    incubation_delay: f32,
+}
+
+#[wasm_bindgen(js_class = CDS_SLE_SIR_D)]
+impl Model {
+    pub fn draw(canvas: HtmlCanvasElement, params: Params) -> Result<(), JsValue> {
+        draw_generic(Self::draw_function)(canvas, params)
+    }
+
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        area.fill(&WHITE)?;
+
+        let x_axis_range = 0f32..params.max_time;
+        let y_axis_range = 0f32..1f32;
+
+        let mut chart = ChartBuilder::on(area)
+            .margin(20u32)
+            .x_label_area_size(40u32)
+            .y_label_area_size(60u32)
+            .build_cartesian_2d(x_axis_range, y_axis_range)?;
+
+        chart.configure_mesh()
+            .x_desc("t")
+            .y_desc("N(t)")
+            .x_labels(10)
+            .y_labels(10)
+            .draw()?;
+
+        let step_size = 0.01f32;
+
+        // the same epidemic, where the infected are immediately infectious
+        let model = params.to_undelayed_model();
+        let mut stepper = ode_solvers::Rk4::new(model.ode, 0f32, model.initial_state, model.max_time, step_size);
+        stepper.integrate()?;
+        let (time, population) = stepper.results().get();
+        let undelayed = time.iter().zip(population.iter())
+            .map(|(time, res)| (*time, (res[0], res[1], res[2])));
+        let undelayed = Simulation::new(undelayed)
+            .time_limit(chart.x_range().end);
+
+        let model = params.to_model();
+        let history = model.history();
+        let mut stepper = DelayRk4::new(model.ode, 0f32, model.max_time, step_size, history);
+        stepper.integrate()?;
+        let (time, population) = stepper.results().get();
+        let delayed = time.iter().zip(population.iter())
+            .map(|(time, res)| (*time, (res[0], res[1], res[2])));
+        let delayed = Simulation::new(delayed)
+            .time_limit(chart.x_range().end);
+
+        let undelayed_style = |color: RGBColor| color.mix(0.35).stroke_width(1);
+        chart.draw_series(LineSeries::new(undelayed.clone().simulation_map(|(x, pops)| (x, pops.0)), undelayed_style(GREEN)))?;
+        chart.draw_series(LineSeries::new(undelayed.clone().simulation_map(|(x, pops)| (x, pops.1)), undelayed_style(RED)))?;
+        chart.draw_series(LineSeries::new(undelayed.clone().simulation_map(|(x, pops)| (x, pops.2)), undelayed_style(BLUE)))?
+            .label("without incubation")
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], undelayed_style(BLACK)));
+
+        // susceptible population
+        chart.draw_series(LineSeries::new(
+            delayed.clone().simulation_map(|(x, pops)| (x, pops.0)),
+            GREEN.stroke_width(2)
+        ))?
+        .label("S(t)")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], GREEN));
+
+        // infected population
+        chart.draw_series(LineSeries::new(
+            delayed.clone().simulation_map(|(x, pops)| (x, pops.1)),
+            RED.stroke_width(2)
+        ))?
+        .label("I(t)")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], RED));
+
+        // recovered population
+        chart.draw_series(LineSeries::new(
+            delayed.clone().simulation_map(|(x, pops)| (x, pops.2)),
+            BLUE.stroke_width(2)
+        ))?
+        .label("R(t)")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], BLUE));
+
+        // draw legend
+        chart.configure_series_labels()
+            .background_style(WHITE)
+            .draw()?;
+
+        Ok(())
+    }
+}
+
+impl Chart for Params {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        Model::draw_function(area, self)
+    }
+}
+
+#[wasm_bindgen(js_class = CDS_SLE_SIR_D_Params)]
+impl Params {
+    pub fn builder() -> Self {
+        Self { ..Default::default() }
+    }
+
+    pub fn max_time(mut self, max_time: f32) -> Self {
+        self.max_time = max_time;
+        self
+    }
+
+    pub fn initial_susceptible_population(mut self, initial_susceptible_pop: f32) -> Self {
+        self.initial_susceptible_pop = initial_susceptible_pop;
+        self
+    }
+
+    pub fn initial_infected_population(mut self, initial_infected_pop: f32) -> Self {
+        self.initial_infected_pop = initial_infected_pop;
+        self
+    }
+
+    pub fn initial_recovered_population(mut self, initial_recovered_pop: f32) -> Self {
+        self.initial_recovered_pop = initial_recovered_pop;
+        self
+    }
+
+    pub fn infection_coefficient(mut self, infection_coefficient: f32) -> Self {
+        self.infection_coefficient = infection_coefficient;
+        self
+    }
+
+    pub fn recovery_coefficient(mut self, recovery_coefficient: f32) -> Self {
+        self.recovery_coefficient = recovery_coefficient;
+        self
+    }
+
+    pub fn incubation_delay(mut self, incubation_delay: f32) -> Self {
+        self.incubation_delay = incubation_delay;
+        self
+    }
+
+    fn to_model(&self) -> DelayedSIRModel {
+        DelayedSIRModel::new(
+            (self.initial_susceptible_pop, self.initial_infected_pop, self.initial_recovered_pop),
+            self.infection_coefficient,
+            self.recovery_coefficient,
+            self.incubation_delay,
+            self.max_time,
+        )
+    }
+
+    fn to_undelayed_model(&self) -> SIRModel {
+        SIRModel::new(
+            (self.initial_susceptible_pop, self.initial_infected_pop, self.initial_recovered_pop),
+            self.infection_coefficient,
+            self.recovery_coefficient,
+            0f32,
+            0f32,
+            self.max_time,
+        )
+    }
+}
@@ -4,6 +4,7 @@ use web_sys::HtmlCanvasElement;
 
 use crate::prelude::*;
 use crate::continuous_dynamical_systems::prelude::*;
+use super::bifurcation_curve::*;
 
 #[wasm_bindgen(js_name = CDS_SLE_SIR_V)]
 pub struct Model { }
@@ -24,18 +25,17 @@ pub struct Params {
 
 #[wasm_bindgen(js_class = CDS_SLE_SIR_V)]
 impl Model {
-    pub fn draw(canvas: HtmlCanvasElement, params: Params) -> Result<(), JsValue> {
-        draw_generic(Self::draw_function)(canvas, params)
+    pub fn draw(canvas: HtmlCanvasElement, typ: String, params: Params) -> Result<(), JsValue> {
+        draw_generic(|area, params| (typ.as_str(), params).draw(area))(canvas, params)
     }
 
-    fn draw_function(canvas: HtmlCanvasElement, params: Params) -> MyDrawResult<()> {
-        let area = draw_prelude(canvas)?;
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
         area.fill(&WHITE)?;
 
         let x_axis_range = 0f32..params.max_time;
         let y_axis_range = 0f32..1f32;
     
-        let mut chart = ChartBuilder::on(&area)
+        let mut chart = ChartBuilder::on(area)
             .margin(20u32)
             .x_label_area_size(40u32)
             .y_label_area_size(60u32)
@@ -70,6 +70,11 @@ impl Model {
                     stepper.integrate()?;
                     stepper.results().to_owned()
                 },
+                ODESolver::ROS23 => {
+                    let mut stepper = Ros23::new(NumericalJacobian(model.ode), 0f32, model.max_time, step_size, model.initial_state, 1.0e-2, 1.0e-6);
+                    stepper.integrate()?;
+                    stepper.results().to_owned()
+                },
             };
 
         let (time, population) = results.get();
@@ -110,6 +115,39 @@ impl Model {
     
         Ok(())
     }
+
+    // equilibria continued over the vaccination coefficient, from the ones at the current value
+    fn draw_continuation<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        let vaccination_coefficient = params.vaccination_coefficient;
+        let system_at = |vaccination_coefficient| NumericalJacobian(
+            Params { vaccination_coefficient, ..params }.to_model().ode
+        );
+
+        let starting_points = SteadyStateFinder::grid(&ode_solvers::Vector3::zeros(), &ode_solvers::Vector3::repeat(1f32), 4);
+        let steady_states: Vec<_> = SteadyStateFinder::new()
+            .non_negative(true)
+            .find(&system_at(vaccination_coefficient), &starting_points)
+            .into_iter()
+            .map(|steady_state| ode_solvers::Vector3::from_column_slice(&steady_state.state))
+            .collect();
+
+        let branches = Continuation::new((0f32, 1f32), 0.01f32)
+            .run_all(system_at, &steady_states, vaccination_coefficient);
+
+        draw_branches(area, ("p", "I*"), (0f32, 1f32), branches, 1)
+    }
+}
+
+impl Chart for (&str, Params) {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        let (typ, params) = self;
+
+        match typ {
+            "normal" => Model::draw_function(area, params),
+            "continuation" => Model::draw_continuation(area, params),
+            _ => Err(format!("Graph type {typ} not supported").into()),
+        }
+    }
 }
 
 #[wasm_bindgen(js_class = CDS_SLE_SIR_V_Params)]
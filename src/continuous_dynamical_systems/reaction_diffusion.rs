@@ -0,0 +1,355 @@
+use std::marker::PhantomData;
+use rand::{Rng, SeedableRng};
+use crate::cellular_automata::prelude::*;
+
+// Concentrations (u, v) of the two species in a cell of the grid.
+pub type Concentrations = [f32; 2];
+pub type ConcentrationLattice = Lattice<Concentrations>;
+
+// Fixed boundary with no reactants past the edges of the grid.
+pub struct Depleted;
+
+impl ToCell<Concentrations> for Depleted {
+    fn to_cell() -> Concentrations {
+        [0f32; 2]
+    }
+}
+
+pub type BoundaryFixed = FixedBoundary<Concentrations, Depleted>;
+pub type BoundaryPeriodic = PeriodicBoundary;
+
+#[derive(Clone, Copy)]
+pub enum ReactionDiffusionModel {
+    // u' = -u v^2 + feed (1 - u), v' = u v^2 - (feed + kill) v
+    GrayScott { feed: f32, kill: f32 },
+    // u' = a - (b + 1) u + u^2 v, v' = b u - u^2 v
+    Brusselator { a: f32, b: f32 },
+    // u' = u - u^3 - v, v' = epsilon (u - a1 v - a0)
+    FitzHughNagumo { epsilon: f32, a0: f32, a1: f32 },
+}
+
+#[derive(Clone, Copy)]
+#[derive(Default)]
+pub enum TimeStepping {
+    #[default]
+    Explicit,
+    ADI,
+}
+
+impl TimeStepping {
+    pub fn from_string(str: String) -> Option<Self> {
+        match str.as_str() {
+            "explicit" => Some(Self::Explicit),
+            "adi" => Some(Self::ADI),
+            _ => None,
+        }
+    }
+}
+
+impl ReactionDiffusionModel {
+    pub fn reaction(&self, [u, v]: Concentrations) -> Concentrations {
+        match *self {
+            Self::GrayScott { feed, kill } => [-u * v * v + feed * (1f32 - u), u * v * v - (feed + kill) * v],
+            Self::Brusselator { a, b } => [a - (b + 1f32) * u + u * u * v, b * u - u * u * v],
+            Self::FitzHughNagumo { epsilon, a0, a1 } => [u - u * u * u - v, epsilon * (u - a1 * v - a0)],
+        }
+    }
+
+    // homogeneous steady state, the one perturbed by the initial state
+    pub fn steady_state(&self) -> Concentrations {
+        match *self {
+            Self::GrayScott { .. } => [1f32, 0f32],
+            Self::Brusselator { a, b } => [a, b / a],
+            Self::FitzHughNagumo { a0, a1, .. } => {
+                // root of u - u^3 - (u - a0) / a1 by Newton's method, it is unique for a1 < 1
+                let mut u = 0f32;
+                for _ in 0..50 {
+                    let (f, df) = (u - u.powi(3) - (u - a0) / a1, 1f32 - 3f32 * u * u - 1f32 / a1);
+                    u -= f / df;
+                }
+
+                [u, (u - a0) / a1]
+            },
+        }
+    }
+
+    // Steady state with small random perturbations. The trivial state of Gray-Scott is
+    // stable, so it is perturbed by a few squares of reactants instead.
+    pub fn initial_state(&self, size: (u32, u32), seed: u64) -> ConcentrationLattice {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(seed);
+        let steady_state = self.steady_state();
+
+        let mut lattice = Lattice::fill(size.0, size.1, steady_state);
+        match self {
+            Self::GrayScott { .. } => {
+                let side = u32::min(8, u32::min(size.0, size.1));
+                for _ in 0..u32::max(1, size.0 * size.1 / 1000) {
+                    let (x, y) = (rng.gen_range(0..=size.0 - side), rng.gen_range(0..=size.1 - side));
+                    for (x, y) in (x..x + side).flat_map(|x| (y..y + side).map(move |y| (x, y))) {
+                        lattice.set(x, y, [0.5 + rng.gen_range(-0.01..0.01), 0.25 + rng.gen_range(-0.01..0.01)]);
+                    }
+                }
+            },
+            _ => {
+                for cell in lattice.cells.iter_mut() {
+                    *cell = [cell[0] + rng.gen_range(-0.01..0.01), cell[1] + rng.gen_range(-0.01..0.01)];
+                }
+            },
+        }
+
+        lattice
+    }
+}
+
+// Finite difference solver of the two species reaction-diffusion equations
+//
+//   du/dt = D_u lap(u) + f(u, v), dv/dt = D_v lap(v) + g(u, v)
+//
+// on a grid of spacing `space_step`, with the five points laplacian.
+//
+// The explicit (forward Euler) stepping needs dt < h^2 / (4 D); the semi-implicit
+// Peaceman-Rachford ADI stepping solves the diffusion implicitly along the rows, then
+// along the columns, so that only the reactions limit the time step.
+//
+// The grid is sampled every `sample_interval` units of time.
+pub struct ReactionDiffusion<B> {
+    model: ReactionDiffusionModel,
+    diffusion: [f32; 2],
+    time_step: f32,
+    space_step: f32,
+    stepping: TimeStepping,
+    steps_per_sample: u32,
+
+    state: Option<ConcentrationLattice>,
+    initial_state: ConcentrationLattice,
+    samples: u32,
+    _phantom: PhantomData<B>,
+}
+
+impl<B> Clone for ReactionDiffusion<B> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            initial_state: self.initial_state.clone(),
+            ..*self
+        }
+    }
+}
+
+impl<B> ReactionDiffusion<B>
+    where B: BoundaryCell<Concentrations> + LatticeBoundary
+{
+    pub fn new(model: ReactionDiffusionModel, initial_state: ConcentrationLattice, diffusion: [f32; 2], time_step: f32, space_step: f32, stepping: TimeStepping, sample_interval: f32) -> Result<Self, String> {
+        // otherwise the steps never reach the next sample, or divide by zero
+        if !(time_step.is_finite() && time_step > 0f32) {
+            return Err(format!("time step {time_step} is not positive"));
+        }
+        if !(space_step.is_finite() && space_step > 0f32) {
+            return Err(format!("space step {space_step} is not positive"));
+        }
+
+        Ok(Self {
+            model,
+            diffusion,
+            time_step,
+            space_step,
+            stepping,
+            steps_per_sample: u32::max(1, (sample_interval / time_step).round() as u32),
+            state: None,
+            initial_state,
+            samples: 0,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn laplacian(&self, lattice: &ConcentrationLattice, x: u32, y: u32) -> Concentrations {
+        let [u, v] = lattice.cells[(x + y * lattice.size.0) as usize];
+        let h2 = self.space_step * self.space_step;
+
+        VonNeumannNeighborhood::OFFSETS.iter()
+            .map(|(dx, dy)| B::get_cell(lattice, x as i64 + *dx as i64, y as i64 + *dy as i64))
+            .fold([0f32; 2], |[lu, lv], [nu, nv]| [lu + (nu - u) / h2, lv + (nv - v) / h2])
+    }
+
+    fn explicit_step(&self, lattice: ConcentrationLattice) -> ConcentrationLattice {
+        let (dt, [du, dv]) = (self.time_step, self.diffusion);
+
+        Lattice::from_fn(lattice.size.0, lattice.size.1, |x, y| {
+            let [u, v] = lattice.cells[(x + y * lattice.size.0) as usize];
+            let [lu, lv] = self.laplacian(&lattice, x, y);
+            let [fu, fv] = self.model.reaction([u, v]);
+
+            Ok([u + dt * (du * lu + fu), v + dt * (dv * lv + fv)])
+        }).unwrap()
+    }
+
+    fn adi_step(&self, lattice: ConcentrationLattice) -> ConcentrationLattice {
+        let lattice = self.adi_half_step(&lattice, false);
+        self.adi_half_step(&lattice, true)
+    }
+
+    // implicit along the rows (or the columns), explicit along the other direction,
+    // with the reactions integrated explicitly over the half step
+    fn adi_half_step(&self, lattice: &ConcentrationLattice, implicit_columns: bool) -> ConcentrationLattice {
+        let size_x = lattice.size.0 as i64;
+        let half_step = self.time_step / 2f32;
+        let (lines, length) = if implicit_columns { (lattice.size.0, lattice.size.1) } else { (lattice.size.1, lattice.size.0) };
+        let coordinates = |line: u32, position: i64| if implicit_columns { (line as i64, position) } else { (position, line as i64) };
+        let (across_x, across_y) = if implicit_columns { (1, 0) } else { (0, 1) };
+
+        let periodic = B::wrap(-1, -1, lattice.size).is_some();
+        let coefficients = self.diffusion.map(|diffusion| diffusion * half_step / (self.space_step * self.space_step));
+        let solvers = coefficients.map(|coefficient| LineSolver::new(coefficient, length as usize, periodic));
+        let mut next = lattice.clone();
+
+        for line in 0..lines {
+            // explicit diffusion across the line and reactions
+            let mut rhs: [Vec<f32>; 2] = [Vec::with_capacity(length as usize), Vec::with_capacity(length as usize)];
+            for position in 0..length as i64 {
+                let (x, y) = coordinates(line, position);
+                let cell = lattice.cells[(x + y * size_x) as usize];
+                let before = B::get_cell(lattice, x - across_x, y - across_y);
+                let after = B::get_cell(lattice, x + across_x, y + across_y);
+                let reaction = self.model.reaction(cell);
+
+                for species in 0..2 {
+                    let diffusion = coefficients[species] * (before[species] - 2f32 * cell[species] + after[species]);
+                    rhs[species].push(cell[species] + diffusion + half_step * reaction[species]);
+                }
+            }
+
+            // implicit diffusion along the line
+            let (first, last) = (coordinates(line, -1), coordinates(line, length as i64));
+            let (first, last) = (B::get_cell(lattice, first.0, first.1), B::get_cell(lattice, last.0, last.1));
+            for (species, rhs) in rhs.iter_mut().enumerate() {
+                if !periodic {
+                    rhs[0] += coefficients[species] * first[species];
+                    rhs[length as usize - 1] += coefficients[species] * last[species];
+                }
+
+                solvers[species].solve(rhs);
+            }
+
+            let [u, v] = rhs;
+            for (position, cell) in u.into_iter().zip(v).enumerate() {
+                let (x, y) = coordinates(line, position as i64);
+                next.cells[(x + y * size_x) as usize] = [cell.0, cell.1];
+            }
+        }
+
+        next
+    }
+
+    fn step(&self, lattice: ConcentrationLattice) -> ConcentrationLattice {
+        match self.stepping {
+            TimeStepping::Explicit => self.explicit_step(lattice),
+            TimeStepping::ADI => self.adi_step(lattice),
+        }
+    }
+}
+
+// Implicit diffusion along a line of `length` cells, (1 + 2c) x_i - c (x_{i-1} + x_{i+1}) = rhs_i,
+// the same for every line of a half step: the LU factorization of the tridiagonal matrix
+// is computed once, then each line is solved by the forward and backward substitutions.
+//
+// Past the ends of the line x_{-1} = x_n = 0, the fixed boundary being moved into the
+// right hand side, or x_{-1} = x_{n-1} and x_n = x_0 on a periodic line, solved by the
+// Sherman-Morrison correction of the tridiagonal system without the corners.
+struct LineSolver {
+    off_diagonal: f32,
+    upper: Vec<f32>,
+    inverse_pivots: Vec<f32>,
+    // correction vector and the corner weight of a periodic line
+    periodic: Option<(Vec<f32>, f32)>,
+}
+
+impl LineSolver {
+    fn new(coefficient: f32, length: usize, periodic: bool) -> Self {
+        let (diagonal, off_diagonal) = (1f32 + 2f32 * coefficient, -coefficient);
+
+        if !periodic || length < 3 {
+            // both neighbours of a cell of a periodic line of two cells are the other cell
+            let off_diagonal = if periodic && length == 2 { 2f32 * off_diagonal } else { off_diagonal };
+            let diagonal = if periodic && length == 1 { 1f32 } else { diagonal };
+            return Self::factorize(off_diagonal, &vec![diagonal; length]);
+        }
+
+        let gamma = -diagonal;
+        let mut diagonals = vec![diagonal; length];
+        diagonals[0] -= gamma;
+        diagonals[length - 1] -= off_diagonal * off_diagonal / gamma;
+
+        let mut solver = Self::factorize(off_diagonal, &diagonals);
+        let mut correction = vec![0f32; length];
+        correction[0] = gamma;
+        correction[length - 1] = off_diagonal;
+        solver.substitute(&mut correction);
+
+        solver.periodic = Some((correction, gamma));
+        solver
+    }
+
+    fn factorize(off_diagonal: f32, diagonal: &[f32]) -> Self {
+        let mut upper = vec![0f32; diagonal.len()];
+        let mut inverse_pivots = vec![0f32; diagonal.len()];
+
+        for i in 0..diagonal.len() {
+            let pivot = if i == 0 { diagonal[0] } else { diagonal[i] - off_diagonal * upper[i - 1] };
+            inverse_pivots[i] = 1f32 / pivot;
+            upper[i] = off_diagonal * inverse_pivots[i];
+        }
+
+        Self { off_diagonal, upper, inverse_pivots, periodic: None }
+    }
+
+    fn substitute(&self, rhs: &mut [f32]) {
+        let n = rhs.len();
+
+        rhs[0] *= self.inverse_pivots[0];
+        for i in 1..n {
+            rhs[i] = (rhs[i] - self.off_diagonal * rhs[i - 1]) * self.inverse_pivots[i];
+        }
+
+        for i in (0..n - 1).rev() {
+            rhs[i] -= self.upper[i] * rhs[i + 1];
+        }
+    }
+
+    // solves the line in place
+    fn solve(&self, rhs: &mut [f32]) {
+        if rhs.is_empty() {
+            return;
+        }
+
+        self.substitute(rhs);
+
+        if let Some((correction, gamma)) = &self.periodic {
+            let n = rhs.len();
+            let weight = self.off_diagonal / gamma;
+            let factor = (rhs[0] + weight * rhs[n - 1]) / (1f32 + correction[0] + weight * correction[n - 1]);
+
+            for (x, z) in rhs.iter_mut().zip(correction) {
+                *x -= factor * z;
+            }
+        }
+    }
+}
+
+impl<B> Iterator for ReactionDiffusion<B>
+    where B: BoundaryCell<Concentrations> + LatticeBoundary
+{
+    type Item = (f32, ConcentrationLattice);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let state = match self.state.take() {
+            Some(state) => (0..self.steps_per_sample).fold(state, |state, _| self.step(state)),
+            None => self.initial_state.clone(),
+        };
+
+        let time = (self.samples * self.steps_per_sample) as f32 * self.time_step;
+        self.samples += 1;
+        self.state = Some(state.clone());
+
+        Some((time, state))
+    }
+}
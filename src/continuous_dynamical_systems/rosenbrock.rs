@@ -0,0 +1,167 @@
+use nalgebra::{allocator::Allocator, DefaultAllocator, Dim, DMatrix, DVector, Dyn, OVector, U1, LU};
+use ode_solvers::System;
+use ode_solvers::dop_shared::{IntegrationError, SolverResult, Stats};
+
+// Systems that can provide the Jacobian matrix J[i][j] = d f_i / d y_j.
+pub trait Jacobian<V>: System<f32, V> {
+    fn jacobian(&self, x: f32, y: &V, jacobian: &mut DMatrix<f32>);
+}
+
+// Forward difference approximation of the Jacobian, for systems without an analytical one.
+#[derive(Clone)]
+pub struct NumericalJacobian<F>(pub F);
+
+impl<D: Dim, F> System<f32, OVector<f32, D>> for NumericalJacobian<F>
+    where F: System<f32, OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+{
+    fn system(&self, x: f32, y: &OVector<f32, D>, dy: &mut OVector<f32, D>) {
+        self.0.system(x, y, dy);
+    }
+}
+
+impl<D: Dim, F> Jacobian<OVector<f32, D>> for NumericalJacobian<F>
+    where F: System<f32, OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+{
+    fn jacobian(&self, x: f32, y: &OVector<f32, D>, jacobian: &mut DMatrix<f32>) {
+        let mut dy = y.clone();
+        self.0.system(x, y, &mut dy);
+
+        let mut perturbed_y = y.clone();
+        let mut perturbed_dy = y.clone();
+        for j in 0..y.len() {
+            let step = f32::EPSILON.sqrt() * y[j].abs().max(1f32);
+            perturbed_y[j] = y[j] + step;
+            self.0.system(x, &perturbed_y, &mut perturbed_dy);
+            perturbed_y[j] = y[j];
+
+            for i in 0..y.len() {
+                jacobian[(i, j)] = (perturbed_dy[i] - dy[i]) / step;
+            }
+        }
+    }
+}
+
+// Linearly implicit Rosenbrock method of order 2(3) by Shampine and Reichelt (MATLAB's
+// ode23s), L-stable and with adaptive step size, meant for stiff systems.
+pub struct Ros23<D: Dim, F>
+    where DefaultAllocator: Allocator<f32, D>
+{
+    f: F,
+    x: f32,
+    x_end: f32,
+    step_size: f32,
+    y: OVector<f32, D>,
+    rtol: f32,
+    atol: f32,
+    results: SolverResult<f32, OVector<f32, D>>,
+    stats: Stats,
+}
+
+impl<D: Dim, F> Ros23<D, F>
+    where F: Jacobian<OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+{
+    const MAX_STEPS: u32 = 100000;
+
+    pub fn new(f: F, x: f32, x_end: f32, step_size: f32, y: OVector<f32, D>, rtol: f32, atol: f32) -> Self {
+        Self {
+            f, x, x_end, step_size, y, rtol, atol,
+            results: SolverResult::default(),
+            stats: Stats { num_eval: 0, accepted_steps: 0, rejected_steps: 0 },
+        }
+    }
+
+    pub fn results(&self) -> &SolverResult<f32, OVector<f32, D>> {
+        &self.results
+    }
+
+    pub fn integrate(&mut self) -> Result<Stats, IntegrationError> {
+        let d = 1f32 / (2f32 + std::f32::consts::SQRT_2);
+        let e32 = 6f32 + std::f32::consts::SQRT_2;
+
+        let n = self.y.len();
+        let identity = DMatrix::<f32>::identity(n, n);
+        let mut jacobian = DMatrix::<f32>::zeros(n, n);
+
+        self.results.push(self.x, self.y.clone());
+
+        while self.x < self.x_end {
+            if self.stats.accepted_steps + self.stats.rejected_steps >= Self::MAX_STEPS {
+                return Err(IntegrationError::MaxNumStepReached { x: self.x as f64, n_step: Self::MAX_STEPS });
+            }
+
+            let y = DVector::from_iterator(n, self.y.iter().cloned());
+            let f0 = self.eval(self.x, &y);
+            self.f.jacobian(self.x, &self.y, &mut jacobian);
+
+            // time derivative of the system, zero for autonomous systems
+            let time_step = f32::EPSILON.sqrt() * self.x.abs().max(1f32);
+            let time_derivative = (self.eval(self.x + time_step, &y) - &f0) / time_step;
+
+            loop {
+                let h = f32::min(self.step_size, self.x_end - self.x);
+                if h <= f32::EPSILON * self.x.abs().max(1f32) {
+                    return Err(IntegrationError::StepSizeUnderflow { x: self.x as f64 });
+                }
+
+                // W = I - h d J
+                let w: LU<f32, Dyn, Dyn> = (&identity - &jacobian * (h * d)).lu();
+                if !w.is_invertible() {
+                    self.step_size = h / 2f32;
+                    self.stats.rejected_steps += 1;
+                    continue;
+                }
+                let solve = |b: DVector<f32>| solve_lu(&w, &b);
+
+                let k1 = solve(&f0 + &time_derivative * (h * d));
+                let f1 = self.eval(self.x + h / 2f32, &(&y + &k1 * (h / 2f32)));
+                let k2 = solve(&f1 - &k1) + &k1;
+                let new_y = &y + &k2 * h;
+                let f2 = self.eval(self.x + h, &new_y);
+                let k3 = solve(&f2 - (&k2 - &f1) * e32 - (&k1 - &f0) * 2f32 + &time_derivative * (h * d));
+
+                let error = (&k1 - &k2 * 2f32 + &k3) * (h / 6f32);
+                let error =
+                    if error.iter().all(|error| error.is_finite()) {
+                        error.iter().zip(y.iter().zip(new_y.iter()))
+                            .map(|(error, (y, new_y))| error.abs() / (self.atol + self.rtol * f32::max(y.abs(), new_y.abs())))
+                            .fold(0f32, f32::max)
+                    } else {
+                        f32::INFINITY
+                    };
+
+                let factor = 0.8f32 * error.powf(-1f32 / 3f32);
+                if error <= 1f32 {
+                    self.x = if h == self.x_end - self.x { self.x_end } else { self.x + h };
+                    self.y = self.to_state(&new_y);
+                    self.results.push(self.x, self.y.clone());
+                    self.step_size = h * if factor.is_finite() { factor.min(5f32) } else { 5f32 };
+                    self.stats.accepted_steps += 1;
+                    break;
+                }
+
+                self.step_size = h * if factor.is_finite() { factor.max(0.2f32) } else { 0.2f32 };
+                self.stats.rejected_steps += 1;
+            }
+        }
+
+        Ok(self.stats)
+    }
+
+    fn eval(&mut self, x: f32, y: &DVector<f32>) -> DVector<f32> {
+        let y = self.to_state(y);
+        let mut dy = y.clone();
+        self.f.system(x, &y, &mut dy);
+        self.stats.num_eval += 1;
+
+        DVector::from_iterator(dy.len(), dy.iter().cloned())
+    }
+
+    fn to_state(&self, y: &DVector<f32>) -> OVector<f32, D> {
+        OVector::from_iterator_generic(self.y.shape_generic().0, U1, y.iter().cloned())
+    }
+}
+
+// kept outside of the generic impl, whose allocator bound on D confuses the inference of `LU::solve`
+fn solve_lu(lu: &LU<f32, Dyn, Dyn>, b: &DVector<f32>) -> DVector<f32> {
+    lu.solve(b).unwrap()
+}
@@ -1,5 +1,7 @@
 use ode_solvers::*;
 
+use super::delay::DelaySystem;
+
 pub struct SIRModel {
     pub initial_state: Vector3<f32>,
     pub ode: SIRModelODE,
@@ -34,4 +36,52 @@ impl ode_solvers::System<f32, Vector3<f32>> for SIRModelODE {
 
         dy[0] = ds; dy[1] = di; dy[2] = dr;
     }
-}
\ No newline at end of file
+}
+
+// SIR model with an incubation period: the individuals infected at time t become
+// infectious at t + incubation_delay, the ones still incubating being 1 - S - I - R.
+pub struct DelayedSIRModel {
+    pub initial_state: Vector3<f32>,
+    pub ode: DelayedSIRModelODE,
+    pub max_time: f32,
+}
+
+#[derive(Clone)]
+pub struct DelayedSIRModelODE {
+    infection_coefficient: f32,
+    recovery_coefficient: f32,
+    incubation_delay: f32,
+}
+
+impl DelayedSIRModel {
+    pub fn new(initial_population: (f32, f32, f32), infection_coefficient: f32, recovery_coefficient: f32, incubation_delay: f32, max_time: f32) -> Self {
+        Self {
+            initial_state: [initial_population.0, initial_population.1, initial_population.2].into(),
+            ode: DelayedSIRModelODE { infection_coefficient, recovery_coefficient, incubation_delay },
+            max_time
+        }
+    }
+
+    // no infection before the initial time, when the initial infected appear
+    pub fn history(&self) -> impl Fn(f32) -> Vector3<f32> {
+        let initial_state = self.initial_state;
+        move |x| if x < 0f32 { Vector3::new(initial_state[0], 0f32, initial_state[2]) } else { initial_state }
+    }
+}
+
+impl DelaySystem<Vector3<f32>> for DelayedSIRModelODE {
+    fn delays(&self) -> Vec<f32> {
+        vec![self.incubation_delay]
+    }
+
+    fn system(&self, _: f32, y: &Vector3<f32>, delayed: &[Vector3<f32>], dy: &mut Vector3<f32>) {
+        let (s, i) = (y[0], y[1]);
+        let (delayed_s, delayed_i) = (delayed[0][0], delayed[0][1]);
+
+        let ds = - self.infection_coefficient * s * i;
+        let di = self.infection_coefficient * delayed_s * delayed_i - self.recovery_coefficient * i;
+        let dr = self.recovery_coefficient * i;
+
+        dy[0] = ds; dy[1] = di; dy[2] = dr;
+    }
+}
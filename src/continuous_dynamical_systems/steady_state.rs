@@ -0,0 +1,249 @@
+use nalgebra::{allocator::Allocator, Complex, DefaultAllocator, Dim, DMatrix, DVector, OVector, Schur, U1};
+use crate::continuous_dynamical_systems::Jacobian;
+
+// Equilibria y* of an autonomous system, f(y*) = 0, found by damped Newton iteration
+// from several starting points and classified by the eigenvalues of the Jacobian.
+//
+// Systems without an analytical Jacobian can be wrapped in `NumericalJacobian`.
+#[derive(Clone)]
+pub struct SteadyStateFinder {
+    tolerance: f32,
+    max_iterations: u32,
+    non_negative: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct SteadyState {
+    pub state: Vec<f32>,
+    pub eigenvalues: Vec<Complex<f32>>,
+    pub stability: Stability,
+}
+
+// Linear stability, from the signs of the real parts of the eigenvalues and the
+// presence of imaginary parts (oscillations around the equilibrium).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stability {
+    StableNode,
+    UnstableNode,
+    Saddle,
+    StableFocus,
+    UnstableFocus,
+    // purely imaginary eigenvalues: closed orbits for the linearization
+    Center,
+    // some real eigenvalue is zero, linearization is inconclusive
+    NonHyperbolic,
+    // the eigenvalues could not be computed
+    Undetermined,
+}
+
+impl Default for SteadyStateFinder {
+    fn default() -> Self {
+        Self { tolerance: 1.0e-4, max_iterations: 100, non_negative: false }
+    }
+}
+
+impl SteadyStateFinder {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn max_iterations(mut self, max_iterations: u32) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    // discard equilibria with negative components, as for population models
+    pub fn non_negative(mut self, non_negative: bool) -> Self {
+        self.non_negative = non_negative;
+        self
+    }
+
+    // regular grid of starting points over the box [lower, upper]
+    pub fn grid<D: Dim>(lower: &OVector<f32, D>, upper: &OVector<f32, D>, points_per_axis: usize) -> Vec<OVector<f32, D>>
+        where DefaultAllocator: Allocator<f32, D>
+    {
+        let points_per_axis = points_per_axis.max(2);
+        let total = points_per_axis.pow(lower.len() as u32);
+
+        (0..total)
+            .map(|mut index| {
+                let mut point = lower.clone();
+                for (axis, value) in point.iter_mut().enumerate() {
+                    let step = (index % points_per_axis) as f32 / (points_per_axis - 1) as f32;
+                    *value += (upper[axis] - lower[axis]) * step;
+                    index /= points_per_axis;
+                }
+
+                point
+            })
+            .collect()
+    }
+
+    // distinct equilibria reached from the starting points, in order of discovery
+    pub fn find<D: Dim, F>(&self, system: &F, starting_points: &[OVector<f32, D>]) -> Vec<SteadyState>
+        where F: Jacobian<OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+    {
+        let mut steady_states: Vec<SteadyState> = Vec::new();
+
+        for starting_point in starting_points {
+            let Some(state) = self.newton(system, starting_point.clone()) else {
+                continue;
+            };
+
+            if self.non_negative && state.iter().any(|value| *value < -self.tolerance.sqrt()) {
+                continue;
+            }
+
+            let scale = 1f32 + state.amax();
+            let duplicate = steady_states.iter()
+                .any(|other| other.state.iter().zip(state.iter()).all(|(a, b)| (a - b).abs() <= self.tolerance.sqrt() * scale));
+            if duplicate {
+                continue;
+            }
+
+            steady_states.push(self.classify(system, state));
+        }
+
+        steady_states
+    }
+
+    pub fn classify<D: Dim, F>(&self, system: &F, state: OVector<f32, D>) -> SteadyState
+        where F: Jacobian<OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+    {
+        let n = state.len();
+        let mut jacobian = DMatrix::<f32>::zeros(n, n);
+        system.jacobian(0f32, &state, &mut jacobian);
+
+        let eigenvalues = eigenvalues(jacobian);
+        let stability = Stability::from_eigenvalues(&eigenvalues);
+
+        SteadyState { state: state.iter().cloned().collect(), eigenvalues, stability }
+    }
+
+    fn newton<D: Dim, F>(&self, system: &F, mut y: OVector<f32, D>) -> Option<OVector<f32, D>>
+        where F: Jacobian<OVector<f32, D>>, DefaultAllocator: Allocator<f32, D>
+    {
+        let n = y.len();
+        let mut jacobian = DMatrix::<f32>::zeros(n, n);
+        let residual = |y: &OVector<f32, D>| {
+            let mut dy = y.clone();
+            system.system(0f32, y, &mut dy);
+            DVector::from_iterator(n, dy.iter().cloned())
+        };
+
+        let mut f = residual(&y);
+        for _ in 0..self.max_iterations {
+            system.jacobian(0f32, &y, &mut jacobian);
+            if !f.iter().chain(jacobian.iter()).all(|value| value.is_finite()) {
+                return None;
+            }
+
+            // the size of f is measured against the size of its linear part
+            let scale = 1f32 + jacobian.amax() * y.amax();
+            if f.amax() <= self.tolerance * scale {
+                return Some(y);
+            }
+
+            // least squares step, so that singular Jacobians (e.g. conserved quantities) do not stop the iteration
+            let step = solve_least_squares(jacobian.clone(), -&f, self.tolerance * self.tolerance);
+
+            // backtracking line search on the size of the residual
+            let mut damping = 1f32;
+            loop {
+                let candidate = y.clone() + OVector::from_iterator_generic(y.shape_generic().0, U1, step.iter().map(|dx| dx * damping));
+                let candidate_f = residual(&candidate);
+
+                if candidate_f.amax() < f.amax() || damping < 1.0e-3 {
+                    y = candidate;
+                    f = candidate_f;
+                    break;
+                }
+
+                damping /= 2f32;
+            }
+        }
+
+        None
+    }
+}
+
+impl Stability {
+    pub fn from_eigenvalues(eigenvalues: &[Complex<f32>]) -> Self {
+        if eigenvalues.is_empty() {
+            return Self::Undetermined;
+        }
+
+        // eigenvalues come from a finite precision Jacobian, compare them to the largest one
+        let epsilon = 1.0e-3 * eigenvalues.iter().map(|eigenvalue| eigenvalue.norm()).fold(1.0e-3, f32::max);
+
+        let positive = eigenvalues.iter().filter(|eigenvalue| eigenvalue.re > epsilon).count();
+        let negative = eigenvalues.iter().filter(|eigenvalue| eigenvalue.re < -epsilon).count();
+        let oscillating = eigenvalues.iter().any(|eigenvalue| eigenvalue.im.abs() > epsilon);
+        let real_zero = eigenvalues.iter().any(|eigenvalue| eigenvalue.re.abs() <= epsilon && eigenvalue.im.abs() <= epsilon);
+
+        match (positive, negative) {
+            _ if real_zero => Self::NonHyperbolic,
+            (0, 0) => Self::Center,
+            (0, _) if positive + negative < eigenvalues.len() => Self::Center,
+            (0, _) if oscillating => Self::StableFocus,
+            (0, _) => Self::StableNode,
+            (_, 0) if oscillating => Self::UnstableFocus,
+            (_, 0) => Self::UnstableNode,
+            _ => Self::Saddle,
+        }
+    }
+
+    pub fn is_stable(&self) -> bool {
+        matches!(self, Self::StableNode | Self::StableFocus)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::StableNode => "stable node",
+            Self::UnstableNode => "unstable node",
+            Self::Saddle => "saddle",
+            Self::StableFocus => "stable focus",
+            Self::UnstableFocus => "unstable focus",
+            Self::Center => "center",
+            Self::NonHyperbolic => "non-hyperbolic",
+            Self::Undetermined => "undetermined",
+        }
+    }
+}
+
+impl std::fmt::Display for Stability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::fmt::Display for SteadyState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.iter().map(|value| format!("{value:.4}")).collect::<Vec<_>>().join(", ");
+        let eigenvalues = self.eigenvalues.iter()
+            .map(|eigenvalue| format!("{:.4}{:+.4}i", eigenvalue.re, eigenvalue.im))
+            .collect::<Vec<_>>().join(", ");
+
+        write!(f, "({state}): {}, eigenvalues [{eigenvalues}]", self.stability)
+    }
+}
+
+// kept outside of the generic functions, whose allocator bound on D confuses the type inference of nalgebra's solvers
+fn solve_least_squares(jacobian: DMatrix<f32>, b: DVector<f32>, epsilon: f32) -> DVector<f32> {
+    jacobian.svd(true, true).solve(&b, epsilon).unwrap()
+}
+
+pub(crate) fn eigenvalues(jacobian: DMatrix<f32>) -> Vec<Complex<f32>> {
+    if !jacobian.iter().all(|value| value.is_finite()) {
+        return Vec::new();
+    }
+
+    Schur::try_new(jacobian, f32::EPSILON, 10000)
+        .map(|schur| schur.complex_eigenvalues().iter().cloned().collect())
+        .unwrap_or_default()
+}
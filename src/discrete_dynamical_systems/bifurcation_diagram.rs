@@ -0,0 +1,82 @@
+use super::logistic_equation::LogisticEquation;
+
+// One-parameter bifurcation diagram of the logistic map: for each birth rate in the
+// range the map is iterated, the transient is discarded and the following points,
+// which lie on the attractor, are recorded. The Lyapunov exponent is estimated along
+// the same points as the mean of ln|f'(N(t))|: negative on periodic orbits, positive
+// in the chaotic regions.
+#[derive(Clone)]
+pub struct LogisticBifurcation {
+    initial_population: f32,
+    carrying_capacity: f32,
+    min_birth_rate: f32,
+    max_birth_rate: f32,
+    samples: usize,
+    transient: usize,
+    recorded: usize,
+}
+
+#[derive(Clone)]
+pub struct BifurcationPoint {
+    pub birth_rate: f32,
+    pub attractor: Vec<f32>,
+    pub lyapunov_exponent: f32,
+}
+
+impl LogisticBifurcation {
+    pub fn new(initial_population: f32, carrying_capacity: f32, (min_birth_rate, max_birth_rate): (f32, f32), samples: usize) -> Self {
+        Self {
+            initial_population,
+            carrying_capacity,
+            min_birth_rate,
+            max_birth_rate,
+            samples,
+            transient: 500,
+            recorded: 200,
+        }
+    }
+
+    pub fn transient(mut self, transient: usize) -> Self {
+        self.transient = transient;
+        self
+    }
+
+    pub fn recorded(mut self, recorded: usize) -> Self {
+        self.recorded = recorded;
+        self
+    }
+
+    pub fn birth_rates(&self) -> impl Iterator<Item = f32> + Clone {
+        let (min, max) = (self.min_birth_rate, self.max_birth_rate);
+        let intervals = usize::max(self.samples, 2) - 1;
+
+        (0..=intervals).map(move |i| min + (max - min) * i as f32 / intervals as f32)
+    }
+
+    pub fn compute(&self) -> Vec<BifurcationPoint> {
+        self.birth_rates()
+            .map(|birth_rate| self.compute_at(birth_rate))
+            .collect()
+    }
+
+    pub fn compute_at(&self, birth_rate: f32) -> BifurcationPoint {
+        let model = LogisticEquation::new(self.initial_population, birth_rate, self.carrying_capacity);
+
+        let attractor: Vec<f32> = model.clone()
+            .map(|(_, population)| population)
+            .skip(self.transient)
+            .take(self.recorded)
+            .collect();
+
+        // the population escapes to -infinity for birth rates above 4
+        if attractor.iter().any(|population| !population.is_finite()) {
+            return BifurcationPoint { birth_rate, attractor: Vec::new(), lyapunov_exponent: f32::INFINITY };
+        }
+
+        let lyapunov_exponent = attractor.iter()
+            .map(|population| model.derivative(*population).abs().max(f32::MIN_POSITIVE).ln())
+            .sum::<f32>() / attractor.len().max(1) as f32;
+
+        BifurcationPoint { birth_rate, attractor, lyapunov_exponent }
+    }
+}
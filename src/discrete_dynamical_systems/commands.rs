@@ -0,0 +1,217 @@
+use crate::prelude::*;
+use crate::cli::{Arguments, Command, Flag};
+use super::prelude::*;
+use super::pages;
+
+pub(crate) const COMMANDS: &[Command] = &[
+    Command {
+        name: "dds-linear-birth-death",
+        description: "discrete linear birth and death model",
+        flags: &[
+            Flag { name: "max_time", default: "40", description: "simulated time" },
+            Flag { name: "initial_population", default: "50", description: "initial population" },
+            Flag { name: "birth_rate", default: "1", description: "birth rate" },
+            Flag { name: "death_rate", default: "0.1", description: "death rate" },
+            Flag { name: "graph", default: "normal", description: "chart of the png and svg formats: normal or phase" },
+        ],
+        run: linear_birth_death,
+        chart: Some(linear_birth_death_chart),
+    },
+    Command {
+        name: "dds-linear-birth-model",
+        description: "discrete linear birth model",
+        flags: &[
+            Flag { name: "max_time", default: "30", description: "simulated time" },
+            Flag { name: "time_step", default: "1", description: "time step" },
+            Flag { name: "initial_population", default: "1", description: "initial population" },
+            Flag { name: "offsprings_per_individual", default: "1", description: "offsprings per individual per period" },
+            Flag { name: "reproduction_period", default: "1", description: "reproduction period" },
+            Flag { name: "graph", default: "normal", description: "chart of the png and svg formats: normal or phase" },
+        ],
+        run: linear_birth_model,
+        chart: Some(linear_birth_model_chart),
+    },
+    Command {
+        name: "dds-linear-birth-migration",
+        description: "discrete linear birth model with migration",
+        flags: &[
+            Flag { name: "max_time", default: "100", description: "simulated time" },
+            Flag { name: "initial_population", default: "40", description: "initial population" },
+            Flag { name: "birth_death_rate", default: "1", description: "grow factor of the population" },
+            Flag { name: "migration_coefficient", default: "1", description: "migrants per step" },
+            Flag { name: "graph", default: "normal", description: "chart of the png and svg formats: normal or phase" },
+        ],
+        run: linear_birth_migration,
+        chart: Some(linear_birth_migration_chart),
+    },
+    Command {
+        name: "dds-logistic-equation",
+        description: "logistic map",
+        flags: &[
+            Flag { name: "max_time", default: "50", description: "simulated time" },
+            Flag { name: "initial_population", default: "50", description: "initial population" },
+            Flag { name: "birth_rate", default: "3", description: "birth rate" },
+            Flag { name: "carrying_capacity", default: "200", description: "carrying capacity" },
+            Flag { name: "graph", default: "normal", description: "chart of the png and svg formats: normal or phase" },
+        ],
+        run: logistic_equation,
+        chart: Some(logistic_equation_chart),
+    },
+    Command {
+        name: "dds-logistic-bifurcation",
+        description: "bifurcation diagram and Lyapunov exponent of the logistic map",
+        flags: &[
+            Flag { name: "initial_population", default: "30", description: "initial population" },
+            Flag { name: "carrying_capacity", default: "100", description: "carrying capacity" },
+            Flag { name: "min_birth_rate", default: "2.5", description: "lowest birth rate" },
+            Flag { name: "max_birth_rate", default: "4", description: "highest birth rate" },
+            Flag { name: "samples", default: "600", description: "number of birth rates" },
+            Flag { name: "transient", default: "500", description: "discarded steps" },
+            Flag { name: "recorded", default: "200", description: "recorded steps on the attractor" },
+        ],
+        run: logistic_bifurcation,
+        chart: Some(logistic_bifurcation_chart),
+    },
+    Command {
+        name: "dds-male-female-fish",
+        description: "discrete fish population with males and females",
+        flags: &[
+            Flag { name: "max_time", default: "50", description: "simulated time" },
+            Flag { name: "initial_female_pop", default: "16", description: "initial female population" },
+            Flag { name: "initial_male_pop", default: "23", description: "initial male population" },
+            Flag { name: "birth_rate", default: "1.3", description: "birth rate" },
+            Flag { name: "male_death_rate", default: "0.5", description: "death rate of the males" },
+            Flag { name: "carrying_capacity", default: "200", description: "carrying capacity" },
+        ],
+        run: male_female_fish,
+        chart: Some(male_female_fish_chart),
+    },
+];
+
+fn linear_birth_death(arguments: &Arguments) -> Result<String, String> {
+    let birth_rate = arguments.get::<f32>("birth_rate")? - arguments.get::<f32>("death_rate")?;
+    let model = LinearBirthModel::new(arguments.get("initial_population")?, birth_rate, 0f32, 1f32);
+
+    Ok(arguments.export(Simulation::new(model).time_limit(arguments.get("max_time")?), &["t", "N"]))
+}
+
+fn linear_birth_death_chart(arguments: &Arguments) -> Result<String, String> {
+    let params = pages::linear_birth_death_model::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .initial_population(arguments.get("initial_population")?)
+        .birth_rate(arguments.get("birth_rate")?)
+        .death_rate(arguments.get("death_rate")?);
+
+    arguments.render((arguments.get_string("graph")?.as_str(), params))
+}
+
+fn linear_birth_model(arguments: &Arguments) -> Result<String, String> {
+    let time_step: f32 = arguments.get("time_step")?;
+    let birth_rate = 1f32 + arguments.get::<f32>("offsprings_per_individual")? * time_step / arguments.get::<f32>("reproduction_period")?;
+    let model = LinearBirthModel::new(arguments.get("initial_population")?, birth_rate, 0f32, time_step);
+
+    Ok(arguments.export(Simulation::new(model).time_limit(arguments.get("max_time")?), &["t", "N"]))
+}
+
+fn linear_birth_model_chart(arguments: &Arguments) -> Result<String, String> {
+    let params = pages::linear_birth_model::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .time_step(arguments.get("time_step")?)
+        .initial_population(arguments.get("initial_population")?)
+        .offsprings_per_individual(arguments.get("offsprings_per_individual")?)
+        .reproduction_period(arguments.get("reproduction_period")?);
+
+    arguments.render((arguments.get_string("graph")?.as_str(), params))
+}
+
+fn linear_birth_migration(arguments: &Arguments) -> Result<String, String> {
+    let model = LinearBirthModel::new(
+        arguments.get("initial_population")?,
+        arguments.get("birth_death_rate")?,
+        arguments.get("migration_coefficient")?,
+        1f32,
+    );
+
+    Ok(arguments.export(Simulation::new(model).time_limit(arguments.get("max_time")?), &["t", "N"]))
+}
+
+fn linear_birth_migration_chart(arguments: &Arguments) -> Result<String, String> {
+    let params = pages::linear_birth_model_with_migration::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .initial_population(arguments.get("initial_population")?)
+        .birth_death_rate(arguments.get("birth_death_rate")?)
+        .migration_coefficient(arguments.get("migration_coefficient")?);
+
+    arguments.render((arguments.get_string("graph")?.as_str(), params))
+}
+
+fn logistic_equation(arguments: &Arguments) -> Result<String, String> {
+    let model = LogisticEquation::new(
+        arguments.get("initial_population")?,
+        arguments.get("birth_rate")?,
+        arguments.get("carrying_capacity")?,
+    );
+
+    Ok(arguments.export(Simulation::new(model).time_limit(arguments.get("max_time")?), &["t", "N"]))
+}
+
+fn logistic_equation_chart(arguments: &Arguments) -> Result<String, String> {
+    let params = pages::logistic_equation::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .initial_population(arguments.get("initial_population")?)
+        .birth_rate(arguments.get("birth_rate")?)
+        .carrying_capacity(arguments.get("carrying_capacity")?);
+
+    arguments.render((arguments.get_string("graph")?.as_str(), params))
+}
+
+// one row per recorded point of each attractor
+fn logistic_bifurcation(arguments: &Arguments) -> Result<String, String> {
+    let model = LogisticBifurcation::new(
+        arguments.get("initial_population")?,
+        arguments.get("carrying_capacity")?,
+        (arguments.get("min_birth_rate")?, arguments.get("max_birth_rate")?),
+        arguments.get("samples")?,
+    )
+    .transient(arguments.get("transient")?)
+    .recorded(arguments.get("recorded")?);
+
+    let points: Vec<(f32, (f32, f32))> = model.compute().into_iter()
+        .flat_map(|point| point.attractor.into_iter()
+            .map(move |population| (point.birth_rate, (population, point.lyapunov_exponent))))
+        .collect();
+
+    Ok(arguments.export(Simulation::new(points.into_iter()), &["r", "N", "lyapunov_exponent"]))
+}
+
+fn logistic_bifurcation_chart(arguments: &Arguments) -> Result<String, String> {
+    arguments.render(pages::logistic_bifurcation::Params::builder()
+        .initial_population(arguments.get("initial_population")?)
+        .carrying_capacity(arguments.get("carrying_capacity")?)
+        .min_birth_rate(arguments.get("min_birth_rate")?)
+        .max_birth_rate(arguments.get("max_birth_rate")?)
+        .samples(arguments.get("samples")?)
+        .transient(arguments.get("transient")?)
+        .recorded(arguments.get("recorded")?))
+}
+
+fn male_female_fish(arguments: &Arguments) -> Result<String, String> {
+    let model = MaleFemaleFishPopulation::new(
+        (arguments.get("initial_female_pop")?, arguments.get("initial_male_pop")?),
+        arguments.get("birth_rate")?,
+        arguments.get("male_death_rate")?,
+        arguments.get("carrying_capacity")?,
+    );
+
+    Ok(arguments.export(Simulation::new(model).time_limit(arguments.get("max_time")?), &["t", "female", "male"]))
+}
+
+fn male_female_fish_chart(arguments: &Arguments) -> Result<String, String> {
+    arguments.render(pages::male_female_fish_population::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .initial_female_population(arguments.get("initial_female_pop")?)
+        .initial_male_population(arguments.get("initial_male_pop")?)
+        .birth_rate(arguments.get("birth_rate")?)
+        .male_death_rate(arguments.get("male_death_rate")?)
+        .carrying_capacity(arguments.get("carrying_capacity")?))
+}
@@ -16,6 +16,11 @@ impl LogisticEquation {
             last_state: None,
         }
     }
+
+    // derivative of the map with respect to the population
+    pub fn derivative(&self, population: f32) -> f32 {
+        self.birth_rate * (1f32 - 2f32 * population / self.carrying_capacity)
+    }
 }
 
 impl Iterator for LogisticEquation {
@@ -1,17 +1,23 @@
-mod pages {
-    mod linear_birth_model;
-    mod linear_birth_death_model;
-    mod linear_birth_model_with_migration;
-    mod logistic_equation;
-    mod male_female_fish_population;
+pub mod pages {
+    pub mod linear_birth_model;
+    pub mod linear_birth_death_model;
+    pub mod linear_birth_model_with_migration;
+    pub mod logistic_equation;
+    pub mod logistic_bifurcation;
+    pub mod male_female_fish_population;
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod commands;
+
 mod prelude {
     pub use super::linear_birth_model::*;
     pub use super::logistic_equation::*;
+    pub use super::bifurcation_diagram::*;
     pub use super::male_female_fish_population::*;
 }
 
 mod linear_birth_model;
 mod logistic_equation;
+mod bifurcation_diagram;
 mod male_female_fish_population;
\ No newline at end of file
@@ -21,18 +21,10 @@ pub struct Params {
 #[wasm_bindgen(js_class = DDS_LBM)]
 impl Model {
     pub fn draw(canvas: HtmlCanvasElement, typ: String, params: Params) -> Result<(), JsValue> {
-        match GraphType::from_string(typ) {
-            Some(GraphType::Function) => 
-                draw_generic(Self::draw_function)(canvas, params),
-            Some(GraphType::PhaseGraph) =>
-                draw_generic(Self::draw_phase_graph)(canvas, params),
-            None =>
-                Err(format!("Graph type not supported").into())
-        }
+        draw_generic(|area, params| (typ.as_str(), params).draw(area))(canvas, params)
     }
 
-    fn draw_function(canvas: HtmlCanvasElement, params: Params) -> MyDrawResult<()> {
-        let area = draw_prelude(canvas)?;
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
         area.fill(&WHITE)?;
     
         let max_population_display =
@@ -41,7 +33,7 @@ impl Model {
         let x_axis_range = 0f32..params.max_time;
         let y_axis_range = 0..max_population_display;
     
-        let mut chart = ChartBuilder::on(&area)
+        let mut chart = ChartBuilder::on(area)
             .margin(20u32)
             .x_label_area_size(40u32)
             .y_label_area_size(60u32)
@@ -69,8 +61,7 @@ impl Model {
         Ok(())
     }
     
-    fn draw_phase_graph(canvas: HtmlCanvasElement, params: Params) -> MyDrawResult<()> {
-        let area = draw_prelude(canvas)?;
+    fn draw_phase_graph<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
         area.fill(&WHITE)?;
     
         let max_population_display =
@@ -80,7 +71,7 @@ impl Model {
         let x_axis_range = 0f32..max_population_display;
         let y_axis_range = 0f32..max_population_display;
     
-        let mut chart = ChartBuilder::on(&area)
+        let mut chart = ChartBuilder::on(area)
             .margin(20u32)
             .x_label_area_size(40u32)
             .y_label_area_size(60u32)
@@ -123,6 +114,21 @@ impl Model {
     }
 }
 
+impl Chart for (&str, Params) {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        let (typ, params) = self;
+
+        match GraphType::from_string(typ.to_owned()) {
+            Some(GraphType::Function) => 
+                Model::draw_function(area, params),
+            Some(GraphType::PhaseGraph) =>
+                Model::draw_phase_graph(area, params),
+            None =>
+                Err(format!("Graph type not supported").into())
+        }
+    }
+}
+
 #[wasm_bindgen(js_class = DDS_LBM_Params)]
 impl Params {
     pub fn builder() -> Self {
@@ -0,0 +1,151 @@
+use plotters::prelude::*;
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlCanvasElement;
+
+use crate::prelude::*;
+use crate::discrete_dynamical_systems::prelude::*;
+
+#[wasm_bindgen(js_name = DDS_LE_BIF)]
+pub struct Model { }
+
+#[wasm_bindgen(js_name = DDS_LE_BIF_Params)]
+#[derive(Default)]
+pub struct Params {
+    initial_population: f32,
+    carrying_capacity: f32,
+    min_birth_rate: f32,
+    max_birth_rate: f32,
+    samples: usize,
+    transient: usize,
+    recorded: usize,
+}
+
+#[wasm_bindgen(js_class = DDS_LE_BIF)]
+impl Model {
+    pub fn draw(canvas: HtmlCanvasElement, params: Params) -> Result<(), JsValue> {
+        draw_generic(Self::draw_function)(canvas, params)
+    }
+
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        // the exponent diverges to -infinity on superstable orbits, clamp it for display
+        const MIN_LYAPUNOV_DISPLAY: f32 = -4f32;
+
+        area.fill(&WHITE)?;
+
+        let (upper, lower) = area.split_vertically(area.dim_in_pixel().1 * 2 / 3);
+
+        let birth_rate_range = params.min_birth_rate..params.max_birth_rate;
+        let carrying_capacity = params.carrying_capacity;
+        let points = params.to_model().compute();
+
+        // bifurcation diagram
+        let mut chart = ChartBuilder::on(&upper)
+            .margin(20u32)
+            .x_label_area_size(30u32)
+            .y_label_area_size(60u32)
+            .build_cartesian_2d(birth_rate_range.clone(), 0f32..carrying_capacity)?;
+
+        chart.configure_mesh()
+            .x_desc("r")
+            .y_desc("N(t)")
+            .x_labels(10)
+            .y_labels(10)
+            .draw()?;
+
+        chart.draw_series(
+            points.iter()
+                .flat_map(|point| point.attractor.iter().map(|population| (point.birth_rate, *population)))
+                .filter(|(_, population)| (0f32..=carrying_capacity).contains(population))
+                .map(|point| Pixel::new(point, BLACK.mix(0.5)))
+        )?;
+
+        // Lyapunov exponents
+        let lyapunov: Vec<(f32, f32)> = points.iter()
+            .filter(|point| point.lyapunov_exponent.is_finite())
+            .map(|point| (point.birth_rate, point.lyapunov_exponent.max(MIN_LYAPUNOV_DISPLAY)))
+            .collect();
+
+        let max_lyapunov_display = lyapunov.iter().map(|(_, exponent)| *exponent).fold(0f32, f32::max) + 0.5f32;
+
+        let mut chart = ChartBuilder::on(&lower)
+            .margin(20u32)
+            .x_label_area_size(30u32)
+            .y_label_area_size(60u32)
+            .build_cartesian_2d(birth_rate_range, MIN_LYAPUNOV_DISPLAY..max_lyapunov_display)?;
+
+        chart.configure_mesh()
+            .x_desc("r")
+            .y_desc("λ")
+            .x_labels(10)
+            .y_labels(5)
+            .draw()?;
+
+        chart.draw_series(LineSeries::new(
+            [(params.min_birth_rate, 0f32), (params.max_birth_rate, 0f32)],
+            &BLACK
+        ))?;
+
+        chart.draw_series(LineSeries::new(lyapunov, &RED))?;
+
+        Ok(())
+    }
+}
+
+impl Chart for Params {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        Model::draw_function(area, self)
+    }
+}
+
+#[wasm_bindgen(js_class = DDS_LE_BIF_Params)]
+impl Params {
+    pub fn builder() -> Self {
+        Self { ..Default::default() }
+    }
+
+    pub fn initial_population(mut self, initial_population: f32) -> Self {
+        self.initial_population = initial_population;
+        self
+    }
+
+    pub fn carrying_capacity(mut self, carrying_capacity: f32) -> Self {
+        self.carrying_capacity = carrying_capacity;
+        self
+    }
+
+    pub fn min_birth_rate(mut self, min_birth_rate: f32) -> Self {
+        self.min_birth_rate = min_birth_rate;
+        self
+    }
+
+    pub fn max_birth_rate(mut self, max_birth_rate: f32) -> Self {
+        self.max_birth_rate = max_birth_rate;
+        self
+    }
+
+    pub fn samples(mut self, samples: usize) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    pub fn transient(mut self, transient: usize) -> Self {
+        self.transient = transient;
+        self
+    }
+
+    pub fn recorded(mut self, recorded: usize) -> Self {
+        self.recorded = recorded;
+        self
+    }
+
+    fn to_model(&self) -> LogisticBifurcation {
+        LogisticBifurcation::new(
+            self.initial_population,
+            self.carrying_capacity,
+            (self.min_birth_rate, self.max_birth_rate),
+            self.samples
+        )
+        .transient(self.transient)
+        .recorded(self.recorded)
+    }
+}
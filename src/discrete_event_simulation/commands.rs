@@ -0,0 +1,44 @@
+use crate::prelude::*;
+use crate::cli::{Arguments, Command, Flag};
+use super::prelude::*;
+use super::pages;
+
+pub(crate) const COMMANDS: &[Command] = &[
+    Command {
+        name: "des-customer-queue",
+        description: "single operator customer queue, queue length and operator availability per event",
+        flags: &[
+            Flag { name: "max_time", default: "500", description: "simulated time" },
+            Flag { name: "customer_arrival_lambda", default: "1", description: "rate of the customer arrivals" },
+            Flag { name: "customer_served_mean", default: "1", description: "mean service time" },
+            Flag { name: "customer_served_std_dev", default: "1", description: "standard deviation of the service time" },
+            Flag { name: "simulation_seed", default: "0", description: "random seed" },
+        ],
+        run: customer_queue,
+        chart: Some(customer_queue_chart),
+    },
+];
+
+fn customer_queue(arguments: &Arguments) -> Result<String, String> {
+    let model = CustomerQueue::build_des(
+        arguments.get("customer_arrival_lambda")?,
+        arguments.get("customer_served_mean")?,
+        arguments.get("customer_served_std_dev")?,
+        arguments.get("simulation_seed")?,
+    );
+
+    let simulation = Simulation::new(model)
+        .time_limit(arguments.get("max_time")?)
+        .simulation_map(|(time, state)| (time, (state.queue_length, state.operator_available as u32)));
+
+    Ok(arguments.export(simulation, &["t", "queue_length", "operator_available"]))
+}
+
+fn customer_queue_chart(arguments: &Arguments) -> Result<String, String> {
+    arguments.render(pages::customer_queue::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .customer_arrival_lambda(arguments.get("customer_arrival_lambda")?)
+        .customer_served_mean(arguments.get("customer_served_mean")?)
+        .customer_served_std_dev(arguments.get("customer_served_std_dev")?)
+        .simulation_seed(arguments.get("simulation_seed")?))
+}
@@ -1,7 +1,10 @@
 pub mod pages {
-    mod customer_queue;
+    pub mod customer_queue;
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod commands;
+
 pub mod prelude {
     pub use super::discete_event_simulation::*;
     pub use super::customer_queue::*;
@@ -9,4 +9,6 @@ pub mod stochastic_simulation;
 pub mod multiset_rewriting;
 pub mod discrete_event_simulation;
 pub mod cellular_automata;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
\ No newline at end of file
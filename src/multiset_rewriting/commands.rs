@@ -0,0 +1,55 @@
+use crate::prelude::*;
+use crate::cli::{Arguments, Command, Flag};
+use super::prelude::*;
+use super::pages;
+
+pub(crate) const COMMANDS: &[Command] = &[
+    Command {
+        name: "msr-frog-l-e-complexes",
+        description: "P system of the Pelophylax lessonae-esculentus complexes, adults of each species",
+        flags: &[
+            Flag { name: "max_time", default: "500", description: "simulated time" },
+            Flag { name: "initial_lessonae_pop", default: "160", description: "initial lessonae population" },
+            Flag { name: "initial_hybrid_pop", default: "30", description: "initial hybrid population" },
+            Flag { name: "initial_ridibundus_pop", default: "10", description: "initial ridibundus population" },
+            Flag { name: "carrying_capacity", default: "400", description: "carrying capacity" },
+            Flag { name: "selection_strength", default: "1", description: "sexual selection strength" },
+            Flag { name: "simulation_seed", default: "0", description: "random seed" },
+        ],
+        run: frog_l_e_complexes,
+        chart: Some(frog_l_e_complexes_chart),
+    },
+];
+
+fn frog_l_e_complexes(arguments: &Arguments) -> Result<String, String> {
+    let model = FrogLEComplexes::build_model(
+        (arguments.get("initial_lessonae_pop")?, arguments.get("initial_hybrid_pop")?, arguments.get("initial_ridibundus_pop")?),
+        arguments.get("selection_strength")?,
+        arguments.get("carrying_capacity")?,
+        arguments.get("simulation_seed")?,
+    );
+
+    // adults grouped by species as in the page
+    let [ll, lyl, lr, lyr, lrd, lyrd, rr, ryr, rdrd, rydrd, rdr, rydr, ryrd] = FrogLEComplexes::adults_objects();
+    let simulation = Simulation::new(model)
+        .time_limit(arguments.get("max_time")?)
+        .simulation_map(move |(time, state)| (time, (
+            state.get(&ll) + state.get(&lyl),
+            state.get(&lr) + state.get(&lyr) + state.get(&lrd) + state.get(&lyrd),
+            state.get(&rr) + state.get(&ryr) + state.get(&rdrd) + state.get(&rydrd)
+                + state.get(&rdr) + state.get(&rydr) + state.get(&ryrd),
+        )));
+
+    Ok(arguments.export(simulation, &["t", "lessonae", "hybrids", "ridibundus"]))
+}
+
+fn frog_l_e_complexes_chart(arguments: &Arguments) -> Result<String, String> {
+    arguments.render(pages::frog_l_e_complexes::Params::builder()
+        .max_time(arguments.get("max_time")?)
+        .initial_lessonae_pop(arguments.get("initial_lessonae_pop")?)
+        .initial_hybrid_pop(arguments.get("initial_hybrid_pop")?)
+        .initial_ridibundus_pop(arguments.get("initial_ridibundus_pop")?)
+        .carrying_capacity(arguments.get("carrying_capacity")?)
+        .selection_strength(arguments.get("selection_strength")?)
+        .simulation_seed(arguments.get("simulation_seed")?))
+}
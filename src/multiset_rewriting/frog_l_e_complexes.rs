@@ -1,4 +1,4 @@
-use super::prelude::*;
+use super::minimal_probabilistic_p_system::*;
 
 #[derive(Clone, PartialEq, Eq)]
 struct Frog {
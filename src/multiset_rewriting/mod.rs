@@ -1,9 +1,11 @@
-mod pages {
-    mod frog_l_e_complexes;
+pub mod pages {
+    pub mod frog_l_e_complexes;
 }
 
-mod prelude {
-    pub use super::minimal_probabilistic_p_system::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod commands;
+
+pub mod prelude {
     pub use super::frog_l_e_complexes::*;
 }
 
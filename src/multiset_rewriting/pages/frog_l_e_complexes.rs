@@ -4,6 +4,7 @@ use web_sys::HtmlCanvasElement;
 
 use crate::prelude::*;
 use crate::multiset_rewriting::prelude::*;
+use crate::multiset_rewriting::minimal_probabilistic_p_system::MinimalProbabilisticPSystem;
 
 #[wasm_bindgen(js_name = MSR_FLE)]
 pub struct Model { }
@@ -26,15 +27,14 @@ impl Model {
         draw_generic(Self::draw_function)(canvas, params)
     }
 
-    fn draw_function(canvas: HtmlCanvasElement, params: Params) -> MyDrawResult<()> {
-        let area = draw_prelude(canvas)?;
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
         area.fill(&WHITE)?;
     
         let max_time = params.max_time;
         let x_axis_range = 0f32..params.max_time;
         let y_axis_range = 0..(params.carrying_capacity as f32 * 2f32 / 3f32) as u32;
     
-        let mut chart = ChartBuilder::on(&area)
+        let mut chart = ChartBuilder::on(area)
             .margin(20u32)
             .x_label_area_size(40u32)
             .y_label_area_size(60u32)
@@ -93,6 +93,12 @@ impl Model {
     }
 }
 
+impl Chart for Params {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        Model::draw_function(area, self)
+    }
+}
+
 #[wasm_bindgen(js_class = MSR_FLE_Params)]
 impl Params {
     pub fn builder() -> Self {
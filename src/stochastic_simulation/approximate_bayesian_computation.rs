@@ -0,0 +1,295 @@
+use rand::{Rng, SeedableRng};
+use rand::distributions::WeightedIndex;
+use rand_distr::{Distribution, Normal};
+
+// Approximate Bayesian computation of the posterior of the parameters (e.g. the rate
+// constants) of a stochastic model whose likelihood is intractable. Parameters are
+// accepted when the summary statistics of a simulation with them are close enough to
+// the observed ones.
+//
+// The model is built from the parameter values and a seed, so that replicas of any
+// stochastic model (`StochasticSimulation`, `MinimalProbabilisticPSystem`, ...) can be
+// run; `states_at` helps computing summaries from their trajectories. The distance
+// between summaries is the euclidean one after dividing each statistic by its scale,
+// by default the magnitude of the observed value.
+#[derive(Clone)]
+pub struct ApproximateBayesianComputation {
+    priors: Vec<Prior>,
+    observed: Vec<f32>,
+    scales: Vec<f32>,
+    seed: u64,
+    max_simulations: u32,
+    quantile: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Prior {
+    Uniform(f32, f32),
+    // uniform in the logarithm, for rates spanning orders of magnitude
+    LogUniform(f32, f32),
+}
+
+#[derive(Clone, Debug)]
+pub struct Particle {
+    pub parameters: Vec<f32>,
+    pub weight: f32,
+    pub distance: f32,
+}
+
+// weighted sample of the posterior, with the weights summing to one
+#[derive(Clone, Debug)]
+pub struct Posterior {
+    pub particles: Vec<Particle>,
+    pub tolerance: f32,
+    pub simulations: u32,
+}
+
+impl ApproximateBayesianComputation {
+    pub fn new(priors: Vec<Prior>, observed: Vec<f32>, seed: u64) -> Self {
+        let scales = observed.iter().map(|value| value.abs().max(f32::EPSILON)).collect();
+        Self { priors, observed, scales, seed, max_simulations: 100000, quantile: 0.5 }
+    }
+
+    pub fn scales(mut self, scales: Vec<f32>) -> Self {
+        self.scales = scales;
+        self
+    }
+
+    // budget of simulations, after which the samplers return what they have found
+    pub fn max_simulations(mut self, max_simulations: u32) -> Self {
+        self.max_simulations = max_simulations;
+        self
+    }
+
+    // quantile of the distances of a generation used as tolerance of the next one
+    pub fn quantile(mut self, quantile: f32) -> Self {
+        self.quantile = quantile;
+        self
+    }
+
+    // Rejection sampling from the prior: draws until `samples` parameters are accepted
+    // within the tolerance, or the simulation budget runs out.
+    pub fn rejection<F, S, G>(&self, samples: usize, tolerance: f32, make_model: F, summary: G) -> Posterior
+        where F: Fn(&[f32], u64) -> S, G: Fn(S) -> Vec<f32>
+    {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(self.seed);
+        let mut particles = Vec::with_capacity(samples);
+        let mut simulations = 0;
+
+        while particles.len() < samples && simulations < self.max_simulations {
+            let parameters: Vec<f32> = self.priors.iter().map(|prior| prior.sample(&mut rng)).collect();
+            let distance = self.simulate(&parameters, &mut rng, &make_model, &summary);
+            simulations += 1;
+
+            if distance <= tolerance {
+                particles.push(Particle { parameters, weight: 1f32, distance });
+            }
+        }
+
+        Posterior::new(particles, tolerance, simulations)
+    }
+
+    // Sequential Monte Carlo (population Monte Carlo of Beaumont et al., 2009): the first
+    // generation is sampled from the prior, each of the next ones perturbs the particles
+    // of the previous generation with a gaussian kernel of twice their variance and
+    // accepts them within a tolerance shrinking to the configured quantile of the
+    // previous distances. Particles are weighted by prior / proposal density.
+    pub fn smc<F, S, G>(&self, particles: usize, generations: usize, make_model: F, summary: G) -> Posterior
+        where F: Fn(&[f32], u64) -> S, G: Fn(S) -> Vec<f32>
+    {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(self.seed);
+        let mut simulations = 0;
+
+        let population: Vec<Particle> = (0..particles)
+            .map(|_| {
+                let parameters: Vec<f32> = self.priors.iter().map(|prior| prior.sample(&mut rng)).collect();
+                let distance = self.simulate(&parameters, &mut rng, &make_model, &summary);
+                Particle { parameters, weight: 1f32, distance }
+            })
+            .collect();
+        simulations += particles as u32;
+
+        let mut posterior = Posterior::new(population, f32::INFINITY, simulations);
+
+        for _ in 1..generations {
+            if posterior.particles.is_empty() {
+                break;
+            }
+
+            let mut distances: Vec<f32> = posterior.particles.iter().map(|particle| particle.distance).collect();
+            distances.sort_by(f32::total_cmp);
+            let tolerance = distances[((distances.len() - 1) as f32 * self.quantile.clamp(0f32, 1f32)) as usize];
+
+            let previous = &posterior.particles;
+            let kernels: Vec<Normal<f32>> = posterior.std_dev().iter().zip(self.priors.iter())
+                .map(|(std_dev, prior)| {
+                    let std_dev = (std_dev * std::f32::consts::SQRT_2).max(f32::EPSILON * prior.width());
+                    Normal::new(0f32, std_dev).unwrap()
+                })
+                .collect();
+            let Ok(choice) = WeightedIndex::new(previous.iter().map(|particle| particle.weight)) else {
+                break;
+            };
+
+            let mut population = Vec::with_capacity(particles);
+            while population.len() < particles && simulations < self.max_simulations {
+                let parent = &previous[choice.sample(&mut rng)];
+                let parameters: Vec<f32> = parent.parameters.iter().zip(kernels.iter())
+                    .map(|(value, kernel)| value + kernel.sample(&mut rng))
+                    .collect();
+
+                let prior_density = self.prior_density(&parameters);
+                if prior_density <= 0f32 {
+                    continue;
+                }
+
+                let distance = self.simulate(&parameters, &mut rng, &make_model, &summary);
+                simulations += 1;
+
+                if distance <= tolerance {
+                    let proposal_density: f32 = previous.iter()
+                        .map(|particle| particle.weight * Self::kernel_density(&kernels, &particle.parameters, &parameters))
+                        .sum();
+
+                    population.push(Particle { parameters, weight: prior_density / proposal_density, distance });
+                }
+            }
+
+            // out of budget, or with the weights lost to the kernel densities underflowing,
+            // the last complete generation is the result
+            let total_weight: f32 = population.iter().map(|particle| particle.weight).sum();
+            if population.len() < particles || !(total_weight.is_finite() && total_weight > 0f32) {
+                posterior.simulations = simulations;
+                break;
+            }
+
+            posterior = Posterior::new(population, tolerance, simulations);
+        }
+
+        posterior
+    }
+
+    fn simulate<F, S, G>(&self, parameters: &[f32], rng: &mut rand::rngs::SmallRng, make_model: &F, summary: &G) -> f32
+        where F: Fn(&[f32], u64) -> S, G: Fn(S) -> Vec<f32>
+    {
+        let statistics = summary(make_model(parameters, rng.gen()));
+        if statistics.len() != self.observed.len() {
+            return f32::INFINITY;
+        }
+
+        let distance = statistics.iter().zip(self.observed.iter()).zip(self.scales.iter())
+            .map(|((statistic, observed), scale)| ((statistic - observed) / scale).powi(2))
+            .sum::<f32>()
+            .sqrt();
+
+        if distance.is_nan() { f32::INFINITY } else { distance }
+    }
+
+    fn prior_density(&self, parameters: &[f32]) -> f32 {
+        self.priors.iter().zip(parameters.iter())
+            .map(|(prior, value)| prior.density(*value))
+            .product()
+    }
+
+    // the normalization constant is the same for all the particles, and cancels with the weights'
+    fn kernel_density(kernels: &[Normal<f32>], from: &[f32], to: &[f32]) -> f32 {
+        let exponent: f32 = kernels.iter().zip(from.iter().zip(to.iter()))
+            .map(|(kernel, (from, to))| ((to - from) / kernel.std_dev()).powi(2) / 2f32)
+            .sum();
+
+        (-exponent).exp()
+    }
+}
+
+impl Prior {
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> f32 {
+        match *self {
+            Prior::Uniform(min, max) => rng.gen_range(min..=max),
+            Prior::LogUniform(min, max) => rng.gen_range(min.ln()..=max.ln()).exp(),
+        }
+    }
+
+    pub fn density(&self, value: f32) -> f32 {
+        match *self {
+            Prior::Uniform(min, max) if (min..=max).contains(&value) => 1f32 / (max - min),
+            Prior::LogUniform(min, max) if (min..=max).contains(&value) => 1f32 / (value * (max / min).ln()),
+            _ => 0f32,
+        }
+    }
+
+    fn width(&self) -> f32 {
+        match *self {
+            Prior::Uniform(min, max) | Prior::LogUniform(min, max) => max - min,
+        }
+    }
+}
+
+impl Posterior {
+    fn new(mut particles: Vec<Particle>, tolerance: f32, simulations: u32) -> Self {
+        let total: f32 = particles.iter().map(|particle| particle.weight).sum();
+        for particle in particles.iter_mut() {
+            particle.weight /= total;
+        }
+
+        Self { particles, tolerance, simulations }
+    }
+
+    pub fn mean(&self) -> Vec<f32> {
+        let dimension = self.particles.first().map_or(0, |particle| particle.parameters.len());
+
+        (0..dimension)
+            .map(|i| self.particles.iter().map(|particle| particle.weight * particle.parameters[i]).sum())
+            .collect()
+    }
+
+    pub fn std_dev(&self) -> Vec<f32> {
+        self.mean().iter().enumerate()
+            .map(|(i, mean)| self.particles.iter()
+                .map(|particle| particle.weight * (particle.parameters[i] - mean).powi(2))
+                .sum::<f32>()
+                .sqrt())
+            .collect()
+    }
+
+    // weighted quantile of one of the parameters
+    pub fn quantile(&self, parameter: usize, quantile: f32) -> f32 {
+        let mut values: Vec<(f32, f32)> = self.particles.iter()
+            .map(|particle| (particle.parameters[parameter], particle.weight))
+            .collect();
+        values.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        let mut cumulative = 0f32;
+        for (value, weight) in values.iter() {
+            cumulative += weight;
+            if cumulative >= quantile {
+                return *value;
+            }
+        }
+
+        values.last().map_or(f32::NAN, |(value, _)| *value)
+    }
+
+    pub fn effective_sample_size(&self) -> f32 {
+        1f32 / self.particles.iter().map(|particle| particle.weight.powi(2)).sum::<f32>()
+    }
+}
+
+// State of a trajectory at each of the given (increasing) times, i.e. the one after the
+// last event that happened before it, as in `Ensemble`.
+pub fn states_at<S, Y>(simulation: S, times: &[f32]) -> Vec<Y>
+    where S: IntoIterator<Item = (f32, Y)>, Y: Clone
+{
+    let mut simulation = simulation.into_iter().peekable();
+    let Some((_, mut state)) = simulation.next() else {
+        return Vec::new();
+    };
+
+    times.iter()
+        .map(|time| {
+            while let Some((_, next_state)) = simulation.next_if(|(event_time, _)| event_time <= time) {
+                state = next_state;
+            }
+            state.clone()
+        })
+        .collect()
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use rand::{Rng, SeedableRng};
+use crate::chemical_reactions::prelude::*;
+use crate::stochastic_simulation::indexed_network::*;
+
+// Chemical Langevin equation integrated with the Euler-Maruyama scheme:
+//
+//   X(t + dt) = X(t) + sum_j v_j a_j(X) dt + sum_j v_j sqrt(a_j(X) dt) N_j(0, 1)
+//
+// where v_j is the state change and a_j the propensity of reaction j.
+// Amounts are continuous and clamped at zero after every step.
+#[derive(Clone)]
+pub struct LangevinSimulation {
+    network: IndexedNetwork,
+    time_step: f32,
+    rng: rand::rngs::SmallRng,
+
+    state: Option<(f32, Vec<f32>)>,
+    fix_point: bool,
+}
+
+impl LangevinSimulation {
+    pub fn new(reactions: Vec<Reaction>, initial_state: HashMap<Molecule, u32>, time_step: f32, seed: u64) -> Self {
+        Self::from_parts(reactions, initial_state, time_step, rand::rngs::SmallRng::seed_from_u64(seed))
+    }
+
+    pub(crate) fn from_parts(reactions: Vec<Reaction>, initial_state: HashMap<Molecule, u32>, time_step: f32, rng: rand::rngs::SmallRng) -> Self {
+        Self {
+            network: IndexedNetwork::new(&reactions, &initial_state),
+            time_step,
+            rng,
+            state: None,
+            fix_point: false,
+        }
+    }
+}
+
+impl Iterator for LangevinSimulation {
+    type Item = (f32, Vec<(Molecule, f32)>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.fix_point {
+            return None;
+        }
+
+        let Some((time, mut state)) = self.state.take() else {
+            let state: Vec<f32> = self.network.initial_state.iter().map(|quantity| *quantity as f32).collect();
+            let output = self.network.output(0f32, &state);
+            self.state = Some((0f32, state));
+            return Some(output);
+        };
+
+        let propensities: Vec<f32> = self.network.reactions.iter()
+            .map(|reaction| reaction.get_continuous_propensity(&state)).collect();
+
+        if propensities.iter().all(|propensity| *propensity <= 0f32) {
+            // no reaction can be applied
+            self.fix_point = true;
+            return None;
+        }
+
+        let mut delta = vec![0f32; state.len()];
+        for (reaction, propensity) in self.network.reactions.iter().zip(propensities) {
+            let noise: f32 = self.rng.sample(rand_distr::StandardNormal);
+            let firings = propensity * self.time_step + (propensity * self.time_step).sqrt() * noise;
+
+            for (molecule, change) in reaction.changes.iter() {
+                delta[*molecule] += *change as f32 * firings;
+            }
+        }
+
+        for (quantity, delta) in state.iter_mut().zip(delta) {
+            *quantity = f32::max(*quantity + delta, 0f32);
+        }
+
+        let time = time + self.time_step;
+        let output = self.network.output(time, &state);
+        self.state = Some((time, state));
+        Some(output)
+    }
+}
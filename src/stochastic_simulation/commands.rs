@@ -0,0 +1,405 @@
+use crate::prelude::*;
+use crate::chemical_reactions::prelude::*;
+use crate::cli::{Arguments, Command, Flag};
+use super::prelude::*;
+use super::pages;
+use super::SSAMethod;
+use crate::continuous_dynamical_systems::ODESolver;
+
+pub(crate) const COMMANDS: &[Command] = &[
+    Command {
+        name: "ssa-lotka-volterra",
+        description: "stochastic Lotka-Volterra predator-prey model",
+        flags: &[
+            Flag { name: "algorithm", default: "ssa", description: "ode, ssa, cle or ensemble" },
+            Flag { name: "solver", default: "rk4", description: "ODE solver: dop853, dopri5, rk4 or ros23" },
+            Flag { name: "max_time", default: "1.5", description: "simulated time" },
+            Flag { name: "initial_prey_pop", default: "180", description: "initial prey population" },
+            Flag { name: "initial_predator_pop", default: "150", description: "initial predator population" },
+            Flag { name: "prey_birth_rate", default: "18", description: "birth rate of the preys" },
+            Flag { name: "predator_death_rate", default: "12", description: "death rate of the predators" },
+            Flag { name: "hunting_meetings", default: "0.1", description: "rate of the hunting meetings" },
+            Flag { name: "hunt_offsprings", default: "1", description: "predator offsprings per hunt" },
+            Flag { name: "ssa_method", default: "direct", description: "SSA method: direct, nrm or tau" },
+            Flag { name: "seed", default: "0", description: "random seed" },
+            Flag { name: "replicas", default: "50", description: "replicas of the ensemble" },
+        ],
+        run: lotka_volterra,
+        chart: Some(lotka_volterra_chart),
+    },
+    Command {
+        name: "ssa-enzymatic-activity",
+        description: "stochastic Michaelis-Menten enzymatic activity",
+        flags: &[
+            Flag { name: "algorithm", default: "ssa", description: "ode, ssa, cle or ensemble" },
+            Flag { name: "solver", default: "rk4", description: "ODE solver: dop853, dopri5, rk4 or ros23" },
+            Flag { name: "max_time", default: "1", description: "simulated time" },
+            Flag { name: "initial_enzyme", default: "100", description: "initial enzyme molecules" },
+            Flag { name: "initial_reactant", default: "100", description: "initial reactant molecules" },
+            Flag { name: "binding_rate", default: "0.3", description: "binding rate" },
+            Flag { name: "unbinding_rate", default: "10", description: "unbinding rate" },
+            Flag { name: "catalysis_rate", default: "10", description: "catalysis rate" },
+            Flag { name: "ssa_method", default: "direct", description: "SSA method: direct, nrm or tau" },
+            Flag { name: "seed", default: "0", description: "random seed" },
+            Flag { name: "replicas", default: "50", description: "replicas of the ensemble" },
+        ],
+        run: enzymatic_activity,
+        chart: Some(enzymatic_activity_chart),
+    },
+    Command {
+        name: "ssa-negative-feedback-loop",
+        description: "stochastic negative feedback loop of three genes",
+        flags: &[
+            Flag { name: "algorithm", default: "ssa", description: "ode, ssa, cle or ensemble" },
+            Flag { name: "solver", default: "rk4", description: "ODE solver: dop853, dopri5, rk4 or ros23" },
+            Flag { name: "max_time", default: "1", description: "simulated time" },
+            Flag { name: "initial_state", default: "5,1,3", description: "initial genes g1,g2,g3" },
+            Flag { name: "production_rates", default: "10,10000,10", description: "production rates of the proteins" },
+            Flag { name: "binding_rates", default: "10,0.1,10", description: "binding rates of the proteins to the genes" },
+            Flag { name: "unbinding_rates", default: "2,20,20", description: "unbinding rates of the proteins" },
+            Flag { name: "decay_rates", default: "1,100,1", description: "decay rates of the proteins" },
+            Flag { name: "production_delay", default: "0", description: "mean delay between the production reactions and the release of the proteins, no delay if 0" },
+            Flag { name: "delay_shape", default: "0", description: "shape of the gamma distributed delays of the SSA, fixed delays if 0" },
+            Flag { name: "ssa_method", default: "direct", description: "SSA method: direct, nrm or tau, delays only by the direct method" },
+            Flag { name: "seed", default: "0", description: "random seed" },
+            Flag { name: "replicas", default: "50", description: "replicas of the ensemble" },
+        ],
+        run: negative_feedback_loop,
+        chart: Some(negative_feedback_loop_chart),
+    },
+    Command {
+        name: "ssa-gene-expression",
+        description: "autoregulated gene expression in nucleus and cytoplasm, ode in concentrations and the others in counts",
+        flags: &[
+            Flag { name: "algorithm", default: "ssa", description: "ode, ssa, cle or ensemble" },
+            Flag { name: "solver", default: "rk4", description: "ODE solver: dop853, dopri5, rk4 or ros23" },
+            Flag { name: "max_time", default: "50", description: "simulated time" },
+            Flag { name: "genes", default: "1", description: "copies of the gene" },
+            Flag { name: "volumes", default: "1,4", description: "volumes of the nucleus and of the cytoplasm" },
+            Flag { name: "transcription_rate", default: "20", description: "maximum transcription rate of each copy of the gene" },
+            Flag { name: "repression_threshold", default: "5", description: "nuclear protein concentration halving the transcription" },
+            Flag { name: "hill_coefficient", default: "2", description: "cooperativity of the repression" },
+            Flag { name: "export_rate", default: "1", description: "export rate of the mRNA to the cytoplasm" },
+            Flag { name: "translation_rate", default: "5", description: "translation rate" },
+            Flag { name: "import_rate", default: "0.5", description: "import rate of the protein into the nucleus" },
+            Flag { name: "mrna_decay_rate", default: "0.5", description: "decay rate of the mRNA" },
+            Flag { name: "protein_decay_rate", default: "0.2", description: "decay rate of the protein" },
+            Flag { name: "ssa_method", default: "direct", description: "SSA method: direct, nrm or tau" },
+            Flag { name: "seed", default: "0", description: "random seed" },
+            Flag { name: "replicas", default: "50", description: "replicas of the ensemble" },
+        ],
+        run: gene_expression,
+        chart: None,
+    },
+    Command {
+        name: "ssa-spatial-lotka-volterra",
+        description: "Lotka-Volterra travelling waves on a lattice (next subvolume method), total populations per sample",
+        flags: &[
+            Flag { name: "size", default: "48", description: "side of the lattice of voxels" },
+            Flag { name: "max_time", default: "40", description: "simulated time" },
+            Flag { name: "sample_interval", default: "0.2", description: "time between two samples" },
+            Flag { name: "initial_prey_pop", default: "50", description: "initial preys of each voxel" },
+            Flag { name: "initial_predator_pop", default: "10", description: "initial predators of each central voxel" },
+            Flag { name: "prey_birth_rate", default: "1", description: "birth rate of the preys" },
+            Flag { name: "prey_crowding", default: "0.04", description: "rate of the competition between preys" },
+            Flag { name: "predator_death_rate", default: "0.4", description: "death rate of the predators" },
+            Flag { name: "hunting_meetings", default: "0.04", description: "rate of the hunting meetings" },
+            Flag { name: "prey_diffusion", default: "0.1", description: "jump rate of the preys to each neighbouring voxel" },
+            Flag { name: "predator_diffusion", default: "0.3", description: "jump rate of the predators to each neighbouring voxel" },
+            Flag { name: "seed", default: "0", description: "random seed" },
+        ],
+        run: spatial_lotka_volterra,
+        chart: Some(spatial_lotka_volterra_chart),
+    },
+];
+
+// same integration step and ensemble grid of the pages
+const LANGEVIN_TIME_STEP: f32 = 0.001;
+const ENSEMBLE_GRID_POINTS: usize = 200;
+const ENSEMBLE_QUANTILES: [f32; 4] = [0.05, 0.25, 0.75, 0.95];
+
+fn lotka_volterra(arguments: &Arguments) -> Result<String, String> {
+    let (initial_preys, initial_predators) = (arguments.get("initial_prey_pop")?, arguments.get("initial_predator_pop")?);
+    let (prey_birth_rate, predator_death_rate) = (arguments.get("prey_birth_rate")?, arguments.get("predator_death_rate")?);
+    let (hunting_meetings, hunt_offsprings) = (arguments.get("hunting_meetings")?, arguments.get("hunt_offsprings")?);
+
+    simulate(arguments,
+        |solver, max_time| LotkaVolterra::make_ode(initial_preys, initial_predators, prey_birth_rate, predator_death_rate, hunting_meetings, hunt_offsprings, solver, max_time),
+        |seed| LotkaVolterra::make_ssa(initial_preys, initial_predators, prey_birth_rate, predator_death_rate, hunting_meetings, hunt_offsprings, seed),
+    )
+}
+
+fn lotka_volterra_chart(arguments: &Arguments) -> Result<String, String> {
+    let params = pages::lotka_volterra::Params::builder()
+        .solver(arguments.get_string("solver")?)
+        .max_time(arguments.get("max_time")?)
+        .initial_prey_population(arguments.get("initial_prey_pop")?)
+        .initial_predator_population(arguments.get("initial_predator_pop")?)
+        .prey_birth_rate(arguments.get("prey_birth_rate")?)
+        .predator_death_rate(arguments.get("predator_death_rate")?)
+        .hunting_meetings(arguments.get("hunting_meetings")?)
+        .hunt_offsprings(arguments.get("hunt_offsprings")?)
+        .ssa_method(arguments.get_string("ssa_method")?)
+        .ssa_seed(arguments.get("seed")?)
+        .ssa_replicas(arguments.get("replicas")?);
+
+    arguments.render((arguments.get_string("algorithm")?.as_str(), params))
+}
+
+fn enzymatic_activity(arguments: &Arguments) -> Result<String, String> {
+    let (initial_enzyme, initial_reactant) = (arguments.get("initial_enzyme")?, arguments.get("initial_reactant")?);
+    let (binding_rate, unbinding_rate) = (arguments.get("binding_rate")?, arguments.get("unbinding_rate")?);
+    let catalysis_rate = arguments.get("catalysis_rate")?;
+
+    simulate(arguments,
+        |solver, max_time| EnzymaticActivity::make_ode(initial_enzyme, initial_reactant, binding_rate, unbinding_rate, catalysis_rate, solver, max_time),
+        |seed| EnzymaticActivity::make_ssa(initial_enzyme, initial_reactant, binding_rate, unbinding_rate, catalysis_rate, seed),
+    )
+}
+
+fn enzymatic_activity_chart(arguments: &Arguments) -> Result<String, String> {
+    let params = pages::enzymatic_activity::Params::builder()
+        .solver(arguments.get_string("solver")?)
+        .max_time(arguments.get("max_time")?)
+        .initial_enzyme(arguments.get("initial_enzyme")?)
+        .initial_reactant(arguments.get("initial_reactant")?)
+        .binding_rate(arguments.get("binding_rate")?)
+        .unbinding_rate(arguments.get("unbinding_rate")?)
+        .catalysis_rate(arguments.get("catalysis_rate")?)
+        .ssa_method(arguments.get_string("ssa_method")?)
+        .ssa_seed(arguments.get("seed")?)
+        .ssa_replicas(arguments.get("replicas")?);
+
+    arguments.render((arguments.get_string("algorithm")?.as_str(), params))
+}
+
+fn negative_feedback_loop(arguments: &Arguments) -> Result<String, String> {
+    let initial_state = triple(arguments, "initial_state")?;
+    let production_rates = triple(arguments, "production_rates")?;
+    let binding_rates = triple(arguments, "binding_rates")?;
+    let unbinding_rates = triple(arguments, "unbinding_rates")?;
+    let decay_rates = triple(arguments, "decay_rates")?;
+
+    let production_delay: f32 = arguments.get("production_delay")?;
+    if production_delay > 0f32 {
+        let delay_shape: f32 = arguments.get("delay_shape")?;
+        let delay =
+            if delay_shape > 0f32 { ReactionDelay::Gamma { mean: production_delay, shape: delay_shape } }
+            else { ReactionDelay::Fixed(production_delay) };
+
+        return simulate_delayed(arguments,
+            |max_time| NegativeFeedbackLoop::make_delayed_ode(initial_state, production_rates, binding_rates, unbinding_rates, decay_rates, production_delay, max_time),
+            |seed| NegativeFeedbackLoop::make_delayed_ssa(initial_state, production_rates, binding_rates, unbinding_rates, decay_rates, delay, seed),
+        );
+    }
+
+    simulate(arguments,
+        |solver, max_time| NegativeFeedbackLoop::make_ode(initial_state, production_rates, binding_rates, unbinding_rates, decay_rates, solver, max_time),
+        |seed| NegativeFeedbackLoop::make_ssa(initial_state, production_rates, binding_rates, unbinding_rates, decay_rates, seed),
+    )
+}
+
+fn negative_feedback_loop_chart(arguments: &Arguments) -> Result<String, String> {
+    let (initial_state, production_rates) = (triple(arguments, "initial_state")?, triple(arguments, "production_rates")?);
+    let (binding_rates, unbinding_rates) = (triple(arguments, "binding_rates")?, triple(arguments, "unbinding_rates")?);
+    let decay_rates = triple(arguments, "decay_rates")?;
+
+    let params = pages::negative_feedback_loop::Params::builder()
+        .solver(arguments.get_string("solver")?)
+        .max_time(arguments.get("max_time")?)
+        .initial_state(initial_state.0, initial_state.1, initial_state.2)
+        .production_rates(production_rates.0, production_rates.1, production_rates.2)
+        .binding_rates(binding_rates.0, binding_rates.1, binding_rates.2)
+        .unbinding_rates(unbinding_rates.0, unbinding_rates.1, unbinding_rates.2)
+        .decay_rates(decay_rates.0, decay_rates.1, decay_rates.2)
+        .production_delay(arguments.get("production_delay")?)
+        .delay_shape(arguments.get("delay_shape")?)
+        .ssa_method(arguments.get_string("ssa_method")?)
+        .ssa_seed(arguments.get("seed")?)
+        .ssa_replicas(arguments.get("replicas")?);
+
+    arguments.render((arguments.get_string("algorithm")?.as_str(), params))
+}
+
+fn gene_expression(arguments: &Arguments) -> Result<String, String> {
+    let genes = arguments.get("genes")?;
+    let volumes = match arguments.get_list("volumes")?[..] {
+        [nucleus, cytoplasm] if nucleus > 0f32 && cytoplasm > 0f32 => (nucleus, cytoplasm),
+        _ => return Err("flag --volumes expects two positive comma separated values".to_owned()),
+    };
+    let rates = GeneExpressionRates {
+        transcription: arguments.get("transcription_rate")?,
+        repression_threshold: arguments.get("repression_threshold")?,
+        hill_coefficient: arguments.get("hill_coefficient")?,
+        export: arguments.get("export_rate")?,
+        translation: arguments.get("translation_rate")?,
+        import: arguments.get("import_rate")?,
+        mrna_decay: arguments.get("mrna_decay_rate")?,
+        protein_decay: arguments.get("protein_decay_rate")?,
+    };
+
+    simulate(arguments,
+        |solver, max_time| GeneExpression::make_ode(genes, volumes, rates, solver, max_time),
+        |seed| GeneExpression::make_ssa(genes, volumes, rates, seed),
+    )
+}
+
+fn spatial_lotka_volterra(arguments: &Arguments) -> Result<String, String> {
+    let sample_interval: f32 = arguments.get("sample_interval")?;
+    if sample_interval <= 0f32 {
+        return Err(format!("sample interval {sample_interval} is not positive"));
+    }
+
+    let model = SpatialLotkaVolterra::make_ssa(
+        arguments.get("size")?,
+        (arguments.get("initial_prey_pop")?, arguments.get("initial_predator_pop")?),
+        (arguments.get("prey_birth_rate")?, arguments.get("prey_crowding")?, arguments.get("predator_death_rate")?, arguments.get("hunting_meetings")?),
+        (arguments.get("prey_diffusion")?, arguments.get("predator_diffusion")?),
+        sample_interval,
+        arguments.get("seed")?,
+    );
+
+    let (prey, predator) = LotkaVolterra::species();
+    let (prey, predator) = (model.get_species_id(&prey).unwrap(), model.get_species_id(&predator).unwrap());
+    let max_time: f32 = arguments.get("max_time")?;
+
+    let totals: Vec<(f32, (u32, u32))> = model
+        .take_while(|(time, _)| *time <= max_time)
+        .map(|(time, lattice)| {
+            let total = |species: usize| lattice.cells.iter().map(|voxel| voxel[species]).sum::<u32>();
+            (time, (total(prey), total(predator)))
+        })
+        .collect();
+
+    Ok(arguments.export(Simulation::new(totals.into_iter()), &["t", "v", "p"]))
+}
+
+// the lattice at max_time, sampled as on the page
+fn spatial_lotka_volterra_chart(arguments: &Arguments) -> Result<String, String> {
+    let params = pages::spatial_lotka_volterra::Params::builder()
+        .size(arguments.get("size")?)
+        .max_time(arguments.get("max_time")?)
+        .initial_prey_pop(arguments.get("initial_prey_pop")?)
+        .initial_predator_pop(arguments.get("initial_predator_pop")?)
+        .prey_birth_rate(arguments.get("prey_birth_rate")?)
+        .prey_crowding(arguments.get("prey_crowding")?)
+        .predator_death_rate(arguments.get("predator_death_rate")?)
+        .hunting_meetings(arguments.get("hunting_meetings")?)
+        .prey_diffusion(arguments.get("prey_diffusion")?)
+        .predator_diffusion(arguments.get("predator_diffusion")?)
+        .simulation_seed(arguments.get("seed")?);
+
+    let model = pages::spatial_lotka_volterra::Model::build(params).map_err(|_| "cannot build the lattice".to_owned())?;
+    arguments.render((&model, u32::MAX))
+}
+
+// Runs the model with the algorithm of the arguments; the ensemble is summarized by
+// the mean, standard deviation and quantiles of each species on a time grid.
+fn simulate<O, S>(arguments: &Arguments, make_ode: O, make_ssa: S) -> Result<String, String>
+    where O: FnOnce(ODESolver, f32) -> ODESimulation, S: Fn(u64) -> StochasticSimulation
+{
+    let max_time: f32 = arguments.get("max_time")?;
+    let seed: u64 = arguments.get("seed")?;
+
+    let solver = arguments.get_string("solver")?;
+    let solver = ODESolver::from_string(solver.clone()).ok_or(format!("unknown solver '{solver}'"))?;
+    let ssa_method = arguments.get_string("ssa_method")?;
+    let ssa_method = SSAMethod::from_string(ssa_method.clone()).ok_or(format!("unknown SSA method '{ssa_method}'"))?;
+
+    let algorithm = arguments.get_string("algorithm")?;
+    let output = match algorithm.as_str() {
+        "ode" => {
+            let simulation = Simulation::new(make_ode(solver, max_time).into_iter().map(sorted_by_name))
+                .time_limit(max_time);
+            arguments.export(simulation, &["t"])
+        },
+        "ssa" => {
+            let simulation = Simulation::new(make_ssa(seed).with_method(ssa_method).map(sorted_by_name))
+                .fix_point(max_time + 1f32)
+                .time_limit(max_time);
+            arguments.export(simulation, &["t"])
+        },
+        "cle" => {
+            let simulation = Simulation::new(make_ssa(seed).to_langevin(LANGEVIN_TIME_STEP).map(sorted_by_name))
+                .fix_point(max_time + 1f32)
+                .time_limit(max_time);
+            arguments.export(simulation, &["t"])
+        },
+        "ensemble" => {
+            let ensemble = Ensemble::new(arguments.get("replicas")?, seed, max_time, ENSEMBLE_GRID_POINTS)
+                .quantiles(ENSEMBLE_QUANTILES.to_vec())
+                .run(|seed| make_ssa(seed).with_method(ssa_method));
+            export_ensemble(arguments, ensemble)
+        },
+        _ => return Err(format!("unknown algorithm '{algorithm}', expected ode, ssa, cle or ensemble")),
+    };
+
+    Ok(output)
+}
+
+// the ode is integrated with delays and the ssa follows the delayed direct method,
+// the chemical Langevin equation has no delayed counterpart
+fn simulate_delayed<O, S>(arguments: &Arguments, make_ode: O, make_ssa: S) -> Result<String, String>
+    where O: FnOnce(f32) -> ODESimulation, S: Fn(u64) -> SSAEngine
+{
+    let max_time: f32 = arguments.get("max_time")?;
+    let seed: u64 = arguments.get("seed")?;
+
+    let algorithm = arguments.get_string("algorithm")?;
+    let output = match algorithm.as_str() {
+        "ode" => {
+            let simulation = Simulation::new(make_ode(max_time).into_iter().map(sorted_by_name))
+                .time_limit(max_time);
+            arguments.export(simulation, &["t"])
+        },
+        "ssa" => {
+            let simulation = Simulation::new(make_ssa(seed).map(sorted_by_name))
+                .fix_point(max_time + 1f32)
+                .time_limit(max_time);
+            arguments.export(simulation, &["t"])
+        },
+        "ensemble" => {
+            let ensemble = Ensemble::new(arguments.get("replicas")?, seed, max_time, ENSEMBLE_GRID_POINTS)
+                .quantiles(ENSEMBLE_QUANTILES.to_vec())
+                .run(make_ssa);
+            export_ensemble(arguments, ensemble)
+        },
+        _ => return Err(format!("unknown algorithm '{algorithm}' with delays, expected ode, ssa or ensemble")),
+    };
+
+    Ok(output)
+}
+
+fn export_ensemble(arguments: &Arguments, mut ensemble: EnsembleStatistics) -> String {
+    ensemble.species.sort_by(|a, b| a.molecule.get_name().cmp(b.molecule.get_name()));
+
+    let rows: Vec<(f32, Vec<(String, f32)>)> = ensemble.time.iter().enumerate()
+        .map(|(time_id, time)| {
+            let statistics = ensemble.species.iter()
+                .flat_map(|species| {
+                    let name = species.molecule.get_name();
+                    [(format!("{name}_mean"), species.mean[time_id]), (format!("{name}_std_dev"), species.std_dev[time_id])]
+                        .into_iter()
+                        .chain(species.quantiles.iter().map(move |(quantile, series)| (format!("{name}_q{quantile}"), series[time_id])))
+                })
+                .collect();
+            (*time, statistics)
+        })
+        .collect();
+
+    arguments.export(Simulation::new(rows.into_iter()), &["t"])
+}
+
+// the states list the species in hash order, columns are sorted to be reproducible
+fn sorted_by_name<T>((time, mut state): (f32, Vec<(Molecule, T)>)) -> (f32, Vec<(Molecule, T)>) {
+    state.sort_by(|(a, _), (b, _)| a.get_name().cmp(b.get_name()));
+    (time, state)
+}
+
+fn triple<T: std::str::FromStr>(arguments: &Arguments, name: &str) -> Result<(T, T, T), String> {
+    let values: Vec<T> = arguments.get_list(name)?;
+    match <[T; 3]>::try_from(values) {
+        Ok([a, b, c]) => Ok((a, b, c)),
+        Err(_) => Err(format!("flag --{name} expects three comma separated values")),
+    }
+}
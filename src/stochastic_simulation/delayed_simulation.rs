@@ -0,0 +1,149 @@
+use std::collections::{HashMap, VecDeque};
+use rand::Rng;
+use crate::chemical_reactions::prelude::*;
+use crate::stochastic_simulation::indexed_network::*;
+
+// Time between the firing of a delayed reaction and the release of its products.
+#[derive(Clone, Copy)]
+pub enum ReactionDelay {
+    Fixed(f32),
+    // gamma distributed, the larger the shape the narrower around the mean
+    Gamma { mean: f32, shape: f32 },
+}
+
+impl ReactionDelay {
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> f32 {
+        match *self {
+            ReactionDelay::Fixed(delay) => delay,
+            ReactionDelay::Gamma { mean, shape } => {
+                let distribution = rand_distr::Gamma::new(shape, mean / shape).unwrap();
+                rng.sample(distribution)
+            },
+        }
+    }
+
+    pub fn mean(&self) -> f32 {
+        match *self {
+            ReactionDelay::Fixed(delay) => delay,
+            ReactionDelay::Gamma { mean, .. } => mean,
+        }
+    }
+}
+
+// Barrio's rejection method for the delayed SSA: the next firing time is drawn as in
+// the direct method, but if a delayed reaction completes before it, the time advances
+// to the completion instead, the products are released and the firing is drawn again
+// (exact, as the firing times are memoryless). A delayed reaction takes its reactants
+// when it fires and releases its products when it completes, the species on both sides
+// of the reaction (e.g. the gene transcribing a protein) are never taken.
+#[derive(Clone)]
+pub struct DelayedSimulation {
+    network: IndexedNetwork,
+    delays: Vec<Option<ReactionDelay>>,
+    rng: rand::rngs::SmallRng,
+
+    state: Option<(f32, Vec<u32>)>,
+    // completion times of the delayed reactions that fired, sorted
+    pending: VecDeque<(f32, usize)>,
+    fix_point: bool,
+}
+
+impl DelayedSimulation {
+    // `delays[i]` is the delay of the i-th reaction, `None` for the instantaneous ones
+    pub(crate) fn from_parts(reactions: Vec<Reaction>, delays: Vec<Option<ReactionDelay>>, initial_state: HashMap<Molecule, u32>, rng: rand::rngs::SmallRng) -> Self {
+        let network = IndexedNetwork::new(&reactions, &initial_state);
+
+        Self {
+            network,
+            delays,
+            rng,
+            state: None,
+            pending: VecDeque::new(),
+            fix_point: false,
+        }
+    }
+
+    fn fire(&mut self, time: f32, reaction_id: usize, state: &mut [u32]) {
+        let reaction = &self.network.reactions[reaction_id];
+        let Some(delay) = self.delays.get(reaction_id).cloned().flatten() else {
+            reaction.apply(state);
+            return;
+        };
+
+        for (molecule, change) in reaction.changes.iter().filter(|(_, change)| *change < 0) {
+            state[*molecule] = (state[*molecule] as i64 + change) as u32;
+        }
+
+        let completion_time = time + delay.sample(&mut self.rng);
+        let position = self.pending.partition_point(|(other_time, _)| *other_time <= completion_time);
+        self.pending.insert(position, (completion_time, reaction_id));
+    }
+
+    fn complete(&self, reaction_id: usize, state: &mut [u32]) {
+        let reaction = &self.network.reactions[reaction_id];
+        for (molecule, change) in reaction.changes.iter().filter(|(_, change)| *change > 0) {
+            state[*molecule] = (state[*molecule] as i64 + change) as u32;
+        }
+    }
+}
+
+impl Iterator for DelayedSimulation {
+    type Item = (f32, Vec<(Molecule, u32)>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.fix_point {
+            return None;
+        }
+
+        let Some((time, mut state)) = self.state.take() else {
+            let state = self.network.initial_state.clone();
+            let output = self.network.output(0f32, &state);
+            self.state = Some((0f32, state));
+            return Some(output);
+        };
+
+        let propensities = self.network.get_propensities(&state);
+        let propensities_sum: f32 = propensities.iter().sum();
+
+        let firing_time =
+            if propensities_sum > 0f32 {
+                let distribution = rand_distr::Exp::new(propensities_sum).unwrap();
+                time + self.rng.sample(distribution)
+            } else {
+                f32::INFINITY
+            };
+
+        let time = match self.pending.front().cloned() {
+            Some((completion_time, reaction_id)) if completion_time <= firing_time => {
+                self.pending.pop_front();
+                self.complete(reaction_id, &mut state);
+                completion_time
+            },
+            _ if propensities_sum == 0f32 => {
+                // no reaction can be applied and none is pending
+                self.fix_point = true;
+                return None;
+            },
+            _ => {
+                // choose reaction to apply
+                let distribution = rand::distributions::Uniform::new(0f32, propensities_sum);
+                let chosen_reaction_value = self.rng.sample(distribution);
+
+                let chosen_reaction = propensities.iter()
+                    .scan(0f32, |partial_sum, propensity| {
+                        *partial_sum += propensity;
+                        Some(*partial_sum)
+                    })
+                    .position(|value| value > chosen_reaction_value)
+                    .unwrap_or(propensities.len() - 1);
+
+                self.fire(firing_time, chosen_reaction, &mut state);
+                firing_time
+            },
+        };
+
+        let output = self.network.output(time, &state);
+        self.state = Some((time, state));
+        Some(output)
+    }
+}
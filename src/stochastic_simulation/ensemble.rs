@@ -0,0 +1,130 @@
+use crate::chemical_reactions::prelude::*;
+
+// Runs many seeded replicas of a stochastic model and summarizes them on a common
+// time grid. Each trajectory is resampled as a step function, i.e. the amount at a
+// grid time is the one after the last event that happened before it.
+#[derive(Clone)]
+pub struct Ensemble {
+    replicas: u32,
+    seed: u64,
+    max_time: f32,
+    grid_points: usize,
+    quantiles: Vec<f32>,
+}
+
+#[derive(Clone)]
+pub struct EnsembleStatistics {
+    pub time: Vec<f32>,
+    pub species: Vec<SpeciesStatistics>,
+}
+
+#[derive(Clone)]
+pub struct SpeciesStatistics {
+    pub molecule: Molecule,
+    pub mean: Vec<f32>,
+    pub std_dev: Vec<f32>,
+    pub quantiles: Vec<(f32, Vec<f32>)>,
+}
+
+impl Ensemble {
+    pub fn new(replicas: u32, seed: u64, max_time: f32, grid_points: usize) -> Self {
+        Self { replicas, seed, max_time, grid_points, quantiles: Vec::new() }
+    }
+
+    pub fn quantiles(mut self, quantiles: Vec<f32>) -> Self {
+        self.quantiles = quantiles;
+        self
+    }
+
+    pub fn time_grid(&self) -> Vec<f32> {
+        let intervals = usize::max(self.grid_points, 2) - 1;
+        (0..=intervals)
+            .map(|i| self.max_time * i as f32 / intervals as f32)
+            .collect()
+    }
+
+    // the i-th replica is built with seed `seed + i`
+    pub fn run<F, S>(&self, make_model: F) -> EnsembleStatistics
+        where F: Fn(u64) -> S, S: Iterator<Item = (f32, Vec<(Molecule, u32)>)>
+    {
+        let time = self.time_grid();
+        let mut species: Vec<Molecule> = Vec::new();
+
+        // samples[species][time][replica]
+        let mut samples: Vec<Vec<Vec<f32>>> = Vec::new();
+
+        for replica in 0..self.replicas {
+            let mut simulation = make_model(self.seed.wrapping_add(replica as u64)).peekable();
+            let mut state: Vec<(Molecule, u32)> = Vec::new();
+
+            for (time_id, grid_time) in time.iter().enumerate() {
+                while let Some((_, next_state)) = simulation.next_if(|(event_time, _)| event_time <= grid_time) {
+                    state = next_state;
+                }
+
+                for (molecule, quantity) in state.iter() {
+                    let species_id = species.iter().position(|other| other == molecule)
+                        .unwrap_or_else(|| {
+                            species.push(molecule.clone());
+                            samples.push(vec![Vec::new(); time.len()]);
+                            species.len() - 1
+                        });
+
+                    samples[species_id][time_id].push(*quantity as f32);
+                }
+            }
+        }
+
+        let species = species.into_iter().zip(samples)
+            .map(|(molecule, samples)| self.species_statistics(molecule, samples))
+            .collect();
+
+        EnsembleStatistics { time, species }
+    }
+
+    fn species_statistics(&self, molecule: Molecule, mut samples: Vec<Vec<f32>>) -> SpeciesStatistics {
+        let mut mean = Vec::with_capacity(samples.len());
+        let mut std_dev = Vec::with_capacity(samples.len());
+        let mut quantiles: Vec<(f32, Vec<f32>)> = self.quantiles.iter()
+            .map(|quantile| (*quantile, Vec::with_capacity(samples.len())))
+            .collect();
+
+        for values in samples.iter_mut() {
+            let count = values.len() as f32;
+            let sample_mean = values.iter().sum::<f32>() / count;
+            let variance = values.iter().map(|value| (value - sample_mean).powi(2)).sum::<f32>() / count;
+
+            mean.push(sample_mean);
+            std_dev.push(variance.sqrt());
+
+            values.sort_by(f32::total_cmp);
+            for (quantile, series) in quantiles.iter_mut() {
+                series.push(Self::quantile(values, *quantile));
+            }
+        }
+
+        SpeciesStatistics { molecule, mean, std_dev, quantiles }
+    }
+
+    // linear interpolation between the closest ranks of the sorted values
+    fn quantile(sorted_values: &[f32], quantile: f32) -> f32 {
+        let rank = quantile.clamp(0f32, 1f32) * (sorted_values.len() - 1) as f32;
+        let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+
+        sorted_values[lower] + (sorted_values[upper] - sorted_values[lower]) * (rank - lower as f32)
+    }
+}
+
+impl EnsembleStatistics {
+    pub fn get(&self, molecule: &Molecule) -> Option<&SpeciesStatistics> {
+        self.species.iter().find(|statistics| &statistics.molecule == molecule)
+    }
+}
+
+impl SpeciesStatistics {
+    pub fn get_quantile(&self, quantile: f32) -> Option<&[f32]> {
+        self.quantiles.iter()
+            .find(|(other, _)| *other == quantile)
+            .map(|(_, series)| series.as_slice())
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use crate::continuous_dynamical_systems::ODESolver;
+use crate::chemical_reactions::prelude::*;
+use crate::stochastic_simulation::prelude::*;
+
+// Negatively autoregulated gene with nucleus/cytoplasm separation: the mRNA is
+// transcribed in the nucleus and exported to the cytoplasm, where it is translated;
+// the protein is imported back into the nucleus, where it represses the transcription.
+//
+// The rates are given on concentrations; the ODE trajectory is in concentrations and
+// the stochastic ones in molecule counts, related by the volumes of the compartments.
+pub struct GeneExpression;
+
+#[derive(Clone, Copy)]
+pub struct GeneExpressionRates {
+    // per copy of the gene
+    pub transcription: f32,
+    pub repression_threshold: f32,
+    pub hill_coefficient: f32,
+    pub export: f32,
+    pub translation: f32,
+    pub import: f32,
+    pub mrna_decay: f32,
+    pub protein_decay: f32,
+}
+
+impl GeneExpression {
+    pub fn network(volumes: (f32, f32), rates: GeneExpressionRates) -> CompartmentalNetwork {
+        let (nucleus, cytoplasm) = Self::compartments(volumes);
+        let ((gene, mrna_nucleus, protein_nucleus), (mrna_cytoplasm, protein_cytoplasm)) = Self::species();
+
+        // hill repression by the nuclear protein of each copy of the gene
+        let (gene_copies, repressor) = (gene.clone(), protein_nucleus.clone());
+        let threshold = rates.repression_threshold.powf(rates.hill_coefficient);
+        let transcription = KineticLaw::custom([&gene, &protein_nucleus], move |amounts| {
+            let repression = threshold / (threshold + amounts.get(&repressor).max(0f32).powf(rates.hill_coefficient));
+            rates.transcription * amounts.get(&gene_copies) * repression
+        });
+
+        CompartmentalNetwork::new([&nucleus, &cytoplasm])
+            .with_reaction(&nucleus, Reaction::new(0f32, [(1, &gene)], [(1, &gene), (1, &mrna_nucleus)]).with_kinetic_law(transcription))
+            .with_transport(rates.export, &mrna_nucleus, &mrna_cytoplasm)
+            .with_reaction(&cytoplasm, Reaction::new(rates.translation, [(1, &mrna_cytoplasm)], [(1, &mrna_cytoplasm), (1, &protein_cytoplasm)]))
+            .with_transport(rates.import, &protein_cytoplasm, &protein_nucleus)
+            .with_reaction(&nucleus, Reaction::new(rates.mrna_decay, [(1, &mrna_nucleus)], []))
+            .with_reaction(&cytoplasm, Reaction::new(rates.mrna_decay, [(1, &mrna_cytoplasm)], []))
+            .with_reaction(&nucleus, Reaction::new(rates.protein_decay, [(1, &protein_nucleus)], []))
+            .with_reaction(&cytoplasm, Reaction::new(rates.protein_decay, [(1, &protein_cytoplasm)], []))
+    }
+
+    // the gene copies are counts, the trajectory is in concentrations
+    pub fn make_ode(genes: u32, volumes: (f32, f32), rates: GeneExpressionRates, solver: ODESolver, max_time: f32) -> ODESimulation {
+        let network = Self::network(volumes, rates);
+        let reactions = network.reactions().unwrap();
+
+        ODESimulation::new(reactions, Self::initial_state(genes), solver, max_time).unwrap()
+            .map_amounts(|molecule, amount| amount / network.get_volume(molecule).unwrap())
+    }
+
+    pub fn make_ssa(genes: u32, volumes: (f32, f32), rates: GeneExpressionRates, simulation_seed: u64) -> StochasticSimulation {
+        let reactions = Self::network(volumes, rates).reactions().unwrap();
+
+        StochasticSimulation::new(reactions, Self::initial_state(genes), simulation_seed)
+    }
+
+    pub fn compartments((nucleus_volume, cytoplasm_volume): (f32, f32)) -> (Compartment, Compartment) {
+        (Compartment::new("nucleus", nucleus_volume), Compartment::new("cytoplasm", cytoplasm_volume))
+    }
+
+    pub fn species() -> ((Molecule, Molecule, Molecule), (Molecule, Molecule)) {
+        // the molecules are named after the compartments, whatever their volumes
+        let (nucleus, cytoplasm) = Self::compartments((1f32, 1f32));
+
+        (
+            (nucleus.molecule("gene"), nucleus.molecule("mRNA"), nucleus.molecule("protein")),
+            (cytoplasm.molecule("mRNA"), cytoplasm.molecule("protein")),
+        )
+    }
+
+    fn initial_state(genes: u32) -> HashMap<Molecule, u32> {
+        let ((gene, _, _), _) = Self::species();
+        vec![(gene, genes)].into_iter().collect()
+    }
+}
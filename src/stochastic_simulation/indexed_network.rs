@@ -0,0 +1,130 @@
+use std::collections::{HashMap, HashSet};
+use crate::chemical_reactions::prelude::*;
+
+// Reaction network compiled to species indices, shared by the simulators that
+// keep their state in a plain vector instead of a `HashMap<Molecule, u32>`.
+#[derive(Clone)]
+pub(crate) struct IndexedNetwork {
+    pub species: Vec<Molecule>,
+    pub reactions: Vec<IndexedReaction>,
+    pub initial_state: Vec<u32>,
+}
+
+#[derive(Clone)]
+pub(crate) struct IndexedReaction {
+    pub kinetic_law: KineticLaw,
+    pub reactants: Vec<(usize, u32)>,
+    pub modifiers: Vec<(Molecule, usize)>,
+    pub changes: Vec<(usize, i64)>,
+}
+
+impl IndexedNetwork {
+    pub fn new(reactions: &[Reaction], initial_state: &HashMap<Molecule, u32>) -> Self {
+        let mut species: Vec<Molecule> = reactions.iter()
+            .flat_map(|reaction| reaction.get_species())
+            .chain(initial_state.keys())
+            .collect::<HashSet<_>>()
+            .into_iter().cloned().collect();
+        species.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+
+        let species_ids: HashMap<&Molecule, usize> = species.iter()
+            .enumerate().map(|(id, molecule)| (molecule, id)).collect();
+
+        let reactions = reactions.iter()
+            .map(|reaction| {
+                let mut changes: HashMap<usize, i64> = HashMap::new();
+                for (stochiometric_coeff, molecule) in reaction.get_reactants() {
+                    *changes.entry(species_ids[molecule]).or_insert(0) -= *stochiometric_coeff as i64;
+                }
+                for (stochiometric_coeff, molecule) in reaction.get_products() {
+                    *changes.entry(species_ids[molecule]).or_insert(0) += *stochiometric_coeff as i64;
+                }
+
+                let mut changes: Vec<_> = changes.into_iter().filter(|(_, change)| *change != 0).collect();
+                changes.sort();
+
+                IndexedReaction {
+                    kinetic_law: reaction.get_kinetic_law().clone(),
+                    reactants: reaction.get_reactants().iter()
+                        .map(|(stochiometric_coeff, molecule)| (species_ids[molecule], *stochiometric_coeff))
+                        .collect(),
+                    modifiers: reaction.get_kinetic_law().get_modifiers().into_iter()
+                        .map(|molecule| (molecule.clone(), species_ids[molecule]))
+                        .collect(),
+                    changes,
+                }
+            })
+            .collect();
+
+        let initial_state = species.iter()
+            .map(|molecule| initial_state.get(molecule).cloned().unwrap_or(0))
+            .collect();
+
+        Self { species, reactions, initial_state }
+    }
+
+    pub fn get_propensities(&self, state: &[u32]) -> Vec<f32> {
+        self.reactions.iter().map(|reaction| reaction.get_propensity(state)).collect()
+    }
+
+    pub fn output<T: Copy>(&self, time: f32, state: &[T]) -> (f32, Vec<(Molecule, T)>) {
+        (time, self.species.iter().cloned().zip(state.iter().cloned()).collect())
+    }
+}
+
+impl IndexedReaction {
+    pub fn get_propensity(&self, state: &[u32]) -> f32 {
+        let KineticLaw::MassAction(kinetic_constant) = self.kinetic_law else {
+            // the other laws do not vanish with the reactants, which must be there to fire
+            if self.reactants.iter().any(|(molecule, stochiometric_coeff)| state[*molecule] < *stochiometric_coeff) {
+                return 0f32;
+            }
+
+            return self.kinetic_law.get_non_mass_action_rate(&|molecule| self.get_modifier(molecule, state) as f32);
+        };
+
+        let distinct_reactant_combinations: u32 =
+            self.reactants.iter()
+            .map(|(molecule, stochiometric_coeff)| num::integer::binomial(state[*molecule], *stochiometric_coeff))
+            .product();
+
+        kinetic_constant * (distinct_reactant_combinations as f32)
+    }
+
+    // propensity over a continuous state, the binomial coefficients become falling factorials
+    pub fn get_continuous_propensity(&self, state: &[f32]) -> f32 {
+        let KineticLaw::MassAction(kinetic_constant) = self.kinetic_law else {
+            return self.kinetic_law.get_non_mass_action_rate(&|molecule| self.get_modifier(molecule, state));
+        };
+
+        let reactant_combinations: f32 =
+            self.reactants.iter()
+            .map(|(molecule, stochiometric_coeff)| {
+                (0..*stochiometric_coeff)
+                    .map(|i| f32::max(state[*molecule] - i as f32, 0f32) / (i + 1) as f32)
+                    .product::<f32>()
+            })
+            .product();
+
+        kinetic_constant * reactant_combinations
+    }
+
+    // species whose amount changes the propensity
+    pub fn get_dependencies(&self) -> impl Iterator<Item = usize> + '_ {
+        self.reactants.iter().map(|(molecule, _)| *molecule)
+            .chain(self.modifiers.iter().map(|(_, molecule)| *molecule))
+    }
+
+    fn get_modifier<T: Copy + Default>(&self, molecule: &Molecule, state: &[T]) -> T {
+        self.modifiers.iter()
+            .find(|(modifier, _)| modifier == molecule)
+            .map(|(_, id)| state[*id])
+            .unwrap_or_default()
+    }
+
+    pub fn apply(&self, state: &mut [u32]) {
+        for (molecule, change) in self.changes.iter() {
+            state[*molecule] = (state[*molecule] as i64 + change) as u32;
+        }
+    }
+}
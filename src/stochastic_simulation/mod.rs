@@ -1,17 +1,86 @@
-mod pages {
-    mod enzymatic_activity;
-    mod lotka_volterra;
-    mod negative_feedback_loop;
+pub mod pages {
+    pub mod enzymatic_activity;
+    pub mod lotka_volterra;
+    pub mod negative_feedback_loop;
+    pub mod spatial_lotka_volterra;
+    mod trajectory_chart;
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod commands;
+
 pub mod prelude {
     pub use super::stochastic_simulation_algorithm::*;
+    pub use super::next_reaction_method::*;
+    pub use super::tau_leaping::*;
+    pub use super::chemical_langevin::*;
+    pub use super::delayed_simulation::*;
+    pub use super::ensemble::*;
+    pub use super::approximate_bayesian_computation::*;
     pub use super::enzymatic_activity::*;
     pub use super::lotka_volterra::*;
     pub use super::negative_feedback_loop::*;
+    pub use super::gene_expression::*;
+    pub use super::spatial_simulation::*;
+    pub use super::spatial_lotka_volterra::*;
+    pub use super::{SSAMethod, SSAEngine};
 }
 
 mod stochastic_simulation_algorithm;
+mod indexed_network;
+mod next_reaction_method;
+mod tau_leaping;
+mod chemical_langevin;
+mod delayed_simulation;
+mod ensemble;
+mod approximate_bayesian_computation;
 mod enzymatic_activity;
 mod lotka_volterra;
-mod negative_feedback_loop;
\ No newline at end of file
+mod negative_feedback_loop;
+mod gene_expression;
+mod spatial_simulation;
+mod spatial_lotka_volterra;
+
+use crate::chemical_reactions::prelude::*;
+use prelude::*;
+
+#[derive(Clone, Copy)]
+#[derive(Default)]
+pub enum SSAMethod {
+    #[default]
+    Direct,
+    NextReaction,
+    TauLeaping,
+}
+
+impl SSAMethod {
+    pub fn from_string(str: String) -> Option<Self> {
+        match str.as_str() {
+            "direct" => Some(Self::Direct),
+            "nrm" => Some(Self::NextReaction),
+            "tau" => Some(Self::TauLeaping),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum SSAEngine {
+    Direct(StochasticSimulation),
+    NextReaction(NextReactionSimulation),
+    TauLeaping(TauLeapingSimulation),
+    Delayed(DelayedSimulation),
+}
+
+impl Iterator for SSAEngine {
+    type Item = (f32, Vec<(Molecule, u32)>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            SSAEngine::Direct(simulation) => simulation.next(),
+            SSAEngine::NextReaction(simulation) => simulation.next(),
+            SSAEngine::TauLeaping(simulation) => simulation.next(),
+            SSAEngine::Delayed(simulation) => simulation.next(),
+        }
+    }
+}
\ No newline at end of file
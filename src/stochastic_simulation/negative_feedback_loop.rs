@@ -43,6 +43,28 @@ impl NegativeFeedbackLoop {
         StochasticSimulation::new(reactions, initial_state, simulation_seed)
     }
 
+    // The proteins are released a delay after the production reactions fire, lumping
+    // together their transcription and translation.
+    pub fn make_delayed_ode(initial_state: (u32, u32, u32), production_rates: (f32, f32, f32), binding_rates: (f32, f32, f32), unbinding_rates: (f32, f32, f32), decay_rates: (f32, f32, f32), production_delay: f32, max_time: f32) -> ODESimulation {
+        let reactions = Self::reactions(production_rates, binding_rates, unbinding_rates, decay_rates);
+        let delays = Self::production_delays(production_delay);
+        let initial_state = Self::initial_state(initial_state);
+
+        ODESimulation::new_delayed(reactions, &delays, initial_state, max_time).unwrap()
+    }
+
+    pub fn make_delayed_ssa(initial_state: (u32, u32, u32), production_rates: (f32, f32, f32), binding_rates: (f32, f32, f32), unbinding_rates: (f32, f32, f32), decay_rates: (f32, f32, f32), production_delay: ReactionDelay, simulation_seed: u64) -> SSAEngine {
+        let delays = Self::production_delays(production_delay);
+
+        Self::make_ssa(initial_state, production_rates, binding_rates, unbinding_rates, decay_rates, simulation_seed)
+            .with_delays(delays)
+    }
+
+    // the three production reactions come first, the other nine are instantaneous
+    fn production_delays<T: Copy>(delay: T) -> Vec<Option<T>> {
+        (0..12).map(|reaction_id| (reaction_id < 3).then_some(delay)).collect()
+    }
+
     pub fn species() -> ((Molecule, Molecule, Molecule), (Molecule, Molecule, Molecule), (Molecule, Molecule, Molecule)) {
         let g = (Molecule::new("g1"), Molecule::new("g2"), Molecule::new("g3"));
         let p = (Molecule::new("p1"), Molecule::new("p2"), Molecule::new("p3"));
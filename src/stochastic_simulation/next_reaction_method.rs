@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use rand::{Rng, SeedableRng};
+use crate::chemical_reactions::prelude::*;
+use crate::stochastic_simulation::indexed_network::*;
+
+// Gibson-Bruck Next Reaction Method: every reaction keeps its own putative firing
+// time in an indexed priority queue, and after each event only the reactions whose
+// propensity depends on species changed by the fired reaction are recomputed.
+#[derive(Clone)]
+pub struct NextReactionSimulation {
+    network: IndexedNetwork,
+    dependencies: Vec<Vec<usize>>,
+    rng: rand::rngs::SmallRng,
+
+    state: Option<(f32, Vec<u32>)>,
+    propensities: Vec<f32>,
+    queue: IndexedPriorityQueue,
+    fix_point: bool,
+}
+
+impl NextReactionSimulation {
+    pub fn new(reactions: Vec<Reaction>, initial_state: HashMap<Molecule, u32>, seed: u64) -> Self {
+        Self::from_parts(reactions, initial_state, rand::rngs::SmallRng::seed_from_u64(seed))
+    }
+
+    pub(crate) fn from_parts(reactions: Vec<Reaction>, initial_state: HashMap<Molecule, u32>, rng: rand::rngs::SmallRng) -> Self {
+        let network = IndexedNetwork::new(&reactions, &initial_state);
+        let reactions = &network.reactions;
+
+        // reaction j depends on reaction i if i changes the amount of one of the species j's propensity depends on
+        let dependencies = reactions.iter().enumerate()
+            .map(|(i, reaction)| {
+                reactions.iter().enumerate()
+                    .filter(|(j, other)| *j == i || other.get_dependencies()
+                        .any(|molecule| reaction.changes.iter().any(|(changed, _)| *changed == molecule))
+                    )
+                    .map(|(j, _)| j)
+                    .collect()
+            })
+            .collect();
+
+        let num_reactions = reactions.len();
+        Self {
+            network,
+            dependencies,
+            rng,
+            state: None,
+            propensities: vec![0f32; num_reactions],
+            queue: IndexedPriorityQueue::new(num_reactions),
+            fix_point: false,
+        }
+    }
+
+    fn sample_firing_time(&mut self, time: f32, propensity: f32) -> f32 {
+        if propensity > 0f32 {
+            let distribution = rand_distr::Exp::new(propensity).unwrap();
+            time + self.rng.sample(distribution)
+        } else {
+            f32::INFINITY
+        }
+    }
+}
+
+impl Iterator for NextReactionSimulation {
+    type Item = (f32, Vec<(Molecule, u32)>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.fix_point {
+            return None;
+        }
+
+        let Some((_, mut state)) = self.state.take() else {
+            // initialize propensities and putative firing times
+            let state = self.network.initial_state.clone();
+            for reaction in 0..self.network.reactions.len() {
+                let propensity = self.network.reactions[reaction].get_propensity(&state);
+                let firing_time = self.sample_firing_time(0f32, propensity);
+
+                self.propensities[reaction] = propensity;
+                self.queue.update(reaction, firing_time);
+            }
+
+            let output = self.network.output(0f32, &state);
+            self.state = Some((0f32, state));
+            return Some(output);
+        };
+
+        let Some((chosen_reaction, time)) = self.queue.peek().filter(|(_, time)| time.is_finite()) else {
+            // no reaction can be applied
+            self.fix_point = true;
+            return None;
+        };
+
+        self.network.reactions[chosen_reaction].apply(&mut state);
+
+        for dependent in self.dependencies[chosen_reaction].clone() {
+            let old_propensity = self.propensities[dependent];
+            let new_propensity = self.network.reactions[dependent].get_propensity(&state);
+
+            let firing_time =
+                if dependent != chosen_reaction && old_propensity > 0f32 && new_propensity > 0f32 {
+                    // rescale the remaining waiting time instead of drawing a new one
+                    time + (old_propensity / new_propensity) * (self.queue.key(dependent) - time)
+                } else {
+                    self.sample_firing_time(time, new_propensity)
+                };
+
+            self.propensities[dependent] = new_propensity;
+            self.queue.update(dependent, firing_time);
+        }
+
+        let output = self.network.output(time, &state);
+        self.state = Some((time, state));
+        Some(output)
+    }
+}
+
+// binary min-heap over reaction ids, keeping track of each reaction position so that keys can be updated in place
+#[derive(Clone)]
+pub(crate) struct IndexedPriorityQueue {
+    heap: Vec<usize>,
+    positions: Vec<usize>,
+    keys: Vec<f32>,
+}
+
+impl IndexedPriorityQueue {
+    pub fn new(size: usize) -> Self {
+        Self {
+            heap: (0..size).collect(),
+            positions: (0..size).collect(),
+            keys: vec![f32::INFINITY; size],
+        }
+    }
+
+    pub fn peek(&self) -> Option<(usize, f32)> {
+        self.heap.first().map(|&id| (id, self.keys[id]))
+    }
+
+    pub fn key(&self, id: usize) -> f32 {
+        self.keys[id]
+    }
+
+    pub fn update(&mut self, id: usize, key: f32) {
+        self.keys[id] = key;
+        let position = self.sift_up(self.positions[id]);
+        self.sift_down(position);
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.positions[self.heap[a]] = a;
+        self.positions[self.heap[b]] = b;
+    }
+
+    fn sift_up(&mut self, mut position: usize) -> usize {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if self.keys[self.heap[parent]] <= self.keys[self.heap[position]] {
+                break;
+            }
+
+            self.swap(parent, position);
+            position = parent;
+        }
+
+        position
+    }
+
+    fn sift_down(&mut self, mut position: usize) {
+        loop {
+            let mut smallest = position;
+            for child in [2 * position + 1, 2 * position + 2] {
+                if child < self.heap.len() && self.keys[self.heap[child]] < self.keys[self.heap[smallest]] {
+                    smallest = child;
+                }
+            }
+
+            if smallest == position {
+                return;
+            }
+
+            self.swap(smallest, position);
+            position = smallest;
+        }
+    }
+}
@@ -6,11 +6,13 @@ use crate::prelude::*;
 use crate::continuous_dynamical_systems::ODESolver;
 use crate::chemical_reactions::prelude::*;
 use crate::stochastic_simulation::prelude::*;
+use super::trajectory_chart::*;
 
 #[wasm_bindgen(js_name = SSA_LV)]
 pub struct Model { }
 
 #[wasm_bindgen(js_name = SSA_LV_Params)]
+#[derive(Clone)]
 #[derive(Default)]
 pub struct Params {
     solver: ODESolver,
@@ -21,20 +23,22 @@ pub struct Params {
     predator_death_rate: f32,
     hunting_meetings: f32,
     hunt_offsprings: u32,
+    ssa_method: SSAMethod,
     seed: u64,
+    replicas: u32,
 }
 
+// Trajectory animated on the page, advanced frame by frame without rerunning it.
+#[wasm_bindgen(js_name = SSA_LV_Stream)]
+pub struct Stream(TrajectoryStream);
+
 #[wasm_bindgen(js_class = SSA_LV)]
 impl Model {
     pub fn draw(canvas: HtmlCanvasElement, algorithm: String, params: Params) -> Result<(), JsValue> {
-        match algorithm.as_str() {
-            "ode" => draw_generic(Self::draw_ode)(canvas, params),
-            "ssa" => draw_generic(Self::draw_ssa)(canvas, params),
-            _ => Err(format!("Algorithm {algorithm} not supported").into()),
-        }
+        draw_generic(|area, params| (algorithm.as_str(), params).draw(area))(canvas, params)
     }
 
-    fn draw_ode(canvas: HtmlCanvasElement, params: Params) -> MyDrawResult<()> {
+    fn draw_ode<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
         let max_time = params.max_time;
         let model = params.to_ode_model();
 
@@ -50,13 +54,12 @@ impl Model {
             )
             .reduce(f32::max).unwrap_or(0f32) * 1.5f32;
 
-        let area = draw_prelude(canvas)?;
         area.fill(&WHITE)?;
     
         let x_axis_range = 0f32..max_time;
         let y_axis_range = 0f32..max_population_display;
     
-        let mut chart = ChartBuilder::on(&area)
+        let mut chart = ChartBuilder::on(area)
             .margin(20u32)
             .x_label_area_size(30u32)
             .y_label_area_size(30u32)
@@ -103,7 +106,7 @@ impl Model {
         Ok(())
     }
 
-    fn draw_ssa(canvas: HtmlCanvasElement, params: Params) -> MyDrawResult<()> {
+    fn draw_ssa<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
         let max_time = params.max_time;
         let model = params.to_ssa_model();
 
@@ -119,13 +122,12 @@ impl Model {
             )
             .reduce(f32::max).unwrap_or(0f32) * 1.5f32) as u32;
 
-        let area = draw_prelude(canvas)?;
         area.fill(&WHITE)?;
     
         let x_axis_range = 0f32..max_time;
         let y_axis_range = 0..max_population_display;
     
-        let mut chart = ChartBuilder::on(&area)
+        let mut chart = ChartBuilder::on(area)
             .margin(20u32)
             .x_label_area_size(30u32)
             .y_label_area_size(30u32)
@@ -171,6 +173,93 @@ impl Model {
     
         Ok(())
     }
+
+    fn draw_cle<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        let max_time = params.max_time;
+        let trajectory = langevin_trajectory(params.into_cle_model(), max_time);
+
+        draw_trajectories(area, max_time, &Self::species_legend(), vec![("", trajectory)])
+    }
+
+    fn draw_compare<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        let max_time = params.max_time;
+        let ode = ode_trajectory(params.clone().to_ode_model(), max_time);
+        let cle = langevin_trajectory(params.clone().into_cle_model(), max_time);
+        let ssa = ssa_trajectory(params.to_ssa_model(), max_time);
+
+        draw_trajectories(area, max_time, &Self::species_legend(), vec![("ODE", ode), ("CLE", cle), ("SSA", ssa)])
+    }
+
+    fn draw_ensemble<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        let max_time = params.max_time;
+        let ode = ode_trajectory(params.clone().to_ode_model(), max_time);
+        let ensemble = params.into_ensemble();
+
+        draw_ensemble(area, max_time, &Self::species_legend(), ode, &ensemble)
+    }
+
+    fn species_legend() -> Vec<(Molecule, &'static str, RGBColor)> {
+        let (prey, predator) = LotkaVolterra::species();
+        vec![(prey, "V (Preys)", RED), (predator, "P (Predators)", BLUE)]
+    }
+}
+
+impl Chart for (&str, Params) {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        let (algorithm, params) = self;
+
+        match algorithm {
+            "ode" => Model::draw_ode(area, params),
+            "ssa" => Model::draw_ssa(area, params),
+            "cle" => Model::draw_cle(area, params),
+            "compare" => Model::draw_compare(area, params),
+            "ensemble" => Model::draw_ensemble(area, params),
+            _ => Err(format!("Algorithm {algorithm} not supported").into()),
+        }
+    }
+}
+
+#[wasm_bindgen(js_class = SSA_LV_Stream)]
+impl Stream {
+    pub fn build(algorithm: String, params: Params) -> Result<Stream, JsValue> {
+        let max_time = params.max_time;
+        let species = Model::species_legend();
+
+        let stream = match algorithm.as_str() {
+            "ode" => TrajectoryStream::ode(params.to_ode_model(), max_time, species),
+            "ssa" => TrajectoryStream::ssa(params.to_ssa_model(), max_time, species),
+            "cle" => TrajectoryStream::langevin(params.into_cle_model(), max_time, species),
+            _ => return Err(format!("Algorithm {algorithm} cannot be animated").into()),
+        };
+
+        Ok(Self(stream))
+    }
+
+    pub fn advance_steps(&mut self, steps: u32) {
+        self.0.advance_steps(steps as usize);
+    }
+
+    pub fn advance_time(&mut self, time_step: f32) {
+        self.0.advance_time(time_step);
+    }
+
+    pub fn time(&self) -> f32 {
+        self.0.time()
+    }
+
+    pub fn finished(&mut self) -> bool {
+        self.0.is_finished()
+    }
+
+    // draws the states computed since the last call
+    pub fn draw(&mut self, canvas: HtmlCanvasElement) -> Result<(), JsValue> {
+        draw_canvas(canvas, &mut self.0)
+    }
+
+    pub fn redraw(&mut self, canvas: HtmlCanvasElement) -> Result<(), JsValue> {
+        self.0.invalidate();
+        draw_canvas(canvas, &mut self.0)
+    }
 }
 
 #[wasm_bindgen(js_class = SSA_LV_Params)]
@@ -217,11 +306,21 @@ impl Params {
         self
     }
 
+    pub fn ssa_method(mut self, method: String) -> Self {
+        self.ssa_method = SSAMethod::from_string(method).unwrap();
+        self
+    }
+
     pub fn ssa_seed(mut self, seed: u64) -> Self {
         self.seed = seed;
         self
     }
 
+    pub fn ssa_replicas(mut self, replicas: u32) -> Self {
+        self.replicas = replicas;
+        self
+    }
+
     fn to_ode_model(self) -> ODESimulation {
         LotkaVolterra::make_ode(
             self.initial_prey_pop,
@@ -235,7 +334,7 @@ impl Params {
         )
     }
 
-    fn to_ssa_model(self) -> StochasticSimulation {
+    fn to_ssa_model(self) -> SSAEngine {
         LotkaVolterra::make_ssa(
             self.initial_prey_pop,
             self.initial_predator_pop,
@@ -244,6 +343,24 @@ impl Params {
             self.hunting_meetings,
             self.hunt_offsprings,
             self.seed,
-        )
+        ).with_method(self.ssa_method)
+    }
+
+    fn into_cle_model(self) -> LangevinSimulation {
+        LotkaVolterra::make_ssa(
+            self.initial_prey_pop,
+            self.initial_predator_pop,
+            self.prey_birth_rate,
+            self.predator_death_rate,
+            self.hunting_meetings,
+            self.hunt_offsprings,
+            self.seed,
+        ).to_langevin(LANGEVIN_TIME_STEP)
+    }
+
+    fn into_ensemble(self) -> EnsembleStatistics {
+        Ensemble::new(self.replicas, self.seed, self.max_time, ENSEMBLE_GRID_POINTS)
+            .quantiles(ensemble_quantiles())
+            .run(|seed| Params { seed, ..self.clone() }.to_ssa_model())
     }
 }
\ No newline at end of file
@@ -6,11 +6,13 @@ use crate::prelude::*;
 use crate::continuous_dynamical_systems::ODESolver;
 use crate::chemical_reactions::prelude::*;
 use crate::stochastic_simulation::prelude::*;
+use super::trajectory_chart::*;
 
 #[wasm_bindgen(js_name = SSA_NFL)]
 pub struct Model { }
 
 #[wasm_bindgen(js_name = SSA_NFL_Params)]
+#[derive(Clone)]
 #[derive(Default)]
 pub struct Params {
     solver: ODESolver,
@@ -20,20 +22,24 @@ pub struct Params {
     binding_rates: (f32, f32, f32),
     unbinding_rates: (f32, f32, f32),
     decay_rates: (f32, f32, f32),
+    production_delay: f32,
+    delay_shape: f32,
+    ssa_method: SSAMethod,
     seed: u64,
+    replicas: u32,
 }
 
+// Trajectory animated on the page, advanced frame by frame without rerunning it.
+#[wasm_bindgen(js_name = SSA_NFL_Stream)]
+pub struct Stream(TrajectoryStream);
+
 #[wasm_bindgen(js_class = SSA_NFL)]
 impl Model {
     pub fn draw(canvas: HtmlCanvasElement, algorithm: String, params: Params) -> Result<(), JsValue> {
-        match algorithm.as_str() {
-            "ode" => draw_generic(Self::draw_ode)(canvas, params),
-            "ssa" => draw_generic(Self::draw_ssa)(canvas, params),
-            _ => Err(format!("Algorithm {algorithm} not supported").into()),
-        }
+        draw_generic(|area, params| (algorithm.as_str(), params).draw(area))(canvas, params)
     }
 
-    fn draw_ode(canvas: HtmlCanvasElement, params: Params) -> MyDrawResult<()> {
+    fn draw_ode<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
         let max_time = params.max_time;
         let model = params.to_ode_model();
 
@@ -51,13 +57,12 @@ impl Model {
             )
             .reduce(f32::max).unwrap_or(0f32) * 1.15f32;
 
-        let area = draw_prelude(canvas)?;
         area.fill(&WHITE)?;
     
         let x_axis_range = 0f32..max_time;
         let y_axis_range = 0f32..max_population_display;
     
-        let mut chart = ChartBuilder::on(&area)
+        let mut chart = ChartBuilder::on(area)
             .margin(20u32)
             .x_label_area_size(30u32)
             .y_label_area_size(30u32)
@@ -117,7 +122,7 @@ impl Model {
         Ok(())
     }
 
-    fn draw_ssa(canvas: HtmlCanvasElement, params: Params) -> MyDrawResult<()> {
+    fn draw_ssa<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
         let max_time = params.max_time;
         let model = params.to_ssa_model();
 
@@ -135,13 +140,12 @@ impl Model {
             )
             .reduce(f32::max).unwrap_or(0f32) * 1.5f32) as u32;
 
-        let area = draw_prelude(canvas)?;
         area.fill(&WHITE)?;
     
         let x_axis_range = 0f32..max_time;
         let y_axis_range = 0..max_population_display;
     
-        let mut chart = ChartBuilder::on(&area)
+        let mut chart = ChartBuilder::on(area)
             .margin(20u32)
             .x_label_area_size(30u32)
             .y_label_area_size(30u32)
@@ -200,6 +204,103 @@ impl Model {
     
         Ok(())
     }
+
+    fn draw_cle<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        if params.delay().is_some() {
+            return Err("The chemical Langevin equation does not support delays".into());
+        }
+
+        let max_time = params.max_time;
+        let trajectory = langevin_trajectory(params.into_cle_model(), max_time);
+
+        draw_trajectories(area, max_time, &Self::species_legend(), vec![("", trajectory)])
+    }
+
+    fn draw_compare<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        let max_time = params.max_time;
+        let ode = ode_trajectory(params.clone().to_ode_model(), max_time);
+        let ssa = ssa_trajectory(params.clone().to_ssa_model(), max_time);
+
+        // the delayed reactions have no chemical Langevin equation
+        if params.delay().is_some() {
+            return draw_trajectories(area, max_time, &Self::species_legend(), vec![("DDE", ode), ("SSA", ssa)]);
+        }
+
+        let cle = langevin_trajectory(params.into_cle_model(), max_time);
+        draw_trajectories(area, max_time, &Self::species_legend(), vec![("ODE", ode), ("CLE", cle), ("SSA", ssa)])
+    }
+
+    fn draw_ensemble<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, params: Params) -> MyDrawResult<()> {
+        let max_time = params.max_time;
+        let ode = ode_trajectory(params.clone().to_ode_model(), max_time);
+        let ensemble = params.into_ensemble();
+
+        draw_ensemble(area, max_time, &Self::species_legend(), ode, &ensemble)
+    }
+
+    fn species_legend() -> Vec<(Molecule, &'static str, RGBColor)> {
+        let (_, (p1, p2, p3), _) = NegativeFeedbackLoop::species();
+        vec![(p1, "P1", RED), (p2, "P2", BLUE), (p3, "P3", GREEN)]
+    }
+}
+
+impl Chart for (&str, Params) {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        let (algorithm, params) = self;
+
+        match algorithm {
+            "ode" => Model::draw_ode(area, params),
+            "ssa" => Model::draw_ssa(area, params),
+            "cle" => Model::draw_cle(area, params),
+            "compare" => Model::draw_compare(area, params),
+            "ensemble" => Model::draw_ensemble(area, params),
+            _ => Err(format!("Algorithm {algorithm} not supported").into()),
+        }
+    }
+}
+
+#[wasm_bindgen(js_class = SSA_NFL_Stream)]
+impl Stream {
+    pub fn build(algorithm: String, params: Params) -> Result<Stream, JsValue> {
+        let max_time = params.max_time;
+        let species = Model::species_legend();
+
+        let stream = match algorithm.as_str() {
+            "ode" => TrajectoryStream::ode(params.to_ode_model(), max_time, species),
+            "ssa" => TrajectoryStream::ssa(params.to_ssa_model(), max_time, species),
+            "cle" if params.delay().is_some() => return Err("The chemical Langevin equation does not support delays".into()),
+            "cle" => TrajectoryStream::langevin(params.into_cle_model(), max_time, species),
+            _ => return Err(format!("Algorithm {algorithm} cannot be animated").into()),
+        };
+
+        Ok(Self(stream))
+    }
+
+    pub fn advance_steps(&mut self, steps: u32) {
+        self.0.advance_steps(steps as usize);
+    }
+
+    pub fn advance_time(&mut self, time_step: f32) {
+        self.0.advance_time(time_step);
+    }
+
+    pub fn time(&self) -> f32 {
+        self.0.time()
+    }
+
+    pub fn finished(&mut self) -> bool {
+        self.0.is_finished()
+    }
+
+    // draws the states computed since the last call
+    pub fn draw(&mut self, canvas: HtmlCanvasElement) -> Result<(), JsValue> {
+        draw_canvas(canvas, &mut self.0)
+    }
+
+    pub fn redraw(&mut self, canvas: HtmlCanvasElement) -> Result<(), JsValue> {
+        self.0.invalidate();
+        draw_canvas(canvas, &mut self.0)
+    }
 }
 
 #[wasm_bindgen(js_class = SSA_NFL_Params)]
@@ -241,12 +342,55 @@ impl Params {
         self
     }
 
+    // mean delay between the production reactions and the release of the proteins, none if 0
+    pub fn production_delay(mut self, production_delay: f32) -> Self {
+        self.production_delay = production_delay;
+        self
+    }
+
+    // gamma distributed delays of the SSA with the given shape, fixed delays if 0
+    pub fn delay_shape(mut self, delay_shape: f32) -> Self {
+        self.delay_shape = delay_shape;
+        self
+    }
+
+    pub fn ssa_method(mut self, method: String) -> Self {
+        self.ssa_method = SSAMethod::from_string(method).unwrap();
+        self
+    }
+
     pub fn ssa_seed(mut self, seed: u64) -> Self {
         self.seed = seed;
         self
     }
 
+    pub fn ssa_replicas(mut self, replicas: u32) -> Self {
+        self.replicas = replicas;
+        self
+    }
+
+    fn delay(&self) -> Option<ReactionDelay> {
+        match (self.production_delay, self.delay_shape) {
+            (delay, _) if delay <= 0f32 => None,
+            (delay, shape) if shape <= 0f32 => Some(ReactionDelay::Fixed(delay)),
+            (mean, shape) => Some(ReactionDelay::Gamma { mean, shape }),
+        }
+    }
+
+    // with delays, integrated as delay differential equations whatever the solver
     fn to_ode_model(self) -> ODESimulation {
+        if self.delay().is_some() {
+            return NegativeFeedbackLoop::make_delayed_ode(
+                self.initial_state,
+                self.production_rates,
+                self.binding_rates,
+                self.unbinding_rates,
+                self.decay_rates,
+                self.production_delay,
+                self.max_time
+            );
+        }
+
         NegativeFeedbackLoop::make_ode(
             self.initial_state,
             self.production_rates,
@@ -258,7 +402,20 @@ impl Params {
         )
     }
 
-    fn to_ssa_model(self) -> StochasticSimulation {
+    // with delays, simulated by the delayed direct method whatever the ssa method
+    fn to_ssa_model(self) -> SSAEngine {
+        if let Some(delay) = self.delay() {
+            return NegativeFeedbackLoop::make_delayed_ssa(
+                self.initial_state,
+                self.production_rates,
+                self.binding_rates,
+                self.unbinding_rates,
+                self.decay_rates,
+                delay,
+                self.seed,
+            );
+        }
+
         NegativeFeedbackLoop::make_ssa(
             self.initial_state,
             self.production_rates,
@@ -266,6 +423,23 @@ impl Params {
             self.unbinding_rates,
             self.decay_rates,
             self.seed,
-        )
+        ).with_method(self.ssa_method)
+    }
+
+    fn into_cle_model(self) -> LangevinSimulation {
+        NegativeFeedbackLoop::make_ssa(
+            self.initial_state,
+            self.production_rates,
+            self.binding_rates,
+            self.unbinding_rates,
+            self.decay_rates,
+            self.seed,
+        ).to_langevin(LANGEVIN_TIME_STEP)
+    }
+
+    fn into_ensemble(self) -> EnsembleStatistics {
+        Ensemble::new(self.replicas, self.seed, self.max_time, ENSEMBLE_GRID_POINTS)
+            .quantiles(ensemble_quantiles())
+            .run(|seed| Params { seed, ..self.clone() }.to_ssa_model())
     }
 }
\ No newline at end of file
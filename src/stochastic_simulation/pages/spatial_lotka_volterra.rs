@@ -0,0 +1,169 @@
+use image::RgbImage;
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlCanvasElement;
+
+use crate::prelude::*;
+use crate::cellular_automata::prelude::*;
+use crate::stochastic_simulation::prelude::*;
+
+// time between two frames of the page
+const FRAME_INTERVAL: f32 = 0.2;
+
+#[wasm_bindgen(js_name = SSA_SLV)]
+pub struct Model {
+    states: Vec<RgbImage>,
+    size: (u32, u32),
+}
+
+#[wasm_bindgen(js_name = SSA_SLV_Params)]
+#[derive(Default)]
+pub struct Params {
+    size: u32,
+    max_time: f32,
+    initial_prey_pop: u32,
+    initial_predator_pop: u32,
+    prey_birth_rate: f32,
+    prey_crowding: f32,
+    predator_death_rate: f32,
+    hunting_meetings: f32,
+    prey_diffusion: f32,
+    predator_diffusion: f32,
+    seed: u64,
+}
+
+#[wasm_bindgen(js_class = SSA_SLV)]
+impl Model {
+    pub fn build(params: Params) -> Result<Model, JsValue> {
+        let frames = (params.max_time / FRAME_INTERVAL).ceil() as usize + 1;
+        let model = params.into_model();
+        let (prey, predator) = LotkaVolterra::species();
+        let (prey, predator) = (model.get_species_id(&prey).unwrap(), model.get_species_id(&predator).unwrap());
+
+        let lattices: Vec<_> = model.take(frames).map(|(_, lattice)| lattice).collect();
+
+        // the colors are scaled on the largest population reached by each species
+        let max_population = |species: usize| lattices.iter()
+            .flat_map(|lattice| lattice.cells.iter().map(move |voxel| voxel[species]))
+            .max().unwrap_or(0).max(1);
+        let (max_preys, max_predators) = (max_population(prey), max_population(predator));
+
+        let states: Vec<_> = lattices.iter()
+            .map(|lattice| Self::lattice_to_image(lattice, (prey, max_preys), (predator, max_predators)))
+            .collect();
+
+        let size = (states[0].width(), states[0].height());
+        Ok(Self { states, size })
+    }
+
+    pub fn max_step(&self) -> JsValue {
+        JsValue::from_f64((self.states.len() - 1) as f64)
+    }
+
+    pub fn frame_interval() -> f32 {
+        FRAME_INTERVAL
+    }
+
+    pub fn draw(&mut self, canvas: HtmlCanvasElement, step: u32) -> Result<(), JsValue> {
+        canvas.set_width(self.size.0);
+        canvas.set_height(self.size.1);
+
+        draw_generic(|area, step| (&*self, step).draw(area))(canvas, step)
+    }
+
+    fn draw_function<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, image: &RgbImage) -> MyDrawResult<()> {
+        draw_image(area, image)
+    }
+
+    // preys in green, predators in red
+    fn lattice_to_image(lattice: &Lattice<Voxel>, (prey, max_preys): (usize, u32), (predator, max_predators): (usize, u32)) -> RgbImage {
+        let size = lattice.size();
+        let intensity = |amount: u32, max_amount: u32| (255 * amount.min(max_amount) / max_amount) as u8;
+
+        RgbImage::from_fn(size.0, size.1, |x, y| {
+            let voxel = lattice.get(x, y).unwrap();
+            image::Rgb([intensity(voxel[predator], max_predators), intensity(voxel[prey], max_preys), 0])
+        })
+    }
+}
+
+impl Chart for (&Model, u32) {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        let (model, step) = self;
+        let step = usize::min(model.states.len() - 1, step as usize);
+
+        Model::draw_function(area, &model.states[step])
+    }
+}
+
+#[wasm_bindgen(js_class = SSA_SLV_Params)]
+impl Params {
+    pub fn builder() -> Self {
+        Self { ..Default::default() }
+    }
+
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn max_time(mut self, max_time: f32) -> Self {
+        self.max_time = max_time;
+        self
+    }
+
+    pub fn initial_prey_pop(mut self, initial_prey_pop: u32) -> Self {
+        self.initial_prey_pop = initial_prey_pop;
+        self
+    }
+
+    pub fn initial_predator_pop(mut self, initial_predator_pop: u32) -> Self {
+        self.initial_predator_pop = initial_predator_pop;
+        self
+    }
+
+    pub fn prey_birth_rate(mut self, prey_birth_rate: f32) -> Self {
+        self.prey_birth_rate = prey_birth_rate;
+        self
+    }
+
+    pub fn prey_crowding(mut self, prey_crowding: f32) -> Self {
+        self.prey_crowding = prey_crowding;
+        self
+    }
+
+    pub fn predator_death_rate(mut self, predator_death_rate: f32) -> Self {
+        self.predator_death_rate = predator_death_rate;
+        self
+    }
+
+    pub fn hunting_meetings(mut self, hunting_meetings: f32) -> Self {
+        self.hunting_meetings = hunting_meetings;
+        self
+    }
+
+    pub fn prey_diffusion(mut self, prey_diffusion: f32) -> Self {
+        self.prey_diffusion = prey_diffusion;
+        self
+    }
+
+    pub fn predator_diffusion(mut self, predator_diffusion: f32) -> Self {
+        self.predator_diffusion = predator_diffusion;
+        self
+    }
+
+    pub fn simulation_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    fn into_model(self) -> SpatialSimulation {
+        SpatialLotkaVolterra::make_ssa(
+            self.size,
+            (self.initial_prey_pop, self.initial_predator_pop),
+            (self.prey_birth_rate, self.prey_crowding, self.predator_death_rate, self.hunting_meetings),
+            (self.prey_diffusion, self.predator_diffusion),
+            FRAME_INTERVAL,
+            self.seed,
+        )
+    }
+}
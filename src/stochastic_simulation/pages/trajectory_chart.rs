@@ -0,0 +1,284 @@
+use plotters::prelude::*;
+use plotters::coord::types::RangedCoordf32;
+
+use crate::prelude::*;
+use crate::chemical_reactions::prelude::*;
+use crate::stochastic_simulation::prelude::*;
+
+// Step of the Euler-Maruyama integration of the chemical Langevin equation.
+pub const LANGEVIN_TIME_STEP: f32 = 0.001;
+
+pub type Trajectory = Vec<(f32, Vec<(Molecule, f32)>)>;
+
+pub fn ode_trajectory(model: ODESimulation, max_time: f32) -> Trajectory {
+    Simulation::new(model.into_iter())
+        .time_limit(max_time)
+        .collect()
+}
+
+pub fn langevin_trajectory(model: LangevinSimulation, max_time: f32) -> Trajectory {
+    Simulation::new(model)
+        .fix_point(max_time + 1f32)
+        .time_limit(max_time)
+        .collect()
+}
+
+pub fn ssa_trajectory(model: SSAEngine, max_time: f32) -> Trajectory {
+    Simulation::new(model)
+        .fix_point(max_time + 1f32)
+        .time_limit(max_time)
+        .map(|(time, pops)| (time, pops.into_iter().map(|(mol, q)| (mol, q as f32)).collect()))
+        .collect()
+}
+
+// Draws the given species for each trajectory on the same chart. The first trajectory
+// is drawn with thick lines, the following ones with increasingly lighter colors.
+pub fn draw_trajectories<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, max_time: f32, species: &[(Molecule, &str, RGBColor)], trajectories: Vec<(&str, Trajectory)>) -> MyDrawResult<()> {
+    let max_population_display = trajectories.iter()
+        .flat_map(|(_, trajectory)| trajectory.iter())
+        .flat_map(|(_, pops)| pops.iter()
+            .filter(|(mol, _)| species.iter().any(|(other, _, _)| mol == other))
+            .map(|(_, q)| *q)
+        )
+        .reduce(f32::max).unwrap_or(0f32) * 1.5f32;
+
+    area.fill(&WHITE)?;
+
+    let mut chart = trajectory_chart(area, max_time, max_population_display)?;
+
+    chart.configure_mesh()
+        .x_desc("t")
+        .y_desc("N(t)")
+        .x_labels(max_time as usize)
+        .y_labels(10)
+        .draw()?;
+
+    for (level, (name, trajectory)) in trajectories.iter().enumerate() {
+        for (molecule, label, color) in species.iter() {
+            let style = color.mix(1f64 - 0.3f64 * level as f64)
+                .stroke_width(if level == 0 { 2 } else { 1 });
+
+            let label =
+                if name.is_empty() { label.to_string() }
+                else { format!("{label}, {name}") };
+
+            chart.draw_series(LineSeries::new(
+                trajectory.iter().map(|(x, pops)| {
+                    let quantity = pops.iter()
+                        .find(|(mol, _)| mol == molecule).unwrap().1;
+
+                    (*x, quantity)
+                }),
+                style
+            ))?
+            .label(label)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], style));
+        }
+    }
+
+    // draw legend
+    chart.configure_series_labels()
+        .background_style(WHITE)
+        .draw()?;
+
+    Ok(())
+}
+
+// The same coordinates on every call, so that a chart can be drawn over in more calls.
+fn trajectory_chart<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, max_time: f32, max_population_display: f32) -> MyDrawResult<ChartContext<'_, DB, Cartesian2d<RangedCoordf32, RangedCoordf32>>> {
+    let x_axis_range = 0f32..max_time;
+    let y_axis_range = 0f32..f32::max(max_population_display, 1f32);
+
+    let chart = ChartBuilder::on(area)
+        .margin(20u32)
+        .x_label_area_size(30u32)
+        .y_label_area_size(30u32)
+        .build_cartesian_2d(x_axis_range, y_axis_range)?;
+
+    Ok(chart)
+}
+
+// Trajectory computed a frame at a time for the animations of the pages. The states are
+// kept, so each frame draws only the segments added since the previous one; the whole
+// chart is redrawn when the populations outgrow its vertical range.
+pub struct TrajectoryStream {
+    stream: Stream<f32, Vec<(Molecule, f32)>>,
+    max_time: f32,
+    species: Vec<(Molecule, &'static str, RGBColor)>,
+    trajectory: Trajectory,
+    drawn: usize,
+    max_population_display: f32,
+}
+
+impl TrajectoryStream {
+    pub fn ode(model: ODESimulation, max_time: f32, species: Vec<(Molecule, &'static str, RGBColor)>) -> Self {
+        Self::new(model.into_iter(), max_time, species)
+    }
+
+    pub fn langevin(model: LangevinSimulation, max_time: f32, species: Vec<(Molecule, &'static str, RGBColor)>) -> Self {
+        Self::new(model, max_time, species)
+    }
+
+    pub fn ssa(model: SSAEngine, max_time: f32, species: Vec<(Molecule, &'static str, RGBColor)>) -> Self {
+        let model = model.map(|(time, pops)| (time, pops.into_iter().map(|(mol, q)| (mol, q as f32)).collect()));
+        Self::new(model, max_time, species)
+    }
+
+    fn new<T>(model: T, max_time: f32, species: Vec<(Molecule, &'static str, RGBColor)>) -> Self
+        where T: Iterator<Item = (f32, Vec<(Molecule, f32)>)> + Clone + 'static
+    {
+        let stream = Simulation::new(model)
+            .fix_point(max_time + 1f32)
+            .time_limit(max_time)
+            .stream();
+
+        Self { stream, max_time, species, trajectory: Vec::new(), drawn: 0, max_population_display: 0f32 }
+    }
+
+    pub fn advance_steps(&mut self, steps: usize) {
+        let states = self.stream.advance(steps);
+        self.trajectory.extend(states);
+    }
+
+    pub fn advance_time(&mut self, time_step: f32) {
+        let states = self.stream.advance_until(self.time() + time_step);
+        self.trajectory.extend(states);
+    }
+
+    pub fn time(&self) -> f32 {
+        self.trajectory.last().map_or(0f32, |(time, _)| *time)
+    }
+
+    pub fn is_finished(&mut self) -> bool {
+        self.stream.is_finished()
+    }
+
+    // the next draw redraws the whole chart, e.g. after the canvas is resized
+    pub fn invalidate(&mut self) {
+        self.drawn = 0;
+    }
+}
+
+impl Chart for &mut TrajectoryStream {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()> {
+        let max_population = self.trajectory.iter()
+            .flat_map(|(_, pops)| pops.iter()
+                .filter(|(mol, _)| self.species.iter().any(|(other, _, _)| mol == other))
+                .map(|(_, q)| *q)
+            )
+            .reduce(f32::max).unwrap_or(0f32);
+
+        if self.drawn == 0 || max_population > self.max_population_display {
+            self.max_population_display = max_population * 1.5f32;
+            draw_trajectories(area, self.max_time, &self.species, vec![("", self.trajectory.clone())])?;
+        } else {
+            // the last drawn state starts the new segments
+            let mut chart = trajectory_chart(area, self.max_time, self.max_population_display)?;
+            for (molecule, _, color) in self.species.iter() {
+                chart.draw_series(LineSeries::new(
+                    self.trajectory[self.drawn - 1..].iter().map(|(x, pops)| {
+                        let quantity = pops.iter()
+                            .find(|(mol, _)| mol == molecule).unwrap().1;
+
+                        (*x, quantity)
+                    }),
+                    color.stroke_width(2)
+                ))?;
+            }
+        }
+
+        self.drawn = self.trajectory.len();
+        Ok(())
+    }
+}
+
+pub const ENSEMBLE_GRID_POINTS: usize = 200;
+
+// Quantile bands drawn around the ensemble mean, from the outermost to the innermost.
+pub const ENSEMBLE_BANDS: [(f32, f32); 2] = [(0.05, 0.95), (0.25, 0.75)];
+
+pub fn ensemble_quantiles() -> Vec<f32> {
+    ENSEMBLE_BANDS.iter()
+        .flat_map(|(lower, upper)| [*lower, *upper])
+        .collect()
+}
+
+// Draws the ODE curve of each species over the quantile bands and the mean of the ensemble.
+pub fn draw_ensemble<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, max_time: f32, species: &[(Molecule, &str, RGBColor)], ode: Trajectory, ensemble: &EnsembleStatistics) -> MyDrawResult<()> {
+    let max_population_display = species.iter()
+        .filter_map(|(molecule, _, _)| ensemble.get(molecule))
+        .flat_map(|statistics| statistics.quantiles.iter().flat_map(|(_, series)| series.iter()))
+        .chain(ode.iter().flat_map(|(_, pops)| pops.iter()
+            .filter(|(mol, _)| species.iter().any(|(other, _, _)| mol == other))
+            .map(|(_, q)| q)
+        ))
+        .cloned()
+        .reduce(f32::max).unwrap_or(0f32) * 1.5f32;
+
+    area.fill(&WHITE)?;
+
+    let x_axis_range = 0f32..max_time;
+    let y_axis_range = 0f32..f32::max(max_population_display, 1f32);
+
+    let mut chart = ChartBuilder::on(area)
+        .margin(20u32)
+        .x_label_area_size(30u32)
+        .y_label_area_size(30u32)
+        .build_cartesian_2d(x_axis_range, y_axis_range)?;
+
+    chart.configure_mesh()
+        .x_desc("t")
+        .y_desc("N(t)")
+        .x_labels(max_time as usize)
+        .y_labels(10)
+        .draw()?;
+
+    for (molecule, label, color) in species.iter() {
+        let Some(statistics) = ensemble.get(molecule) else {
+            continue;
+        };
+
+        // quantile bands
+        for (lower, upper) in ENSEMBLE_BANDS {
+            let (Some(lower_series), Some(upper_series)) = (statistics.get_quantile(lower), statistics.get_quantile(upper)) else {
+                continue;
+            };
+
+            let band: Vec<(f32, f32)> = ensemble.time.iter().cloned().zip(upper_series.iter().cloned())
+                .chain(ensemble.time.iter().cloned().zip(lower_series.iter().cloned()).rev())
+                .collect();
+
+            chart.draw_series(std::iter::once(Polygon::new(band, color.mix(0.15).filled())))?;
+        }
+
+        // ensemble mean
+        let mean_style = color.mix(0.6).stroke_width(1);
+        chart.draw_series(LineSeries::new(
+            ensemble.time.iter().cloned().zip(statistics.mean.iter().cloned()),
+            mean_style
+        ))?
+        .label(format!("{label}, SSA mean"))
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], mean_style));
+
+        // deterministic solution
+        let ode_style = color.stroke_width(2);
+        chart.draw_series(LineSeries::new(
+            ode.iter().map(|(x, pops)| {
+                let quantity = pops.iter()
+                    .find(|(mol, _)| mol == molecule).unwrap().1;
+
+                (*x, quantity)
+            }),
+            ode_style
+        ))?
+        .label(format!("{label}, ODE"))
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], ode_style));
+    }
+
+    // draw legend
+    chart.configure_series_labels()
+        .background_style(WHITE)
+        .draw()?;
+
+    Ok(())
+}
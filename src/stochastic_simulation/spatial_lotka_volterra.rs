@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use crate::chemical_reactions::prelude::*;
+use crate::cellular_automata::prelude::*;
+use crate::stochastic_simulation::prelude::*;
+
+// Lotka-Volterra predator-prey model on a lattice of voxels: the preys fill the
+// lattice up to the carrying capacity of each voxel, and the predators released in
+// the centre invade it as a travelling wave, followed by the regrowth of the preys.
+pub struct SpatialLotkaVolterra;
+
+impl SpatialLotkaVolterra {
+    fn reactions(prey_birth_rate: f32, prey_crowding: f32, predator_death_rate: f32, hunter_meetings: f32) -> Vec<Reaction> {
+        let (prey, predator) = LotkaVolterra::species();
+
+        vec![
+            Reaction::new(prey_birth_rate, [(1, &prey)], [(2, &prey)]),
+            Reaction::new(prey_crowding, [(2, &prey)], [(1, &prey)]),
+            Reaction::new(predator_death_rate, [(1, &predator)], []),
+            Reaction::new(hunter_meetings, [(1, &prey), (1, &predator)], [(2, &predator)]),
+        ]
+    }
+
+    // the initial populations (preys, predators) are the ones of each voxel, the predators
+    // start in the central ones; the rates are (prey birth, prey crowding, predator death, hunting)
+    pub fn make_ssa(size: u32, (initial_preys, initial_predators): (u32, u32), rates: (f32, f32, f32, f32), diffusion_rates: (f32, f32), sample_interval: f32, simulation_seed: u64) -> SpatialSimulation {
+        let (prey, predator) = LotkaVolterra::species();
+        let reactions = Self::reactions(rates.0, rates.1, rates.2, rates.3);
+
+        let centre = (size / 2).saturating_sub(1)..=(size / 2);
+        let initial_state = Lattice::from_fn(size, size, |x, y| {
+            let predators = if centre.contains(&x) && centre.contains(&y) { initial_predators } else { 0 };
+            Ok(vec![(prey.clone(), initial_preys), (predator.clone(), predators)].into_iter().collect())
+        }).unwrap();
+
+        let diffusion_rates: HashMap<Molecule, f32> = vec![
+            (prey, diffusion_rates.0),
+            (predator, diffusion_rates.1),
+        ].into_iter().collect();
+
+        SpatialSimulation::new::<VonNeumannNeighborhood, ClosedBoundary>(reactions, diffusion_rates, initial_state, sample_interval, simulation_seed)
+    }
+}
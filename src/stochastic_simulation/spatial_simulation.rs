@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use rand::{Rng, SeedableRng};
+use crate::chemical_reactions::prelude::*;
+use crate::cellular_automata::prelude::*;
+use crate::stochastic_simulation::indexed_network::*;
+use crate::stochastic_simulation::next_reaction_method::IndexedPriorityQueue;
+
+// Amounts of the species in a voxel, in the order of `SpatialSimulation::get_species`.
+pub type Voxel = Vec<u32>;
+
+// Walls around a lattice with a fixed boundary: the molecules cannot leave the lattice.
+pub struct Wall;
+
+impl ToCell<Voxel> for Wall {
+    fn to_cell() -> Voxel {
+        vec![]
+    }
+}
+
+pub type ClosedBoundary = FixedBoundary<Voxel, Wall>;
+
+// Next Subvolume Method (Elf-Ehrenberg): the lattice is split in voxels, each one a
+// well-mixed volume running the whole reaction network, and each molecule jumps to
+// each neighbouring voxel at the diffusion rate of its species.
+//
+// Every voxel keeps the putative time of its next event (a reaction or a jump) in an
+// indexed priority queue: the first voxel of the queue fires, then only its own time
+// and the one of the voxel receiving the molecule are updated, as in the NRM.
+//
+// The lattice is sampled every `sample_interval` units of time.
+#[derive(Clone)]
+pub struct SpatialSimulation {
+    network: IndexedNetwork,
+    diffusion_rates: Vec<f32>,
+    neighbors: Vec<Vec<usize>>,
+    size: (u32, u32),
+    sample_interval: f32,
+    rng: rand::rngs::SmallRng,
+
+    voxels: Vec<Voxel>,
+    propensities: Vec<f32>,
+    queue: IndexedPriorityQueue,
+    samples: u32,
+    fix_point: bool,
+}
+
+impl SpatialSimulation {
+    pub fn new<N: Neighborhood, B: LatticeBoundary>(reactions: Vec<Reaction>, diffusion_rates: HashMap<Molecule, f32>, initial_state: Lattice<HashMap<Molecule, u32>>, sample_interval: f32, seed: u64) -> Self {
+        let all_species: HashMap<Molecule, u32> = initial_state.cells.iter()
+            .flat_map(|voxel| voxel.keys())
+            .chain(diffusion_rates.keys())
+            .map(|molecule| (molecule.clone(), 0))
+            .collect();
+        let network = IndexedNetwork::new(&reactions, &all_species);
+
+        let diffusion_rates = network.species.iter()
+            .map(|molecule| diffusion_rates.get(molecule).cloned().unwrap_or(0f32))
+            .collect();
+
+        let size = initial_state.size();
+        let neighbors = (0..size.1)
+            .flat_map(|y| (0..size.0).map(move |x| (x, y)))
+            .map(|(x, y)| initial_state.neighbors::<N, B>(x, y).into_iter()
+                .map(|(x, y)| (x + y * size.0) as usize)
+                .collect())
+            .collect();
+
+        let voxels: Vec<Voxel> = initial_state.cells.iter()
+            .map(|voxel| network.species.iter().map(|molecule| voxel.get(molecule).cloned().unwrap_or(0)).collect())
+            .collect();
+
+        let num_voxels = voxels.len();
+        let mut simulation = Self {
+            network,
+            diffusion_rates,
+            neighbors,
+            size,
+            sample_interval,
+            rng: rand::rngs::SmallRng::seed_from_u64(seed),
+            voxels,
+            propensities: vec![0f32; num_voxels],
+            queue: IndexedPriorityQueue::new(num_voxels),
+            samples: 0,
+            fix_point: false,
+        };
+
+        for voxel in 0..num_voxels {
+            let propensity = simulation.get_voxel_propensity(voxel);
+            let firing_time = simulation.sample_firing_time(0f32, propensity);
+
+            simulation.propensities[voxel] = propensity;
+            simulation.queue.update(voxel, firing_time);
+        }
+
+        simulation
+    }
+
+    pub fn get_species(&self) -> &[Molecule] {
+        &self.network.species
+    }
+
+    pub fn get_species_id(&self, molecule: &Molecule) -> Option<usize> {
+        self.network.species.iter().position(|species| species == molecule)
+    }
+
+    fn get_voxel_propensity(&self, voxel: usize) -> f32 {
+        let reactions: f32 = self.network.reactions.iter()
+            .map(|reaction| reaction.get_propensity(&self.voxels[voxel]))
+            .sum();
+
+        reactions + self.get_diffusion_propensities(voxel).sum::<f32>()
+    }
+
+    fn get_diffusion_propensities(&self, voxel: usize) -> impl Iterator<Item = f32> + '_ {
+        let neighbors = self.neighbors[voxel].len() as f32;
+        self.diffusion_rates.iter().zip(self.voxels[voxel].iter())
+            .map(move |(diffusion_rate, amount)| diffusion_rate * (*amount as f32) * neighbors)
+    }
+
+    fn sample_firing_time(&mut self, time: f32, propensity: f32) -> f32 {
+        if propensity > 0f32 {
+            let distribution = rand_distr::Exp::new(propensity).unwrap();
+            time + self.rng.sample(distribution)
+        } else {
+            f32::INFINITY
+        }
+    }
+
+    // fires the next event of the voxel, returning the voxel a molecule jumped to
+    fn fire(&mut self, voxel: usize) -> Option<usize> {
+        // the reactions of the network, then the jumps of each species
+        let events: Vec<f32> = self.network.reactions.iter()
+            .map(|reaction| reaction.get_propensity(&self.voxels[voxel]))
+            .chain(self.get_diffusion_propensities(voxel))
+            .collect();
+
+        let mut threshold = self.rng.gen::<f32>() * events.iter().sum::<f32>();
+
+        // the last possible event absorbs the rounding errors of the sums
+        let last_event = events.iter().rposition(|propensity| *propensity > 0f32)?;
+        let event = events.iter()
+            .position(|propensity| {
+                threshold -= propensity;
+                threshold < 0f32 && *propensity > 0f32
+            })
+            .unwrap_or(last_event);
+
+        let num_reactions = self.network.reactions.len();
+        if event < num_reactions {
+            self.network.reactions[event].apply(&mut self.voxels[voxel]);
+            None
+        } else {
+            let species = event - num_reactions;
+            let neighbors = &self.neighbors[voxel];
+            let target = neighbors[self.rng.gen_range(0..neighbors.len())];
+
+            self.voxels[voxel][species] -= 1;
+            self.voxels[target][species] += 1;
+            Some(target)
+        }
+    }
+
+    fn lattice(&self) -> Lattice<Voxel> {
+        Lattice { cells: self.voxels.clone(), size: self.size }
+    }
+}
+
+impl Iterator for SpatialSimulation {
+    type Item = (f32, Lattice<Voxel>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.fix_point {
+            return None;
+        }
+
+        let sample_time = self.samples as f32 * self.sample_interval;
+        while let Some((voxel, time)) = self.queue.peek().filter(|(_, time)| *time <= sample_time) {
+            let target = self.fire(voxel);
+
+            let propensity = self.get_voxel_propensity(voxel);
+            let firing_time = self.sample_firing_time(time, propensity);
+            self.propensities[voxel] = propensity;
+            self.queue.update(voxel, firing_time);
+
+            if let Some(target) = target.filter(|target| *target != voxel) {
+                let old_propensity = self.propensities[target];
+                let new_propensity = self.get_voxel_propensity(target);
+
+                let firing_time =
+                    if old_propensity > 0f32 && new_propensity > 0f32 {
+                        // rescale the remaining waiting time instead of drawing a new one
+                        time + (old_propensity / new_propensity) * (self.queue.key(target) - time)
+                    } else {
+                        self.sample_firing_time(time, new_propensity)
+                    };
+
+                self.propensities[target] = new_propensity;
+                self.queue.update(target, firing_time);
+            }
+        }
+
+        // no event can happen anymore
+        if self.queue.peek().is_none_or(|(_, time)| time.is_infinite()) {
+            self.fix_point = true;
+        }
+
+        self.samples += 1;
+        Some((sample_time, self.lattice()))
+    }
+}
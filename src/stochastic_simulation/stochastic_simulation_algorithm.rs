@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet};
 use rand::{Rng, SeedableRng};
 use crate::chemical_reactions::prelude::*;
+use crate::stochastic_simulation::prelude::*;
 
 #[derive(Clone)]
 pub struct StochasticSimulation {
@@ -32,6 +33,25 @@ impl StochasticSimulation {
             fix_point: false,
         }
     }
+
+    pub fn with_method(self, method: SSAMethod) -> SSAEngine {
+        match method {
+            SSAMethod::Direct => SSAEngine::Direct(self),
+            SSAMethod::NextReaction =>
+                SSAEngine::NextReaction(NextReactionSimulation::from_parts(self.reactions, self.initial_state, self.rng)),
+            SSAMethod::TauLeaping =>
+                SSAEngine::TauLeaping(TauLeapingSimulation::from_parts(self.reactions, self.initial_state, self.rng)),
+        }
+    }
+
+    // the direct method with delayed reactions, `delays[i]` is the delay of the i-th reaction
+    pub fn with_delays(self, delays: Vec<Option<ReactionDelay>>) -> SSAEngine {
+        SSAEngine::Delayed(DelayedSimulation::from_parts(self.reactions, delays, self.initial_state, self.rng))
+    }
+
+    pub fn to_langevin(self, time_step: f32) -> LangevinSimulation {
+        LangevinSimulation::from_parts(self.reactions, self.initial_state, time_step, self.rng)
+    }
 }
 
 impl Iterator for StochasticSimulation {
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use rand::{Rng, SeedableRng};
+use crate::chemical_reactions::prelude::*;
+use crate::stochastic_simulation::indexed_network::*;
+
+// Explicit tau-leaping with the step size selection of Cao, Gillespie and Petzold (2006).
+// Reactions that can fire fewer than CRITICAL_FIRINGS more times are critical and fire at
+// most once per leap; when the leap would be shorter than a few exact steps the simulator
+// falls back to EXACT_STEPS steps of the direct method.
+#[derive(Clone)]
+pub struct TauLeapingSimulation {
+    network: IndexedNetwork,
+    highest_orders: Vec<Vec<(u32, u32)>>,
+    epsilon: f64,
+    rng: rand::rngs::SmallRng,
+
+    state: Option<(f64, Vec<u32>)>,
+    exact_steps: u32,
+    fix_point: bool,
+}
+
+impl TauLeapingSimulation {
+    const CRITICAL_FIRINGS: i64 = 10;
+    const EXACT_STEPS: u32 = 100;
+    const EXACT_STEP_THRESHOLD: f64 = 10f64;
+    const DEFAULT_EPSILON: f64 = 0.03;
+
+    pub fn new(reactions: Vec<Reaction>, initial_state: HashMap<Molecule, u32>, seed: u64) -> Self {
+        Self::from_parts(reactions, initial_state, rand::rngs::SmallRng::seed_from_u64(seed))
+    }
+
+    pub(crate) fn from_parts(reactions: Vec<Reaction>, initial_state: HashMap<Molecule, u32>, rng: rand::rngs::SmallRng) -> Self {
+        let network = IndexedNetwork::new(&reactions, &initial_state);
+
+        // for each species, the (reaction order, species coefficient) pairs of the reactions depending on it
+        let mut highest_orders = vec![Vec::new(); network.species.len()];
+        for reaction in network.reactions.iter() {
+            if !reaction.kinetic_law.is_mass_action() {
+                // saturating laws grow at most linearly in each species
+                for molecule in reaction.get_dependencies() {
+                    highest_orders[molecule].push((1, 1));
+                }
+                continue;
+            }
+
+            let order = reaction.reactants.iter().map(|(_, coefficient)| coefficient).sum();
+            for (molecule, coefficient) in reaction.reactants.iter() {
+                highest_orders[*molecule].push((order, *coefficient));
+            }
+        }
+
+        Self {
+            network,
+            highest_orders,
+            epsilon: Self::DEFAULT_EPSILON,
+            rng,
+            state: None,
+            exact_steps: 0,
+            fix_point: false,
+        }
+    }
+
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    // g_i in Cao et al., bounds the relative change of the propensities depending on species i
+    fn highest_order_factor(&self, molecule: usize, quantity: f64) -> f64 {
+        let x = quantity.max(3f64);
+
+        self.highest_orders[molecule].iter()
+            .map(|(order, coefficient)| match (order, coefficient) {
+                (2, 2) => 2f64 + 1f64 / (x - 1f64),
+                (3, 2) => 1.5f64 * (2f64 + 1f64 / (x - 1f64)),
+                (3, 3) => 3f64 + 1f64 / (x - 1f64) + 2f64 / (x - 2f64),
+                (order, _) => *order as f64,
+            })
+            .fold(1f64, f64::max)
+    }
+
+    fn is_critical(reaction: &IndexedReaction, state: &[u32]) -> bool {
+        reaction.changes.iter()
+            .filter(|(_, change)| *change < 0)
+            .map(|(molecule, change)| state[*molecule] as i64 / -change)
+            .min()
+            .is_some_and(|firings| firings < Self::CRITICAL_FIRINGS)
+    }
+
+    fn noncritical_tau(&self, state: &[u32], propensities: &[f64], critical: &[bool]) -> f64 {
+        let mut means = vec![0f64; state.len()];
+        let mut variances = vec![0f64; state.len()];
+
+        for ((reaction, propensity), critical) in self.network.reactions.iter().zip(propensities).zip(critical) {
+            if *critical {
+                continue;
+            }
+
+            for (molecule, change) in reaction.changes.iter() {
+                means[*molecule] += *change as f64 * propensity;
+                variances[*molecule] += (change * change) as f64 * propensity;
+            }
+        }
+
+        (0..state.len())
+            .filter(|molecule| !self.highest_orders[*molecule].is_empty())
+            .map(|molecule| {
+                let quantity = state[molecule] as f64;
+                let bound = f64::max(self.epsilon * quantity / self.highest_order_factor(molecule, quantity), 1f64);
+
+                f64::min(bound / means[molecule].abs(), bound * bound / variances[molecule])
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    fn choose_reaction(&mut self, propensities: &[f64], total: f64, filter: impl Fn(usize) -> bool) -> usize {
+        let distibution = rand::distributions::Uniform::new(0f64, total);
+        let chosen_reaction_value = self.rng.sample(distibution);
+
+        let mut partial_sum = 0f64;
+        let mut chosen_reaction = 0;
+        for (reaction, propensity) in propensities.iter().enumerate().filter(|(reaction, _)| filter(*reaction)) {
+            partial_sum += propensity;
+            chosen_reaction = reaction;
+            if partial_sum > chosen_reaction_value {
+                break;
+            }
+        }
+
+        chosen_reaction
+    }
+
+    fn exact_step(&mut self, time: f64, mut state: Vec<u32>, propensities: &[f64], total: f64) -> (f64, Vec<u32>) {
+        let distribution = rand_distr::Exp::new(total).unwrap();
+        let delta_time = self.rng.sample(distribution);
+
+        let chosen_reaction = self.choose_reaction(propensities, total, |reaction| propensities[reaction] > 0f64);
+        self.network.reactions[chosen_reaction].apply(&mut state);
+
+        (time + delta_time, state)
+    }
+
+    fn leap(&mut self, time: f64, state: Vec<u32>) -> Option<(f64, Vec<u32>)> {
+        let propensities: Vec<f64> = self.network.get_propensities(&state)
+            .into_iter().map(|propensity| propensity as f64).collect();
+        let total: f64 = propensities.iter().sum();
+
+        if total <= 0f64 {
+            return None;
+        }
+
+        if self.exact_steps > 0 {
+            self.exact_steps -= 1;
+            return Some(self.exact_step(time, state, &propensities, total));
+        }
+
+        let critical: Vec<bool> = self.network.reactions.iter().zip(propensities.iter())
+            .map(|(reaction, propensity)| *propensity > 0f64 && Self::is_critical(reaction, &state))
+            .collect();
+
+        let mut noncritical_tau = self.noncritical_tau(&state, &propensities, &critical);
+        if noncritical_tau < Self::EXACT_STEP_THRESHOLD / total {
+            self.exact_steps = Self::EXACT_STEPS - 1;
+            return Some(self.exact_step(time, state, &propensities, total));
+        }
+
+        let critical_total: f64 = propensities.iter().zip(critical.iter())
+            .filter(|(_, critical)| **critical)
+            .map(|(propensity, _)| propensity)
+            .sum();
+
+        loop {
+            let critical_tau =
+                if critical_total > 0f64 {
+                    self.rng.sample(rand_distr::Exp::new(critical_total).unwrap())
+                } else {
+                    f64::INFINITY
+                };
+
+            let tau = f64::min(noncritical_tau, critical_tau);
+            if tau == f64::INFINITY {
+                // no reaction changes the state, only exact steps make progress
+                return Some(self.exact_step(time, state, &propensities, total));
+            }
+
+            let mut new_state: Vec<i64> = state.iter().map(|quantity| *quantity as i64).collect();
+
+            for (reaction, propensity) in propensities.iter().enumerate() {
+                if critical[reaction] || *propensity <= 0f64 {
+                    continue;
+                }
+
+                let distribution = rand_distr::Poisson::new(propensity * tau).unwrap();
+                let firings = self.rng.sample(distribution) as i64;
+                for (molecule, change) in self.network.reactions[reaction].changes.iter() {
+                    new_state[*molecule] += change * firings;
+                }
+            }
+
+            if critical_tau <= noncritical_tau {
+                let chosen_reaction = self.choose_reaction(&propensities, critical_total, |reaction| critical[reaction]);
+                for (molecule, change) in self.network.reactions[chosen_reaction].changes.iter() {
+                    new_state[*molecule] += change;
+                }
+            }
+
+            if new_state.iter().all(|quantity| *quantity >= 0) {
+                let new_state = new_state.into_iter().map(|quantity| quantity as u32).collect();
+                return Some((time + tau, new_state));
+            }
+
+            // some population went negative, retry with a smaller leap
+            noncritical_tau /= 2f64;
+        }
+    }
+}
+
+impl Iterator for TauLeapingSimulation {
+    type Item = (f32, Vec<(Molecule, u32)>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.fix_point {
+            return None;
+        }
+
+        let Some((time, state)) = self.state.take() else {
+            let state = self.network.initial_state.clone();
+            let output = self.network.output(0f32, &state);
+            self.state = Some((0f64, state));
+            return Some(output);
+        };
+
+        let Some((time, state)) = self.leap(time, state) else {
+            // no reaction can be applied
+            self.fix_point = true;
+            return None;
+        };
+
+        let output = self.network.output(time as f32, &state);
+        self.state = Some((time, state));
+        Some(output)
+    }
+}
@@ -1,22 +1,40 @@
 use wasm_bindgen::prelude::*;
 
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
 pub mod prelude {
     pub use super::{
         GraphType,
         MyDrawResult,
         StringError,
         MyDrawingArea,
+        MyDrawingBackend,
+        Chart,
         draw_prelude,
         draw_generic,
-        Simulation
+        draw_canvas,
+        draw_image,
+        render_svg,
+        Simulation,
+        Stream,
+        Columns
     };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub use super::render_png;
 }
 
 pub mod phase_graph;
+pub mod parameter_sweep;
+pub mod export;
 pub mod simulation_limits;
+pub mod stream;
 
 use simulation_limits::*;
 use phase_graph::*;
+pub use export::Columns;
+pub use stream::Stream;
 
 pub enum GraphType {
     Function,
@@ -57,8 +75,19 @@ impl std::fmt::Display for StringError {
 impl std::error::Error for StringError { }
 
 
-// helper functions for canvas drawing
-pub type MyDrawingArea = plotters::drawing::DrawingArea<plotters_canvas::CanvasBackend, plotters::coord::Shift>;
+// helper functions for drawing, on the canvas of the pages or natively on bitmaps and svgs
+pub type MyDrawingArea<DB = plotters_canvas::CanvasBackend> = plotters::drawing::DrawingArea<DB, plotters::coord::Shift>;
+
+// Plotters backend the charts can be drawn on, i.e. any whose errors can be boxed.
+pub trait MyDrawingBackend: plotters::prelude::DrawingBackend<ErrorType: 'static> { }
+
+impl<DB> MyDrawingBackend for DB
+    where DB: plotters::prelude::DrawingBackend<ErrorType: 'static> { }
+
+// A page chart with its parameters, drawable on any backend.
+pub trait Chart {
+    fn draw<DB: MyDrawingBackend>(self, area: &MyDrawingArea<DB>) -> MyDrawResult<()>;
+}
 
 pub fn draw_prelude(canvas: web_sys::HtmlCanvasElement) -> MyDrawResult<MyDrawingArea> {
     let Some(area) = plotters_canvas::CanvasBackend::with_canvas_object(canvas) else {
@@ -69,13 +98,60 @@ pub fn draw_prelude(canvas: web_sys::HtmlCanvasElement) -> MyDrawResult<MyDrawin
 }
 
 pub fn draw_generic<F, T, Params>(fun: F) -> impl Fn(web_sys::HtmlCanvasElement, Params) -> Result<T, JsValue>
-    where F: Fn(web_sys::HtmlCanvasElement, Params) -> MyDrawResult<T>
+    where F: Fn(&MyDrawingArea, Params) -> MyDrawResult<T>
 {
     move |canvas, params| {
-        fun(canvas, params).map_err(|err| err.to_string().into())
+        draw_prelude(canvas)
+            .and_then(|area| fun(&area, params))
+            .map_err(|err| err.to_string().into())
     }
 }
 
+// Draws a chart kept by the page between calls, e.g. a stream drawn frame by frame.
+pub fn draw_canvas<C: Chart>(canvas: web_sys::HtmlCanvasElement, chart: C) -> Result<(), JsValue> {
+    draw_prelude(canvas)
+        .and_then(|area| chart.draw(&area))
+        .map_err(|err| err.to_string().into())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn render_png<C: Chart, P: AsRef<Path>>(chart: C, path: P, size: (u32, u32)) -> MyDrawResult<()> {
+    use plotters::prelude::*;
+
+    let area = BitMapBackend::new(path.as_ref(), size).into_drawing_area();
+    chart.draw(&area)?;
+    area.present()?;
+
+    Ok(())
+}
+
+pub fn render_svg<C: Chart>(chart: C, size: (u32, u32)) -> MyDrawResult<String> {
+    use plotters::prelude::*;
+
+    let mut svg = String::new();
+    {
+        let area = SVGBackend::with_string(&mut svg, size).into_drawing_area();
+        chart.draw(&area)?;
+        area.present()?;
+    }
+
+    Ok(svg)
+}
+
+// Draws a lattice image one pixel per cell, as sized on the pages, scaled up
+// without smoothing when the area is larger (e.g. a PNG of a report).
+pub fn draw_image<DB: MyDrawingBackend>(area: &MyDrawingArea<DB>, image: &image::RgbImage) -> MyDrawResult<()> {
+    let (width, height) = area.dim_in_pixel();
+    let scale = u32::max(1, u32::min(width / image.width().max(1), height / image.height().max(1)));
+    let image = image::imageops::resize(image, image.width() * scale, image.height() * scale, image::imageops::FilterType::Nearest);
+
+    let element = plotters::element::BitMapElement::with_owned_buffer((0, 0), image.dimensions(), image.into_raw())
+        .ok_or(StringError::new("image buffer does not match its size".to_owned()))?;
+    area.draw(&element)?;
+
+    Ok(())
+}
+
 // simulation builder
 
 #[derive(Clone)]
@@ -126,6 +202,12 @@ impl<T, X, Y> Simulation<T, X, Y>
         Simulation { simulation: self.simulation.map(fun) }
     }
 
+    pub fn stream(self) -> Stream<X, Y>
+        where T: 'static, X: PartialOrd
+    {
+        Stream::new(self.simulation)
+    }
+
     pub fn cache(self) -> Simulation<impl Iterator<Item = (X, Y)> + Clone, X, Y>
     {
         let data: Vec<_> = self.collect();
@@ -0,0 +1,198 @@
+use std::io::Write;
+use super::Simulation;
+
+// Values of a simulation step as columns, so that trajectories can be exported. Each
+// value carries its own name (e.g. the molecule of a state) or none, and anonymous
+// values are named by the caller of the exporter.
+pub trait Columns {
+    fn columns(&self) -> Vec<(Option<String>, f64)>;
+}
+
+macro_rules! scalar_columns {
+    ($($type:ty),*) => {
+        $(
+            impl Columns for $type {
+                fn columns(&self) -> Vec<(Option<String>, f64)> {
+                    vec![(None, *self as f64)]
+                }
+            }
+        )*
+    };
+}
+
+scalar_columns!(f64, u32, u64, usize, i32, i64);
+
+impl Columns for f32 {
+    // through the shortest decimal representation, so that 0.1f32 is exported as 0.1
+    fn columns(&self) -> Vec<(Option<String>, f64)> {
+        vec![(None, self.to_string().parse().unwrap_or(f64::NAN))]
+    }
+}
+
+macro_rules! tuple_columns {
+    ($($name:ident),*) => {
+        impl<$($name: Columns),*> Columns for ($($name,)*) {
+            #[allow(non_snake_case)]
+            fn columns(&self) -> Vec<(Option<String>, f64)> {
+                let ($($name,)*) = self;
+                let mut columns = Vec::new();
+                $(columns.extend($name.columns());)*
+                columns
+            }
+        }
+    };
+}
+
+tuple_columns!(A, B);
+tuple_columns!(A, B, C);
+tuple_columns!(A, B, C, D);
+tuple_columns!(A, B, C, D, E);
+
+impl<T: Columns> Columns for Vec<T> {
+    fn columns(&self) -> Vec<(Option<String>, f64)> {
+        self.iter().flat_map(|value| value.columns()).collect()
+    }
+}
+
+// value with its own name, e.g. a statistic of a species
+impl<T: Columns> Columns for (String, T) {
+    fn columns(&self) -> Vec<(Option<String>, f64)> {
+        self.1.columns().into_iter()
+            .map(|(_, value)| (Some(self.0.clone()), value))
+            .collect()
+    }
+}
+
+// table with a column for each name found in the trajectory, missing values are NaN
+struct Table {
+    names: Vec<String>,
+    rows: Vec<Vec<f64>>,
+}
+
+impl Table {
+    // The anonymous values of each step get the given names in order, the time first;
+    // the ones without a given name are called x (the time) and y0, y1, ...
+    fn new<I, X, Y>(simulation: I, names: &[&str]) -> Self
+        where I: Iterator<Item = (X, Y)>, X: Columns, Y: Columns
+    {
+        let mut column_names: Vec<String> = Vec::new();
+        let named_rows: Vec<Vec<(usize, f64)>> = simulation
+            .map(|(x, y)| {
+                x.columns().into_iter().chain(y.columns())
+                    .scan(0, |anonymous, (name, value)| {
+                        let name = name.unwrap_or_else(|| {
+                            let index = *anonymous;
+                            *anonymous += 1;
+                            match names.get(index) {
+                                Some(name) => name.to_string(),
+                                None if index == 0 => "x".to_owned(),
+                                None => format!("y{}", index - 1),
+                            }
+                        });
+
+                        let column = column_names.iter().position(|other| *other == name)
+                            .unwrap_or_else(|| {
+                                column_names.push(name);
+                                column_names.len() - 1
+                            });
+
+                        Some((column, value))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let rows = named_rows.into_iter()
+            .map(|named_row| {
+                let mut row = vec![f64::NAN; column_names.len()];
+                for (column, value) in named_row {
+                    row[column] = value;
+                }
+                row
+            })
+            .collect();
+
+        Self { names: column_names, rows }
+    }
+
+    fn write_csv<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let header: Vec<String> = self.names.iter().map(|name| csv_field(name)).collect();
+        writeln!(writer, "{}", header.join(","))?;
+
+        for row in self.rows.iter() {
+            let fields: Vec<String> = row.iter()
+                .map(|value| if value.is_nan() { String::new() } else { value.to_string() })
+                .collect();
+            writeln!(writer, "{}", fields.join(","))?;
+        }
+
+        Ok(())
+    }
+
+    // one array of values per column: {"t": [...], "S": [...], ...}
+    fn write_json<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        write!(writer, "{{")?;
+
+        for (column, name) in self.names.iter().enumerate() {
+            let values: Vec<String> = self.rows.iter()
+                .map(|row| if row[column].is_finite() { row[column].to_string() } else { "null".to_owned() })
+                .collect();
+
+            let separator = if column == 0 { "" } else { "," };
+            write!(writer, "{separator}{}:[{}]", json_string(name), values.join(","))?;
+        }
+
+        writeln!(writer, "}}")
+    }
+}
+
+// Export of finite trajectories, limit infinite ones first (e.g. with `time_limit`).
+impl<T, X, Y> Simulation<T, X, Y>
+    where T: Iterator<Item = (X, Y)> + Clone, X: Clone + Columns, Y: Clone + Columns
+{
+    pub fn write_csv<W: Write>(self, writer: &mut W, names: &[&str]) -> std::io::Result<()> {
+        Table::new(self, names).write_csv(writer)
+    }
+
+    pub fn write_json<W: Write>(self, writer: &mut W, names: &[&str]) -> std::io::Result<()> {
+        Table::new(self, names).write_json(writer)
+    }
+
+    pub fn to_csv(self, names: &[&str]) -> String {
+        let mut buffer = Vec::new();
+        self.write_csv(&mut buffer, names).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    pub fn to_json(self, names: &[&str]) -> String {
+        let mut buffer = Vec::new();
+        self.write_json(&mut buffer, names).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+fn json_string(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len() + 2);
+    escaped.push('"');
+    for character in string.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            character if (character as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", character as u32)),
+            character => escaped.push(character),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
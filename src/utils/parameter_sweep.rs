@@ -0,0 +1,269 @@
+use rand::{Rng, SeedableRng};
+use rand::seq::SliceRandom;
+
+// Evaluates a model over samples of its parameters, reducing every run to a few
+// summary outputs. Models are closures from the parameter values (in the order they
+// were declared) to a trajectory of (time, observed value) pairs, so any constructor
+// can be swept, e.g. the infected of the SIR model:
+//
+//   let space = ParameterSpace::new()
+//       .parameter("infection_coefficient", (1f32, 6f32))
+//       .parameter("recovery_coefficient", (0.5f32, 2f32));
+//   let sweep = space.sweep(space.latin_hypercube(100, seed), |p| {
+//       let model = SIRModel::new((0.99, 0.01, 0f32), p[0], p[1], 0f32, 0f32, 10f32);
+//       let mut stepper = Rk4::new(model.ode, 0f32, model.initial_state, model.max_time, 0.01);
+//       stepper.integrate().unwrap();
+//       let (time, states) = stepper.results().get();
+//       time.iter().zip(states.iter()).map(|(time, state)| (*time, state[1])).collect::<Vec<_>>()
+//   });
+#[derive(Clone, Default)]
+pub struct ParameterSpace {
+    parameters: Vec<Parameter>,
+}
+
+#[derive(Clone)]
+pub struct Parameter {
+    pub name: String,
+    pub min: f32,
+    pub max: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SummaryOutput {
+    FinalValue,
+    Peak,
+    TimeToPeak,
+}
+
+// Summary outputs of a trajectory. The same layout holds the sensitivities and the
+// Sobol indices of each output.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Summary {
+    pub final_value: f32,
+    pub peak: f32,
+    pub time_to_peak: f32,
+}
+
+#[derive(Clone)]
+pub struct Sweep {
+    pub parameters: Vec<Parameter>,
+    pub samples: Vec<Vec<f32>>,
+    pub summaries: Vec<Summary>,
+}
+
+// derivatives of the summary outputs at a point, also normalized as (p / y) dy/dp
+#[derive(Clone)]
+pub struct Sensitivity {
+    pub parameter: String,
+    pub derivative: Summary,
+    pub normalized: Summary,
+}
+
+#[derive(Clone)]
+pub struct SobolIndices {
+    pub parameter: String,
+    pub first_order: Summary,
+    pub total: Summary,
+}
+
+impl ParameterSpace {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn parameter(mut self, name: &str, (min, max): (f32, f32)) -> Self {
+        self.parameters.push(Parameter { name: name.to_owned(), min, max });
+        self
+    }
+
+    pub fn get_parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    // all the combinations of evenly spaced values of each parameter
+    pub fn grid(&self, points_per_axis: usize) -> Vec<Vec<f32>> {
+        let intervals = usize::max(points_per_axis, 2) - 1;
+
+        self.parameters.iter().fold(vec![Vec::new()], |samples, parameter| {
+            samples.into_iter()
+                .flat_map(|sample| (0..=intervals).map(move |i| {
+                    let mut sample = sample.clone();
+                    sample.push(parameter.min + (parameter.max - parameter.min) * i as f32 / intervals as f32);
+                    sample
+                }))
+                .collect()
+        })
+    }
+
+    // every parameter range is split in `samples` strata, each of them sampled exactly once
+    pub fn latin_hypercube(&self, samples: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(seed);
+
+        let columns: Vec<Vec<f32>> = self.parameters.iter()
+            .map(|parameter| {
+                let mut strata: Vec<usize> = (0..samples).collect();
+                strata.shuffle(&mut rng);
+
+                strata.into_iter()
+                    .map(|stratum| {
+                        let position = (stratum as f32 + rng.gen::<f32>()) / samples as f32;
+                        parameter.min + (parameter.max - parameter.min) * position
+                    })
+                    .collect()
+            })
+            .collect();
+
+        (0..samples)
+            .map(|sample| columns.iter().map(|column| column[sample]).collect())
+            .collect()
+    }
+
+    fn uniform(&self, samples: usize, rng: &mut rand::rngs::SmallRng) -> Vec<Vec<f32>> {
+        (0..samples)
+            .map(|_| self.parameters.iter()
+                .map(|parameter| rng.gen_range(parameter.min..=parameter.max))
+                .collect())
+            .collect()
+    }
+
+    pub fn sweep<F, I>(&self, samples: Vec<Vec<f32>>, model: F) -> Sweep
+        where F: Fn(&[f32]) -> I, I: IntoIterator<Item = (f32, f32)>
+    {
+        let summaries = samples.iter()
+            .map(|sample| Summary::from_trajectory(model(sample)))
+            .collect();
+
+        Sweep { parameters: self.parameters.clone(), samples, summaries }
+    }
+
+    // central differences with a step relative to the size of each parameter range
+    pub fn local_sensitivities<F, I>(&self, point: &[f32], relative_step: f32, model: F) -> Vec<Sensitivity>
+        where F: Fn(&[f32]) -> I, I: IntoIterator<Item = (f32, f32)>
+    {
+        let at_point = Summary::from_trajectory(model(point));
+
+        self.parameters.iter().enumerate()
+            .map(|(i, parameter)| {
+                let step = relative_step * (parameter.max - parameter.min).abs().max(f32::EPSILON);
+                let evaluate = |value: f32| {
+                    let mut point = point.to_vec();
+                    point[i] = value;
+                    Summary::from_trajectory(model(&point))
+                };
+
+                let (forward, backward) = (evaluate(point[i] + step), evaluate(point[i] - step));
+                let derivative = forward.zip(&backward, |forward, backward| (forward - backward) / (2f32 * step));
+                let normalized = derivative.zip(&at_point, |derivative, value| derivative * point[i] / value);
+
+                Sensitivity { parameter: parameter.name.clone(), derivative, normalized }
+            })
+            .collect()
+    }
+
+    // Saltelli sampling, with the first order estimator of Saltelli et al. (2010) and
+    // the total effect estimator of Jansen (1999): `samples * (parameters + 2)` runs
+    pub fn sobol_indices<F, I>(&self, samples: usize, seed: u64, model: F) -> Vec<SobolIndices>
+        where F: Fn(&[f32]) -> I, I: IntoIterator<Item = (f32, f32)>
+    {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(seed);
+        let a = self.uniform(samples, &mut rng);
+        let b = self.uniform(samples, &mut rng);
+
+        let evaluate = |samples: &[Vec<f32>]| -> Vec<Summary> {
+            samples.iter().map(|sample| Summary::from_trajectory(model(sample))).collect()
+        };
+        let (f_a, f_b) = (evaluate(&a), evaluate(&b));
+
+        let mean = |values: &mut dyn Iterator<Item = Summary>| {
+            let (sum, count) = values.fold((Summary::splat(0f32), 0), |(sum, count), value| (sum.zip(&value, |a, b| a + b), count + 1));
+            sum.map(|sum| sum / count as f32)
+        };
+
+        let average = mean(&mut f_a.iter().chain(f_b.iter()).cloned());
+        let variance = mean(&mut f_a.iter().chain(f_b.iter()).map(|value| value.zip(&average, |value, average| (value - average).powi(2))));
+
+        self.parameters.iter().enumerate()
+            .map(|(i, parameter)| {
+                // A with the i-th column taken from B
+                let a_b: Vec<Vec<f32>> = a.iter().zip(b.iter())
+                    .map(|(a, b)| {
+                        let mut sample = a.clone();
+                        sample[i] = b[i];
+                        sample
+                    })
+                    .collect();
+                let f_a_b = evaluate(&a_b);
+
+                let first_order = mean(&mut f_a.iter().zip(f_b.iter()).zip(f_a_b.iter())
+                    .map(|((f_a, f_b), f_a_b)| f_b.zip(&f_a_b.zip(f_a, |f_a_b, f_a| f_a_b - f_a), |f_b, difference| f_b * difference)));
+                let total = mean(&mut f_a.iter().zip(f_a_b.iter())
+                    .map(|(f_a, f_a_b)| f_a.zip(f_a_b, |f_a, f_a_b| (f_a - f_a_b).powi(2) / 2f32)));
+
+                SobolIndices {
+                    parameter: parameter.name.clone(),
+                    first_order: first_order.zip(&variance, |value, variance| value / variance),
+                    total: total.zip(&variance, |value, variance| value / variance),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Summary {
+    // NaN outputs for empty trajectories
+    pub fn from_trajectory(trajectory: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        trajectory.into_iter()
+            .fold(Self::splat(f32::NAN), |summary, (time, value)| {
+                if summary.peak.is_nan() || value > summary.peak {
+                    Self { final_value: value, peak: value, time_to_peak: time }
+                } else {
+                    Self { final_value: value, ..summary }
+                }
+            })
+    }
+
+    pub fn get(&self, output: SummaryOutput) -> f32 {
+        match output {
+            SummaryOutput::FinalValue => self.final_value,
+            SummaryOutput::Peak => self.peak,
+            SummaryOutput::TimeToPeak => self.time_to_peak,
+        }
+    }
+
+    fn splat(value: f32) -> Self {
+        Self { final_value: value, peak: value, time_to_peak: value }
+    }
+
+    fn map(&self, fun: impl Fn(f32) -> f32) -> Self {
+        Self { final_value: fun(self.final_value), peak: fun(self.peak), time_to_peak: fun(self.time_to_peak) }
+    }
+
+    fn zip(&self, other: &Self, fun: impl Fn(f32, f32) -> f32) -> Self {
+        Self {
+            final_value: fun(self.final_value, other.final_value),
+            peak: fun(self.peak, other.peak),
+            time_to_peak: fun(self.time_to_peak, other.time_to_peak),
+        }
+    }
+}
+
+impl Sweep {
+    pub fn get_outputs(&self, output: SummaryOutput) -> Vec<f32> {
+        self.summaries.iter().map(|summary| summary.get(output)).collect()
+    }
+
+    // samples and outputs as comma separated values, one run per line
+    pub fn to_csv(&self) -> String {
+        let header = self.parameters.iter().map(|parameter| parameter.name.as_str())
+            .chain(["final_value", "peak", "time_to_peak"])
+            .collect::<Vec<_>>().join(",");
+
+        let rows = self.samples.iter().zip(self.summaries.iter())
+            .map(|(sample, summary)| sample.iter()
+                .chain([summary.final_value, summary.peak, summary.time_to_peak].iter())
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>().join(","));
+
+        std::iter::once(header).chain(rows).collect::<Vec<_>>().join("\n")
+    }
+}
@@ -0,0 +1,42 @@
+// Simulation kept alive between the animation frames of a page: it is advanced on
+// demand, by a number of steps or up to a time, instead of being rerun from the start.
+pub struct Stream<X, Y> {
+    simulation: std::iter::Peekable<Box<dyn Iterator<Item = (X, Y)>>>,
+    steps: usize,
+}
+
+impl<X, Y> Stream<X, Y>
+    where X: PartialOrd
+{
+    pub fn new<T: Iterator<Item = (X, Y)> + 'static>(simulation: T) -> Self {
+        let simulation: Box<dyn Iterator<Item = (X, Y)>> = Box::new(simulation);
+        Self { simulation: simulation.peekable(), steps: 0 }
+    }
+
+    // the next states, at most `steps` of them
+    pub fn advance(&mut self, steps: usize) -> Vec<(X, Y)> {
+        let states: Vec<_> = self.simulation.by_ref().take(steps).collect();
+        self.steps += states.len();
+
+        states
+    }
+
+    // the next states up to the given time, included
+    pub fn advance_until(&mut self, time: X) -> Vec<(X, Y)> {
+        let mut states = Vec::new();
+        while let Some(state) = self.simulation.next_if(|(x, _)| *x <= time) {
+            states.push(state);
+        }
+        self.steps += states.len();
+
+        states
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn is_finished(&mut self) -> bool {
+        self.simulation.peek().is_none()
+    }
+}
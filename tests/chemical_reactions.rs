@@ -0,0 +1,218 @@
+use std::collections::{HashMap, HashSet};
+
+use rust_cmcs::chemical_reactions::prelude::*;
+use rust_cmcs::continuous_dynamical_systems::ODESolver;
+use rust_cmcs::stochastic_simulation::prelude::*;
+
+type Side = HashSet<(u32, Molecule)>;
+
+fn sides(reaction: &Reaction) -> (Side, Side) {
+    (reaction.get_reactants().clone(), reaction.get_products().clone())
+}
+
+// `-> B` with rate `k*B` lists B only among the products, the rate law adds it to both sides
+#[test]
+fn sbml_autocatalytic_round_trip() {
+    let sbml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+  <model id="autocatalysis">
+    <listOfCompartments>
+      <compartment id="cell" spatialDimensions="3" size="1" constant="true"/>
+    </listOfCompartments>
+    <listOfSpecies>
+      <species id="B" compartment="cell" initialAmount="10" hasOnlySubstanceUnits="true" boundaryCondition="false" constant="false"/>
+    </listOfSpecies>
+    <listOfReactions>
+      <reaction id="growth" reversible="false">
+        <listOfProducts>
+          <speciesReference species="B" stoichiometry="1" constant="true"/>
+        </listOfProducts>
+        <kineticLaw>
+          <math xmlns="http://www.w3.org/1998/Math/MathML"><apply><times/><ci> k </ci><ci> B </ci></apply></math>
+          <listOfLocalParameters>
+            <localParameter id="k" value="0.5"/>
+          </listOfLocalParameters>
+        </kineticLaw>
+      </reaction>
+    </listOfReactions>
+  </model>
+</sbml>"#;
+
+    let b = Molecule::new("B");
+    let expected = (HashSet::from([(1, b.clone())]), HashSet::from([(2, b.clone())]));
+
+    let network = ReactionNetwork::from_sbml(sbml).unwrap();
+    assert_eq!(network.reactions.len(), 1);
+    assert_eq!(sides(&network.reactions[0]), expected);
+
+    let network = ReactionNetwork::from_sbml(&network.to_sbml("autocatalysis").unwrap()).unwrap();
+    assert_eq!(network.reactions.len(), 1);
+    assert_eq!(sides(&network.reactions[0]), expected);
+    assert_eq!(network.initial_state.get(&b), Some(&10));
+}
+
+// `A -> B` in a compartment of size 2, with the given species
+fn sbml_with_species(species: &str) -> String {
+    format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<sbml xmlns="http://www.sbml.org/sbml/level3/version2/core" level="3" version="2">
+  <model id="conversion">
+    <listOfCompartments>
+      <compartment id="cell" spatialDimensions="3" size="2" constant="true"/>
+    </listOfCompartments>
+    <listOfSpecies>
+      <species id="A" compartment="cell" initialAmount="10" hasOnlySubstanceUnits="true" boundaryCondition="false" constant="false"/>
+      {species}
+    </listOfSpecies>
+    <listOfReactions>
+      <reaction id="conversion" reversible="false">
+        <listOfReactants>
+          <speciesReference species="A" stoichiometry="1" constant="true"/>
+        </listOfReactants>
+        <listOfProducts>
+          <speciesReference species="B" stoichiometry="1" constant="true"/>
+        </listOfProducts>
+        <kineticLaw>
+          <math xmlns="http://www.w3.org/1998/Math/MathML"><apply><times/><cn> 0.5 </cn><ci> A </ci></apply></math>
+        </kineticLaw>
+      </reaction>
+    </listOfReactions>
+  </model>
+</sbml>"#)
+}
+
+#[test]
+fn sbml_amounts_and_unused_species() {
+    let species = |b_concentration: &str, extra: &str| sbml_with_species(&format!(
+        r#"<species id="B" compartment="cell" initialConcentration="{b_concentration}" hasOnlySubstanceUnits="false" boundaryCondition="false" constant="false"/>{extra}"#
+    ));
+
+    let network = ReactionNetwork::from_sbml(&species("1.5", "")).unwrap();
+    assert_eq!(network.initial_state.get(&Molecule::new("B")), Some(&3));
+
+    assert_eq!(
+        ReactionNetwork::from_sbml(&species("1.25", "")).err(),
+        Some(SbmlError::Unsupported(vec![SbmlUnsupported::NonIntegerAmount("B".to_owned())]))
+    );
+
+    let unused = r#"<species id="C" compartment="cell" initialAmount="4" hasOnlySubstanceUnits="true" boundaryCondition="false" constant="false"/>"#;
+    assert_eq!(
+        ReactionNetwork::from_sbml(&species("1.5", unused)).err(),
+        Some(SbmlError::Unsupported(vec![SbmlUnsupported::UnusedSpecies("C".to_owned())]))
+    );
+}
+
+// the networks of the pages written as text
+const ENZYMATIC_ACTIVITY: &str = "
+    e = 100; s = 50
+    e + s <-> es @ 0.01, 0.5
+    es -> e + p @ 0.1";
+
+const LOTKA_VOLTERRA: &str = "
+    v = 100; p = 20
+    v -> 2 v @ 1.5     # preys reproduce
+    p -> @ 0.8         # predators die
+    v + p -> 3p @ 0.02 # a hunt feeds two offsprings";
+
+const NEGATIVE_FEEDBACK_LOOP: &str = "
+    g1 = 1; g2 = 2; g3 = 3
+    g1 -> g1 + p1 @ 5; g2 -> g2 + p2 @ 6; g3 -> g3 + p3 @ 7
+    p1 + g2 <-> p1g2 @ 0.5, 0.1
+    p2 + g3 <-> p2g3 @ 0.6, 0.2
+    p3 + g1 <-> p3g1 @ 0.7, 0.3
+    p1 -> @ 0.4; p2 -> @ 0.5; p3 -> @ 0.6";
+
+fn assert_same_trajectory(parsed: &str, model: ODESimulation) {
+    let network = ReactionNetwork::parse(parsed).unwrap();
+    let parsed = ODESimulation::new(network.reactions, network.initial_state, ODESolver::RK4, 5f32).unwrap();
+
+    let states = |simulation: ODESimulation| simulation.into_iter()
+        .map(|(time, state)| (time, state.into_iter().collect::<HashMap<_, _>>()))
+        .collect::<Vec<_>>();
+    let (parsed, model) = (states(parsed), states(model));
+
+    assert_eq!(parsed.len(), model.len());
+    for ((parsed_time, parsed_state), (model_time, model_state)) in parsed.iter().zip(model.iter()) {
+        assert_eq!(parsed_time, model_time);
+        assert_eq!(parsed_state.len(), model_state.len());
+        for (molecule, amount) in model_state.iter() {
+            let parsed_amount = parsed_state[molecule];
+            assert!((parsed_amount - amount).abs() <= 1e-3 * amount.abs().max(1f32),
+                "{} at time {model_time} is {parsed_amount} in the parsed network and {amount} in the model", molecule.get_name());
+        }
+    }
+}
+
+// the page networks parsed from text are integrated the same as the models building them
+#[test]
+fn parse_page_networks() {
+    assert_same_trajectory(
+        ENZYMATIC_ACTIVITY,
+        EnzymaticActivity::make_ode(100, 50, 0.01, 0.5, 0.1, ODESolver::RK4, 5f32),
+    );
+
+    assert_same_trajectory(
+        LOTKA_VOLTERRA,
+        LotkaVolterra::make_ode(100, 20, 1.5, 0.8, 0.02, 2, ODESolver::RK4, 5f32),
+    );
+
+    assert_same_trajectory(
+        NEGATIVE_FEEDBACK_LOOP,
+        NegativeFeedbackLoop::make_ode((1, 2, 3), (5f32, 6f32, 7f32), (0.5, 0.6, 0.7), (0.1, 0.2, 0.3), (0.4, 0.5, 0.6), ODESolver::RK4, 5f32),
+    );
+}
+
+#[test]
+fn parse_errors_position() {
+    let error = |text: &str| ReactionNetwork::parse(text).err().map(|error| (error.line, error.column, error.message));
+
+    assert_eq!(
+        error("e + s -> es @ 0.01\nes -> e + p 0.1"),
+        Some((2, 13, "expected '@' followed by the kinetic constant, found number '0.1'".to_owned()))
+    );
+    assert_eq!(
+        error("a -> b @ 1\n  a = 1.5"),
+        Some((2, 7, "initial amount '1.5' is not a non-negative integer".to_owned()))
+    );
+    assert_eq!(
+        error("a -> b @ 1; c = 3"),
+        Some((1, 13, "species 'c' has an initial amount but appears in no reaction".to_owned()))
+    );
+    assert_eq!(
+        error("a + b\n  -> c @ 1 $ 2"),
+        Some((2, 12, "unexpected character '$'".to_owned()))
+    );
+}
+
+fn moiety(species: &[&str]) -> ConservedMoiety {
+    ConservedMoiety(species.iter().map(|name| (1, Molecule::new(name))).collect())
+}
+
+#[test]
+fn stoichiometry_matrix_of_enzymatic_activity() {
+    let ode = ChemicalReactionODE::new(ReactionNetwork::parse(ENZYMATIC_ACTIVITY).unwrap().reactions);
+    let matrix = ode.stoichiometry_matrix();
+    let row = |name: &str| matrix.row(ode.get_species_id(&Molecule::new(name)).unwrap()).iter().cloned().collect::<Vec<_>>();
+
+    // binding, unbinding and catalysis
+    assert_eq!(matrix.shape(), (4, 3));
+    assert_eq!(row("e"), vec![-1, 1, 1]);
+    assert_eq!(row("s"), vec![-1, 1, 0]);
+    assert_eq!(row("es"), vec![1, -1, -1]);
+    assert_eq!(row("p"), vec![0, 0, 1]);
+}
+
+#[test]
+fn conserved_moieties_of_page_networks() {
+    let analysis = ChemicalReactionODE::new(ReactionNetwork::parse(ENZYMATIC_ACTIVITY).unwrap().reactions).analyze();
+    assert_eq!(analysis.conserved_moieties, vec![moiety(&["e", "es"]), moiety(&["es", "p", "s"])]);
+    assert_eq!(analysis.only_produced, vec![Molecule::new("p")]);
+
+    let enzyme = &analysis.conserved_moieties[0];
+    for (_, state) in EnzymaticActivity::make_ode(100, 50, 0.01, 0.5, 0.1, ODESolver::RK4, 5f32) {
+        assert!((enzyme.get_total(&state) - 100f32).abs() < 1e-3);
+    }
+
+    // each gene is either free or bound to the protein repressing it
+    let analysis = ChemicalReactionODE::new(ReactionNetwork::parse(NEGATIVE_FEEDBACK_LOOP).unwrap().reactions).analyze();
+    assert_eq!(analysis.conserved_moieties, vec![moiety(&["g2", "p1g2"]), moiety(&["g3", "p2g3"]), moiety(&["g1", "p3g1"])]);
+}
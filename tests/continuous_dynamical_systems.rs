@@ -0,0 +1,49 @@
+use nalgebra::Vector1;
+
+use rust_cmcs::continuous_dynamical_systems::{DelaySystem, DelayRk4};
+use rust_cmcs::continuous_dynamical_systems::{ReactionDiffusion, ReactionDiffusionModel, TimeStepping, BoundaryPeriodic};
+
+// y'(x) = -y(x - tau), whose solution is y = 1 - (x - x0) up to the first delay
+struct DelayedDecay {
+    delay: f32,
+}
+
+impl DelaySystem<Vector1<f32>> for DelayedDecay {
+    fn delays(&self) -> Vec<f32> {
+        vec![self.delay]
+    }
+
+    fn system(&self, _: f32, _: &Vector1<f32>, delayed: &[Vector1<f32>], dy: &mut Vector1<f32>) {
+        dy[0] = -delayed[0][0];
+    }
+}
+
+// x - delay lands just after the initial time on the first step, in f32
+#[test]
+fn delay_rk4_with_non_zero_initial_time() {
+    let mut stepper = DelayRk4::new(DelayedDecay { delay: 0.06 }, 0.01, 5.01, 1.0, |_| Vector1::new(1f32));
+    stepper.integrate().unwrap();
+
+    let (xs, ys) = stepper.results().get();
+    assert!((xs.last().unwrap() - 5.01).abs() < 1e-4);
+    assert!(ys.iter().all(|y| y[0].is_finite()));
+
+    let step = xs.iter().position(|x| *x > 0.069).unwrap();
+    assert!((ys[step][0] - (1f32 - (xs[step] - 0.01))).abs() < 1e-4, "y({}) = {}", xs[step], ys[step][0]);
+}
+
+// a step that is not positive never reaches the next sample
+#[test]
+fn reaction_diffusion_rejects_non_positive_steps() {
+    let model = ReactionDiffusionModel::GrayScott { feed: 0.035, kill: 0.065 };
+    let initial_state = model.initial_state((8, 8), 0);
+    let new = |time_step, space_step| ReactionDiffusion::<BoundaryPeriodic>::new(
+        model, initial_state.clone(), [0.16, 0.08], time_step, space_step, TimeStepping::Explicit, 1f32
+    );
+
+    assert!(new(0f32, 1f32).is_err());
+    assert!(new(-1f32, 1f32).is_err());
+    assert!(new(1f32, 0f32).is_err());
+    assert!(new(f32::NAN, 1f32).is_err());
+    assert!(new(1f32, 1f32).is_ok());
+}
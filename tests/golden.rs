@@ -0,0 +1,208 @@
+// Regression tests of the seeded models: each one is run for a fixed number of steps
+// and compared with the trajectory (or the hashes of the lattices) stored in
+// tests/golden, so that any change of behaviour of the engines is flagged.
+//
+// After an intended change of behaviour the golden files are regenerated with
+//
+//   UPDATE_GOLDEN=1 cargo test --test golden
+
+use std::path::PathBuf;
+
+use rust_cmcs::prelude::*;
+use rust_cmcs::cellular_automata::prelude::*;
+use rust_cmcs::continuous_dynamical_systems::{ReactionDiffusion, ReactionDiffusionModel, TimeStepping, ConcentrationLattice, BoundaryFixed, BoundaryPeriodic};
+use rust_cmcs::chemical_reactions::prelude::Molecule;
+use rust_cmcs::stochastic_simulation::prelude::*;
+use rust_cmcs::multiset_rewriting::prelude::*;
+use rust_cmcs::discrete_event_simulation::prelude::*;
+
+const SEED: u64 = 42;
+
+#[test]
+fn forest_fire() {
+    use forest_fire::*;
+
+    let model = ForestFireModel::new(32, SEED, 0.001, 0.01).unwrap();
+    let rows = lattice_rows(model.take(100), |cell| match cell {
+        Cell::GreenTree => b'g',
+        Cell::BurningTree => b'b',
+        Cell::Empty => b'e',
+    });
+
+    assert_golden("forest_fire", &rows);
+}
+
+#[test]
+fn sand_hourglass() {
+    use sand_hourglass::*;
+
+    let model = SandHourglassModel::from_str(maps::HOURGLASS, SEED, 0.198).unwrap();
+    let rows = lattice_rows(model.take(200), |cell| match cell {
+        Cell::Sand => b's',
+        Cell::Empty => b'e',
+        Cell::Wall => b'w',
+    });
+
+    assert_golden("sand_hourglass", &rows);
+}
+
+#[test]
+fn ssa_lotka_volterra() {
+    let make_ssa = || LotkaVolterra::make_ssa(180, 150, 18f32, 12f32, 0.1, 1, SEED);
+
+    assert_golden("ssa_lotka_volterra_direct", &ssa_trajectory(make_ssa().with_method(SSAMethod::Direct)));
+    assert_golden("ssa_lotka_volterra_nrm", &ssa_trajectory(make_ssa().with_method(SSAMethod::NextReaction)));
+    assert_golden("ssa_lotka_volterra_tau", &ssa_trajectory(make_ssa().with_method(SSAMethod::TauLeaping)));
+}
+
+#[test]
+fn ssa_enzymatic_activity() {
+    let model = EnzymaticActivity::make_ssa(100, 100, 0.3, 10f32, 10f32, SEED);
+
+    assert_golden("ssa_enzymatic_activity", &ssa_trajectory(model.with_method(SSAMethod::Direct)));
+}
+
+#[test]
+fn ssa_negative_feedback_loop() {
+    let model = NegativeFeedbackLoop::make_ssa((5, 1, 3), (10f32, 10000f32, 10f32), (10f32, 0.1, 10f32), (2f32, 20f32, 20f32), (1f32, 100f32, 1f32), SEED);
+
+    assert_golden("ssa_negative_feedback_loop", &ssa_trajectory(model.with_method(SSAMethod::Direct)));
+}
+
+#[test]
+fn ssa_delayed_negative_feedback_loop() {
+    let make_ssa = |delay| NegativeFeedbackLoop::make_delayed_ssa((5, 1, 3), (10f32, 10000f32, 10f32), (10f32, 0.1, 10f32), (2f32, 20f32, 20f32), (1f32, 100f32, 1f32), delay, SEED);
+
+    assert_golden("ssa_delayed_negative_feedback_loop_fixed", &ssa_trajectory(make_ssa(ReactionDelay::Fixed(0.01))));
+    assert_golden("ssa_delayed_negative_feedback_loop_gamma", &ssa_trajectory(make_ssa(ReactionDelay::Gamma { mean: 0.01, shape: 4f32 })));
+}
+
+#[test]
+fn ssa_gene_expression() {
+    let rates = GeneExpressionRates {
+        transcription: 20f32, repression_threshold: 5f32, hill_coefficient: 2f32, export: 1f32,
+        translation: 5f32, import: 0.5, mrna_decay: 0.5, protein_decay: 0.2,
+    };
+    let model = GeneExpression::make_ssa(1, (1f32, 4f32), rates, SEED);
+
+    assert_golden("ssa_gene_expression", &ssa_trajectory(model.with_method(SSAMethod::Direct)));
+}
+
+#[test]
+fn ssa_spatial_lotka_volterra() {
+    let model = SpatialLotkaVolterra::make_ssa(12, (50, 10), (1f32, 0.04, 0.4, 0.04), (0.1, 0.3), 0.5, SEED);
+    let (prey, predator) = LotkaVolterra::species();
+    let (prey, predator) = (model.get_species_id(&prey).unwrap(), model.get_species_id(&predator).unwrap());
+
+    let simulation = Simulation::new(model)
+        .max_steps(40)
+        .simulation_map(move |(time, lattice)| {
+            let total = |species: usize| lattice.cells.iter().map(|voxel| voxel[species]).sum::<u32>();
+            (time, (total(prey), total(predator)))
+        });
+
+    assert_golden("ssa_spatial_lotka_volterra", &simulation.to_csv(&["t", "v", "p"]));
+}
+
+#[test]
+fn reaction_diffusion() {
+    let model = ReactionDiffusionModel::Brusselator { a: 4.5, b: 7.5 };
+    let initial_state = model.initial_state((16, 16), SEED);
+    let mean_concentrations = |(time, lattice): (f32, ConcentrationLattice)| {
+        let mean = |species: usize| lattice.cells.iter().map(|cell| cell[species]).sum::<f32>() / lattice.cells.len() as f32;
+        (time, (mean(0), mean(1)))
+    };
+
+    let explicit = ReactionDiffusion::<BoundaryFixed>::new(model, initial_state.clone(), [2f32, 16f32], 0.01, 1f32, TimeStepping::Explicit, 0.5).unwrap();
+    let simulation = Simulation::new(explicit).max_steps(20).simulation_map(mean_concentrations);
+    assert_golden("reaction_diffusion_explicit", &simulation.to_csv(&["t", "u", "v"]));
+
+    let adi = ReactionDiffusion::<BoundaryPeriodic>::new(model, initial_state, [2f32, 16f32], 0.05, 1f32, TimeStepping::ADI, 0.5).unwrap();
+    let simulation = Simulation::new(adi).max_steps(20).simulation_map(mean_concentrations);
+    assert_golden("reaction_diffusion_adi", &simulation.to_csv(&["t", "u", "v"]));
+}
+
+#[test]
+fn p_system_frog_l_e_complexes() {
+    let model = FrogLEComplexes::build_model((400, 500, 100), 0.5, 1000, SEED);
+    let adults = FrogLEComplexes::adults_objects();
+
+    let names: Vec<String> = std::iter::once("t".to_owned())
+        .chain(adults.iter().map(|object| object.0.clone()))
+        .collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+    let simulation = Simulation::new(model)
+        .max_steps(50)
+        .simulation_map(move |(time, state)| (time, adults.iter().map(|object| state.get(object)).collect::<Vec<u32>>()));
+
+    assert_golden("p_system_frog_l_e_complexes", &simulation.to_csv(&names));
+}
+
+#[test]
+fn customer_queue() {
+    let model = CustomerQueue::build_des(0.5, 1.5, 0.5, SEED);
+    let simulation = Simulation::new(model)
+        .max_steps(300)
+        .simulation_map(|(time, state)| (time, (state.queue_length, state.operator_available as u32)));
+
+    assert_golden("customer_queue", &simulation.to_csv(&["t", "queue_length", "operator_available"]));
+}
+
+// one row per step: the count of each kind of cell and the hash of the whole lattice
+fn lattice_rows<C, I>(states: I, encode: impl Fn(&C) -> u8) -> String
+    where C: Clone + Eq, I: Iterator<Item = Result<Lattice<C>, String>>
+{
+    let mut rows = String::from("step,cells,hash\n");
+    for (step, state) in states.enumerate() {
+        let state = state.unwrap();
+        let cells: Vec<u8> = state.cells.iter().map(&encode).collect();
+
+        let mut kinds = cells.clone();
+        kinds.sort_unstable();
+        kinds.dedup();
+        let counts: Vec<String> = kinds.iter()
+            .map(|kind| format!("{}:{}", *kind as char, cells.iter().filter(|cell| *cell == kind).count()))
+            .collect();
+
+        rows += &format!("{step},{},{:016x}\n", counts.join(" "), fnv1a(&cells));
+    }
+
+    rows
+}
+
+// the species of the states are in hash order, the columns are sorted by name
+fn ssa_trajectory(model: impl Iterator<Item = (f32, Vec<(Molecule, u32)>)> + Clone) -> String {
+    let model = model.map(|(time, mut state)| {
+        state.sort_by(|(a, _), (b, _)| a.get_name().cmp(b.get_name()));
+        (time, state)
+    });
+
+    Simulation::new(model)
+        .max_steps(200)
+        .to_csv(&["t"])
+}
+
+// hash stable across platforms and compiler versions, unlike the ones of std
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn assert_golden(name: &str, actual: &str) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", &format!("{name}.csv")].iter().collect();
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("cannot read {}: {err}, run with UPDATE_GOLDEN=1 to create it", path.display()))
+        .replace("\r\n", "\n");
+
+    if let Some((line, (expected, actual))) = expected.lines().zip(actual.lines()).enumerate().find(|(_, (a, b))| a != b) {
+        panic!("{name} drifted from {} at line {}:\n  expected: {expected}\n  actual:   {actual}", path.display(), line + 1);
+    }
+    assert_eq!(expected.lines().count(), actual.lines().count(), "{name} drifted from {}: different number of rows", path.display());
+}
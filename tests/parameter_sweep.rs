@@ -0,0 +1,32 @@
+use rust_cmcs::utils::parameter_sweep::*;
+
+const SEED: u64 = 42;
+
+// f = a + 2b with a, b uniform in [0, 1]: Var(f) = 1/12 + 4/12, so S_a = 0.2 and S_b = 0.8,
+// with total effects equal to the first order indices as the function is additive
+#[test]
+fn sobol_indices_of_additive_function() {
+    let space = ParameterSpace::new()
+        .parameter("a", (0f32, 1f32))
+        .parameter("b", (0f32, 1f32));
+
+    let indices = space.sobol_indices(5000, SEED, |p| [(0f32, p[0] + 2f32 * p[1])]);
+
+    for (indices, expected) in indices.iter().zip([0.2f32, 0.8f32]) {
+        let (first_order, total) = (indices.first_order.final_value, indices.total.final_value);
+        assert!((first_order - expected).abs() < 0.05, "first order index of {} is {first_order}", indices.parameter);
+        assert!((total - expected).abs() < 0.05, "total index of {} is {total}", indices.parameter);
+    }
+}
+
+#[test]
+fn sweep_summaries() {
+    let space = ParameterSpace::new().parameter("a", (1f32, 3f32));
+
+    let sweep = space.sweep(space.grid(3), |p| [(0f32, 0f32), (1f32, p[0]), (2f32, p[0] / 2f32)]);
+
+    assert_eq!(sweep.samples, vec![vec![1f32], vec![2f32], vec![3f32]]);
+    assert_eq!(sweep.get_outputs(SummaryOutput::Peak), vec![1f32, 2f32, 3f32]);
+    assert_eq!(sweep.get_outputs(SummaryOutput::TimeToPeak), vec![1f32; 3]);
+    assert_eq!(sweep.get_outputs(SummaryOutput::FinalValue), vec![0.5f32, 1f32, 1.5f32]);
+}
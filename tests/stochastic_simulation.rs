@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use rust_cmcs::chemical_reactions::prelude::*;
+use rust_cmcs::continuous_dynamical_systems::ODESolver;
+use rust_cmcs::stochastic_simulation::prelude::*;
+
+const SEED: u64 = 42;
+
+// the Hill law does not vanish with X, the reaction must stop when X is used up
+#[test]
+fn non_mass_action_propensity_without_reactants() {
+    let (x, y) = (Molecule::new("X"), Molecule::new("Y"));
+    let reactions = vec![
+        Reaction::new(1f32, [(1, &x)], []).with_kinetic_law(KineticLaw::hill_repression(&y, 10f32, 5f32, 2f32)),
+    ];
+    let initial_state: HashMap<Molecule, u32> = [(x.clone(), 3), (y.clone(), 0)].into_iter().collect();
+    let make_ssa = || StochasticSimulation::new(reactions.clone(), initial_state.clone(), SEED);
+
+    let engines = [
+        make_ssa().with_method(SSAMethod::Direct),
+        make_ssa().with_method(SSAMethod::NextReaction),
+        make_ssa().with_method(SSAMethod::TauLeaping),
+        make_ssa().with_delays(vec![Some(ReactionDelay::Fixed(0.5))]),
+    ];
+
+    for engine in engines {
+        let amounts: Vec<u32> = engine.take(100)
+            .map(|(_, state)| state.into_iter().find(|(molecule, _)| molecule == &x).unwrap().1)
+            .collect();
+
+        assert!(amounts.iter().all(|amount| *amount <= 3), "X went negative: {amounts:?}");
+        assert_eq!(amounts.last(), Some(&0));
+    }
+}
+
+// birth-death process `-> X` at rate k, `X ->` at rate 1, whose stationary mean is k
+fn birth_death(birth_rate: f32, seed: u64) -> StochasticSimulation {
+    let x = Molecule::new("X");
+    let reactions = vec![
+        Reaction::new(birth_rate, [], [(1, &x)]),
+        Reaction::new(1f32, [(1, &x)], []),
+    ];
+
+    StochasticSimulation::new(reactions, [(x, 0)].into_iter().collect(), seed)
+}
+
+fn birth_death_summary(simulation: StochasticSimulation) -> Vec<f32> {
+    let times: Vec<f32> = (5..=15).map(|time| time as f32).collect();
+    let states = states_at(simulation, &times);
+
+    let mean = states.iter()
+        .map(|state| state.iter().find(|(molecule, _)| molecule.get_name() == "X").map_or(0f32, |(_, amount)| *amount as f32))
+        .sum::<f32>() / times.len() as f32;
+
+    vec![mean]
+}
+
+#[test]
+fn abc_recovers_birth_rate() {
+    let observed = birth_death_summary(birth_death(20f32, SEED));
+    let abc = ApproximateBayesianComputation::new(vec![Prior::Uniform(1f32, 50f32)], observed, SEED);
+
+    let posterior = abc.smc(100, 4, |parameters, seed| birth_death(parameters[0], seed), birth_death_summary);
+    let birth_rate = posterior.mean()[0];
+    assert!((birth_rate - 20f32).abs() < 3f32, "posterior mean of the birth rate is {birth_rate}");
+
+    let posterior = abc.rejection(50, 0.1, |parameters, seed| birth_death(parameters[0], seed), birth_death_summary);
+    let birth_rate = posterior.mean()[0];
+    assert!((birth_rate - 20f32).abs() < 3f32, "posterior mean of the birth rate is {birth_rate}");
+}
+
+#[test]
+fn abc_without_particles() {
+    let abc = ApproximateBayesianComputation::new(vec![Prior::Uniform(1f32, 50f32)], vec![20f32], SEED);
+
+    let posterior = abc.smc(0, 3, |parameters, seed| birth_death(parameters[0], seed), birth_death_summary);
+    assert!(posterior.particles.is_empty());
+}
+
+// the mRNA is transcribed by each copy of the gene, before any protein represses it
+#[test]
+fn gene_expression_transcription_per_gene_copy() {
+    let rates = GeneExpressionRates {
+        transcription: 20f32, repression_threshold: 5f32, hill_coefficient: 2f32, export: 1f32,
+        translation: 5f32, import: 0.5, mrna_decay: 0.5, protein_decay: 0.2,
+    };
+    let ((_, mrna_nucleus, _), _) = GeneExpression::species();
+    let nuclear_mrna = |genes: u32| GeneExpression::make_ode(genes, (2f32, 4f32), rates, ODESolver::RK4, 0.05).into_iter()
+        .last()
+        .and_then(|(_, state)| state.into_iter().find(|(molecule, _)| molecule == &mrna_nucleus))
+        .unwrap().1;
+
+    let (one_gene, two_genes) = (nuclear_mrna(1), nuclear_mrna(2));
+    assert!(one_gene > 0f32);
+    assert!((two_genes / one_gene - 2f32).abs() < 0.01, "nuclear mRNA {one_gene} with one gene and {two_genes} with two");
+}
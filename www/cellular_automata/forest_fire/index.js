@@ -1,5 +1,6 @@
 class Model {}
 class Params {}
+class Stream {}
 
 const button_play = `<svg class="w-6 h-6 text-gray-800" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" width="24" height="24" fill="currentColor" viewBox="0 0 24 24">
   <path fill-rule="evenodd" d="M8.6 5.2A1 1 0 0 0 7 6v12a1 1 0 0 0 1.6.8l8-6a1 1 0 0 0 0-1.6l-8-6Z" clip-rule="evenodd"/>
@@ -22,9 +23,12 @@ const seed = document.getElementById("seed");
 
 const rewind = document.getElementById("rewind");
 const play_pause = document.getElementById("play_pause");
+const endless = document.getElementById("endless");
 
 let chart = null;
 let model = null;
+let stream = null;
+let animation = null;
 var playing = false;
 var anim_speed = 72;
 
@@ -36,9 +40,10 @@ export function main() {
 }
 
 /** This function is used in `bootstrap.js` to setup imports. */
-export function setup(WasmModel, WasmParams) {
+export function setup(WasmModel, WasmParams, WasmStream) {
     Model = WasmModel;
     Params = WasmParams;
+    Stream = WasmStream;
 }
 
 /** Add event listeners. */
@@ -55,6 +60,7 @@ function setupUI() {
 
     rewind.addEventListener("click", rewindFn);
     play_pause.addEventListener("click", playPauseFn);
+    endless.addEventListener("click", endlessFn);
 }
 
 function rewindFn() {
@@ -120,18 +126,20 @@ function updateImage() {
 
 function updateModel() {
     step.value = 0;
-    model = Model.build(
-        Params.builder()
-            .size(start_state.value)
-            .lightning_probability(Number(lightning_prob.value))
-            .growing_probability(Number(growing_prob.value))
-            .max_time(max_time.value)
-            .simulation_seed(seed.value)
-    );
+    model = Model.build(buildParams());
 
     step.max = model.max_step();
 }
 
+function buildParams() {
+    return Params.builder()
+        .size(start_state.value)
+        .lightning_probability(Number(lightning_prob.value))
+        .growing_probability(Number(growing_prob.value))
+        .max_time(max_time.value)
+        .simulation_seed(seed.value);
+}
+
 function updateModelAndDraw() {
     updatePlayPause(false);
     updateModel();
@@ -143,6 +151,7 @@ function updatePlot() {
     status.innerText = "Rendering...";
     const start = performance.now();
 
+    stopEndless();
     chart = model.draw(canvas, step.value);
     updateImage();
     canvas_text.innerHTML = `Current Step: ${step.value}, ` +
@@ -151,3 +160,38 @@ function updatePlot() {
     const end = performance.now();
     status.innerText = `Rendered in ${Math.ceil(end - start)}ms`;
 }
+
+/** Run the automaton past the max time, computing it a frame at a time until stopped. */
+function endlessFn() {
+    const running = stream != null;
+    updatePlayPause(false);
+    stopEndless();
+    if (running) {
+        return;
+    }
+
+    stream = Stream.build(buildParams());
+    var last_frame = null;
+    const frame = (time) => {
+        if (last_frame == null || time - last_frame >= anim_speed) {
+            last_frame = time;
+            stream.advance_steps(1);
+            stream.draw(canvas);
+            updateImage();
+            status.innerText = `Running endlessly, step ${stream.step()}`;
+        }
+        animation = requestAnimationFrame(frame);
+    };
+    animation = requestAnimationFrame(frame);
+}
+
+function stopEndless() {
+    if (animation != null) {
+        cancelAnimationFrame(animation);
+        animation = null;
+    }
+    if (stream != null) {
+        stream.free();
+        stream = null;
+    }
+}
@@ -4,6 +4,8 @@ class Params {}
 const canvas = document.getElementById("canvas");
 const status = document.getElementById("status");
 const canvas_text = document.getElementById("canvas_text");
+const plot_type = document.getElementById("plot_type");
+const show_steady_states = document.getElementById("show_steady_states");
 const solver = document.getElementById("solver");
 
 const init_prey_pop = document.getElementById("init_prey_pop");
@@ -32,6 +34,8 @@ export function setup(WasmModel, WasmParams) {
 function setupUI() {
     status.innerText = "WebAssembly loaded!";
     window.addEventListener("resize", setupCanvas);
+	plot_type.addEventListener("change", updatePlot);
+	show_steady_states.addEventListener("change", updatePlot);
 	solver.addEventListener("input", updatePlot);
 	init_prey_pop.addEventListener("input", updatePlot);
 	init_predator_pop.addEventListener("input", updatePlot);
@@ -68,8 +72,9 @@ function updatePlot() {
         .prey_birth_rate(Number(prey_birth_rate.value))
         .predator_death_rate(Number(predator_death_rate.value))
         .hunting_meetings(Number(hunting_meetings.value))
-        .hunt_offsprings(Number(hunt_offsprings.value));
-    chart = Model.draw(canvas, params);
+        .hunt_offsprings(Number(hunt_offsprings.value))
+        .show_steady_states(show_steady_states.checked);
+    chart = Model.draw(canvas, plot_type.value, params);
     canvas_text.innerHTML = `Max Time ($ t $): ${max_time.value}, ` +
         `Initial Prey Pop ($ V(0) $): ${init_prey_pop.value}, ` + 
         `Initial Predator Pop ($ P(0) $): ${init_predator_pop.value}, ` + 
@@ -0,0 +1,127 @@
+class Model {}
+class Params {}
+
+const image = document.getElementById("image");
+const canvas = document.createElement("canvas");
+const status = document.getElementById("status");
+const canvas_text = document.getElementById("canvas_text");
+
+const model = document.getElementById("model");
+const stepping = document.getElementById("stepping");
+const boundary_condition = document.getElementById("boundary_condition");
+const size = document.getElementById("size");
+const max_time = document.getElementById("max_time");
+const time_step = document.getElementById("time_step");
+const diffusion_u = document.getElementById("diffusion_u");
+const diffusion_v = document.getElementById("diffusion_v");
+const feed = document.getElementById("feed");
+const kill = document.getElementById("kill");
+const a = document.getElementById("a");
+const b = document.getElementById("b");
+const epsilon = document.getElementById("epsilon");
+const a0 = document.getElementById("a0");
+const a1 = document.getElementById("a1");
+const seed = document.getElementById("seed");
+
+// default time scales and diffusion coefficients of each model
+const presets = {
+    gray_scott: { max_time: 5000, time_step: 1, diffusion_u: 0.16, diffusion_v: 0.08 },
+    brusselator: { max_time: 50, time_step: 0.05, diffusion_u: 2, diffusion_v: 16 },
+    fitzhugh_nagumo: { max_time: 100, time_step: 0.05, diffusion_u: 1, diffusion_v: 20 },
+};
+
+let chart = null;
+
+/** Main entry point */
+export function main() {
+    setupUI();
+    setupCanvas();
+}
+
+/** This function is used in `bootstrap.js` to setup imports. */
+export function setup(WasmModel, WasmParams) {
+    Model = WasmModel;
+    Params = WasmParams;
+}
+
+/** Add event listeners. */
+function setupUI() {
+    status.innerText = "WebAssembly loaded!";
+    window.addEventListener("resize", setupCanvas);
+
+	model.addEventListener("change", selectModel);
+	stepping.addEventListener("change", updatePlot);
+	boundary_condition.addEventListener("change", updatePlot);
+	size.addEventListener("change", updatePlot);
+	max_time.addEventListener("change", updatePlot);
+	time_step.addEventListener("change", updatePlot);
+	diffusion_u.addEventListener("change", updatePlot);
+	diffusion_v.addEventListener("change", updatePlot);
+	feed.addEventListener("change", updatePlot);
+	kill.addEventListener("change", updatePlot);
+	a.addEventListener("change", updatePlot);
+	b.addEventListener("change", updatePlot);
+	epsilon.addEventListener("change", updatePlot);
+	a0.addEventListener("change", updatePlot);
+	a1.addEventListener("change", updatePlot);
+	seed.addEventListener("change", updatePlot);
+}
+
+/** Show the parameters of the selected model and load its preset. */
+function selectModel() {
+    for (const name in presets) {
+        document.getElementById(name + "_params").classList.toggle("hidden", name != model.value);
+    }
+
+    const preset = presets[model.value];
+    max_time.value = preset.max_time;
+    time_step.value = preset.time_step;
+    diffusion_u.value = preset.diffusion_u;
+    diffusion_v.value = preset.diffusion_v;
+    updatePlot();
+}
+
+/** Setup canvas to properly handle high DPI and redraw current plot. */
+function setupCanvas() {
+	const dpr = window.devicePixelRatio || 1.0;
+    const aspectRatio = image.width / image.height;
+    var width = image.parentNode.offsetWidth * 0.8;
+    if (width < 400)
+        width = 400;
+    image.style.width = width + "px";
+    image.style.height = width / aspectRatio + "px";
+    image.width = width;
+    image.height = width / aspectRatio;
+    updatePlot();
+}
+
+/** Redraw currently selected plot. */
+function updatePlot() {
+    status.innerText = "Rendering...";
+    const start = performance.now();
+
+    var params = Params.builder()
+        .model(model.value)
+        .stepping(stepping.value)
+        .fixed_boundary(boundary_condition.value == "fixed")
+        .size(Number(size.value))
+        .max_time(Number(max_time.value))
+        .time_step(Number(time_step.value))
+        .diffusion_u(Number(diffusion_u.value))
+        .diffusion_v(Number(diffusion_v.value))
+        .feed(Number(feed.value))
+        .kill(Number(kill.value))
+        .a(Number(a.value))
+        .b(Number(b.value))
+        .epsilon(Number(epsilon.value))
+        .a0(Number(a0.value))
+        .a1(Number(a1.value))
+        .seed(seed.value);
+    chart = Model.draw(canvas, params);
+    image.src = canvas.toDataURL("image/png");
+    canvas_text.innerHTML = `Max Time (t): ${max_time.value}, ` +
+        `Time Step: ${time_step.value}, ` +
+        `Grid Size: ${size.value}`;
+    const end = performance.now();
+    status.innerText = `Rendered in ${Math.ceil(end - start)}ms`;	
+}
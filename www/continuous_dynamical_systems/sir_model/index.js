@@ -11,6 +11,7 @@ const init_infected_pop = document.getElementById("init_infected_pop");
 const infection_coefficient = document.getElementById("infection_coefficient");
 const recovery_coefficient = document.getElementById("recovery_coefficient");
 const max_time = document.getElementById("max_time");
+const observed_data = document.getElementById("observed_data");
 
 let chart = null;
 
@@ -36,6 +37,7 @@ function setupUI() {
     infection_coefficient.addEventListener("input", updatePlot);
 	recovery_coefficient.addEventListener("input", updatePlot);
 	max_time.addEventListener("input", updatePlot);
+	observed_data.addEventListener("change", updatePlot);
 }
 
 /** Setup canvas to properly handle high DPI and redraw current plot. */
@@ -70,8 +72,14 @@ function updatePlot() {
         .initial_infected_population(Number(infected))
         .initial_recovered_population(recovered)
         .infection_coefficient(Number(infection_coefficient.value))
-        .recovery_coefficient(Number(recovery_coefficient.value));
-    chart = Model.draw(canvas, params);
+        .recovery_coefficient(Number(recovery_coefficient.value))
+        .observed_data(String(observed_data.value));
+    try {
+        chart = Model.draw(canvas, params);
+    } catch (error) {
+        status.innerText = `Error: ${error}`;
+        return;
+    }
     canvas_text.innerHTML = `Max Time ($ t $): ${max_time.value}, ` +
         `Initial Susceptible Pop ($ S(0) $): ${susceptible.toFixed(2)}, ` + 
         `Initial Infected Pop ($ I(0) $): ${infected.toFixed(2)}<br/>` + 
@@ -4,6 +4,8 @@ class Params {}
 const canvas = document.getElementById("canvas");
 const status = document.getElementById("status");
 const canvas_text = document.getElementById("canvas_text");
+const plot_type = document.getElementById("plot_type");
+const show_steady_states = document.getElementById("show_steady_states");
 const solver = document.getElementById("solver");
 
 const init_susceptible_pop = document.getElementById("init_susceptible_pop");
@@ -31,6 +33,8 @@ export function setup(WasmModel, WasmParams) {
 function setupUI() {
     status.innerText = "WebAssembly loaded!";
     window.addEventListener("resize", setupCanvas);
+	plot_type.addEventListener("change", updatePlot);
+	show_steady_states.addEventListener("change", updatePlot);
 	solver.addEventListener("input", updatePlot);
 	init_susceptible_pop.addEventListener("input", updatePlot);
 	init_infected_pop.addEventListener("input", updatePlot);
@@ -73,8 +77,9 @@ function updatePlot() {
         .initial_recovered_population(recovered)
         .infection_coefficient(Number(infection_coefficient.value))
         .recovery_coefficient(Number(recovery_coefficient.value))
-        .birth_rate(Number(birth_rate.value));
-    chart = Model.draw(canvas, params);
+        .birth_rate(Number(birth_rate.value))
+        .show_steady_states(show_steady_states.checked);
+    chart = Model.draw(canvas, plot_type.value, params);
     canvas_text.innerHTML = `Max Time ($ t $): ${max_time.value}, ` +
         `Initial Susceptible Pop ($ S(0) $): ${susceptible.toFixed(2)}, ` + 
         `Initial Infected Pop ($ I(0) $): ${infected.toFixed(2)},` + 
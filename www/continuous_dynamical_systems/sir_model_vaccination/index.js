@@ -4,6 +4,7 @@ class Params {}
 const canvas = document.getElementById("canvas");
 const status = document.getElementById("status");
 const canvas_text = document.getElementById("canvas_text");
+const plot_type = document.getElementById("plot_type");
 const solver = document.getElementById("solver");
 
 const init_susceptible_pop = document.getElementById("init_susceptible_pop");
@@ -32,6 +33,7 @@ export function setup(WasmModel, WasmParams) {
 function setupUI() {
     status.innerText = "WebAssembly loaded!";
     window.addEventListener("resize", setupCanvas);
+	plot_type.addEventListener("change", updatePlot);
 	solver.addEventListener("input", updatePlot);
 	init_susceptible_pop.addEventListener("input", updatePlot);
 	init_infected_pop.addEventListener("input", updatePlot);
@@ -77,7 +79,7 @@ function updatePlot() {
         .recovery_coefficient(Number(recovery_coefficient.value))
         .birth_rate(Number(birth_rate.value))
         .vaccination_coefficient(Number(vaccination_coefficient.value));
-    chart = Model.draw(canvas, params);
+    chart = Model.draw(canvas, plot_type.value, params);
     canvas_text.innerHTML = `Max Time ($ t $): ${max_time.value}, ` +
         `Initial Susceptible Pop ($ S(0) $): ${susceptible.toFixed(2)}, ` + 
         `Initial Infected Pop ($ I(0) $): ${infected.toFixed(2)}, ` + 
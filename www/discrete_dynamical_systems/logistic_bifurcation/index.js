@@ -0,0 +1,79 @@
+class Model {}
+class Params {}
+
+const canvas = document.getElementById("canvas");
+const status = document.getElementById("status");
+const canvas_text = document.getElementById("canvas_text");
+
+const min_birth_rate = document.getElementById("min_birth_rate");
+const max_birth_rate = document.getElementById("max_birth_rate");
+const samples = document.getElementById("samples");
+const init_pop = document.getElementById("init_pop");
+const transient = document.getElementById("transient");
+const recorded = document.getElementById("recorded");
+
+let chart = null;
+
+/** Main entry point */
+export function main() {
+    setupUI();
+    setupCanvas();
+}
+
+/** This function is used in `bootstrap.js` to setup imports. */
+export function setup(WasmModel, WasmParams) {
+    Model = WasmModel;
+    Params = WasmParams;
+}
+
+/** Add event listeners. */
+function setupUI() {
+    status.innerText = "WebAssembly loaded!";
+    window.addEventListener("resize", setupCanvas);
+	min_birth_rate.addEventListener("input", updatePlot);
+	max_birth_rate.addEventListener("input", updatePlot);
+	samples.addEventListener("input", updatePlot);
+	init_pop.addEventListener("input", updatePlot);
+	transient.addEventListener("input", updatePlot);
+	recorded.addEventListener("input", updatePlot);
+}
+
+/** Setup canvas to properly handle high DPI and redraw current plot. */
+function setupCanvas() {
+	const dpr = window.devicePixelRatio || 1.0;
+    const aspectRatio = canvas.width / canvas.height;
+    var size = canvas.parentNode.offsetWidth * 0.8;
+    if (size < 600)
+        size = 600;
+    canvas.style.width = size + "px";
+    canvas.style.height = size / aspectRatio + "px";
+    canvas.width = size;
+    canvas.height = size / aspectRatio;
+    updatePlot();
+}
+
+/** Redraw currently selected plot. */
+function updatePlot() {
+    status.innerText = "Rendering...";
+    const start = performance.now();
+    if (Number(min_birth_rate.value) >= Number(max_birth_rate.value)) {
+        min_birth_rate.value = String(Number(max_birth_rate.value) - 0.01);
+    }
+    var params = Params.builder()
+        .initial_population(Number(init_pop.value))
+        .carrying_capacity(100)
+        .min_birth_rate(Number(min_birth_rate.value))
+        .max_birth_rate(Number(max_birth_rate.value))
+        .samples(Number(samples.value))
+        .transient(Number(transient.value))
+        .recorded(Number(recorded.value));
+    chart = Model.draw(canvas, params);
+    canvas_text.innerHTML = `Birth Rate ($ r $): ${min_birth_rate.value} - ${max_birth_rate.value}, ` +
+        `Initial Pop ($ N(0) $): ${init_pop.value}, ` +
+        `Carrying Capacity ($ K $): 100,<br/>` +
+        `Discarded Iterations: ${transient.value}, ` +
+        `Recorded Iterations: ${recorded.value}`;
+    MathJax.typeset();
+    const end = performance.now();
+    status.innerText = `Rendered in ${Math.ceil(end - start)}ms`;	
+}
@@ -1,20 +1,25 @@
 class Model {}
 class Params {}
+class Stream {}
 
 const canvas = document.getElementById("canvas");
 const status = document.getElementById("status");
 const canvas_text = document.getElementById("canvas_text");
+const animate = document.getElementById("animate");
 const solver = document.getElementById("solver");
 
 const init_enzyme = document.getElementById("init_enzyme");
 const init_reactant = document.getElementById("init_reactant");
 const seed = document.getElementById("seed");
+const replicas = document.getElementById("replicas");
 const binding_coeff = document.getElementById("binding_coeff");
 const unbinding_coeff = document.getElementById("unbinding_coeff");
 const catalysis_coeff = document.getElementById("catalysis_coeff");
 const max_time = document.getElementById("max_time");
 
 let chart = null;
+let stream = null;
+let animation = null;
 
 /** Main entry point */
 export function main() {
@@ -23,20 +28,23 @@ export function main() {
 }
 
 /** This function is used in `bootstrap.js` to setup imports. */
-export function setup(WasmModel, WasmParams) {
+export function setup(WasmModel, WasmParams, WasmStream) {
     Model = WasmModel;
     Params = WasmParams;
+    Stream = WasmStream;
 }
 
 /** Add event listeners. */
 function setupUI() {
     status.innerText = "WebAssembly loaded!";
     window.addEventListener("resize", setupCanvas);
+    animate.addEventListener("click", animateFn);
     solver.addEventListener("change", updatePlot);
     
 	init_enzyme.addEventListener("input", updatePlot);
 	init_reactant.addEventListener("input", updatePlot);
 	seed.addEventListener("input", updatePlot);
+	replicas.addEventListener("input", updatePlot);
 	binding_coeff.addEventListener("input", updatePlot);
 	unbinding_coeff.addEventListener("input", updatePlot);
 	catalysis_coeff.addEventListener("input", updatePlot);
@@ -62,12 +70,47 @@ function updatePlot() {
     status.innerText = "Rendering...";
     const start = performance.now();
 
+    stopAnimation();
+    const [chosen_solver, params] = buildParams();
+    chart = Model.draw(canvas, chosen_solver, params);
+    canvas_text.innerHTML = `Max Time ($ t $): ${max_time.value}, ` +
+        `Initial Enzyme ($ E(0) $): ${init_enzyme.value}, ` + 
+        `Initial Reactant ($ S(0) $): ${init_reactant.value}<br/>` + 
+        `Binding Coefficient ($ b $): ${binding_coeff.value}, ` + 
+        `Unbinding Coefficient ($ ub $): ${unbinding_coeff.value}, ` + 
+        `Catalysis Coefficient ($ c $): ${catalysis_coeff.value}`;
+    MathJax.typeset();
+    const end = performance.now();
+    status.innerText = `Rendered in ${Math.ceil(end - start)}ms`;	
+}
+
+/** Parameters of the page, with the algorithm of the chosen solver. */
+function buildParams() {
     var params = Params.builder();
     var chosen_solver = "";
-    if (solver.value == "ssa") {
+    if (solver.value == "ssa" || solver.value == "nrm" || solver.value == "tau") {
         chosen_solver = "ssa";
+        params = params
+            .ssa_method(solver.value == "ssa" ? "direct" : solver.value)
+            .ssa_seed(seed.value);
+    }
+    else if (solver.value == "cle") {
+        chosen_solver = "cle";
         params = params.ssa_seed(seed.value);
     }
+    else if (solver.value == "compare") {
+        chosen_solver = "compare";
+        params = params
+            .solver("rk4")
+            .ssa_seed(seed.value);
+    }
+    else if (solver.value == "ensemble") {
+        chosen_solver = "ensemble";
+        params = params
+            .solver("rk4")
+            .ssa_seed(seed.value)
+            .ssa_replicas(Number(replicas.value));
+    }
     else {
         chosen_solver = "ode";
         params = params.solver(solver.value);
@@ -80,14 +123,43 @@ function updatePlot() {
         .binding_rate(Number(binding_coeff.value))
         .unbinding_rate(Number(unbinding_coeff.value))
         .catalysis_rate(Number(catalysis_coeff.value));
-    chart = Model.draw(canvas, chosen_solver, params);
-    canvas_text.innerHTML = `Max Time ($ t $): ${max_time.value}, ` +
-        `Initial Enzyme ($ E(0) $): ${init_enzyme.value}, ` + 
-        `Initial Reactant ($ S(0) $): ${init_reactant.value}<br/>` + 
-        `Binding Coefficient ($ b $): ${binding_coeff.value}, ` + 
-        `Unbinding Coefficient ($ ub $): ${unbinding_coeff.value}, ` + 
-        `Catalysis Coefficient ($ c $): ${catalysis_coeff.value}`;
-    MathJax.typeset();
-    const end = performance.now();
-    status.innerText = `Rendered in ${Math.ceil(end - start)}ms`;	
+
+    return [chosen_solver, params];
+}
+
+/** Animate the chosen trajectory, computing it a frame at a time. */
+function animateFn() {
+    stopAnimation();
+
+    const [chosen_solver, params] = buildParams();
+    if (chosen_solver == "compare" || chosen_solver == "ensemble") {
+        status.innerText = "Only a single trajectory can be animated";
+        return;
+    }
+
+    stream = Stream.build(chosen_solver, params);
+    const time_step = Number(max_time.value) / 300;
+    const frame = () => {
+        stream.advance_time(time_step);
+        stream.draw(canvas);
+        status.innerText = `Animating, t = ${stream.time().toFixed(3)}`;
+
+        if (stream.finished()) {
+            stopAnimation();
+        } else {
+            animation = requestAnimationFrame(frame);
+        }
+    };
+    animation = requestAnimationFrame(frame);
+}
+
+function stopAnimation() {
+    if (animation != null) {
+        cancelAnimationFrame(animation);
+        animation = null;
+    }
+    if (stream != null) {
+        stream.free();
+        stream = null;
+    }
 }
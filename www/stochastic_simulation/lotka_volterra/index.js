@@ -1,9 +1,11 @@
 class Model {}
 class Params {}
+class Stream {}
 
 const canvas = document.getElementById("canvas");
 const status = document.getElementById("status");
 const canvas_text = document.getElementById("canvas_text");
+const animate = document.getElementById("animate");
 const solver = document.getElementById("solver");
 
 const init_prey_pop = document.getElementById("init_prey_pop");
@@ -13,9 +15,12 @@ const predator_death_rate = document.getElementById("predator_death_rate");
 const hunting_meetings = document.getElementById("hunting_meetings");
 const hunt_offsprings = document.getElementById("hunt_offsprings");
 const seed = document.getElementById("seed");
+const replicas = document.getElementById("replicas");
 const max_time = document.getElementById("max_time");
 
 let chart = null;
+let stream = null;
+let animation = null;
 
 /** Main entry point */
 export function main() {
@@ -24,17 +29,20 @@ export function main() {
 }
 
 /** This function is used in `bootstrap.js` to setup imports. */
-export function setup(WasmModel, WasmParams) {
+export function setup(WasmModel, WasmParams, WasmStream) {
     Model = WasmModel;
     Params = WasmParams;
+    Stream = WasmStream;
 }
 
 /** Add event listeners. */
 function setupUI() {
     status.innerText = "WebAssembly loaded!";
     window.addEventListener("resize", setupCanvas);
+    animate.addEventListener("click", animateFn);
 	solver.addEventListener("input", updatePlot);
 	seed.addEventListener("input", updatePlot);
+	replicas.addEventListener("input", updatePlot);
 	init_prey_pop.addEventListener("input", updatePlot);
 	init_predator_pop.addEventListener("input", updatePlot);
 	prey_birth_rate.addEventListener("input", updatePlot);
@@ -63,12 +71,48 @@ function updatePlot() {
     status.innerText = "Rendering...";
     const start = performance.now();
     
+    stopAnimation();
+    const [chosen_solver, params] = buildParams();
+    chart = Model.draw(canvas, chosen_solver, params);
+    canvas_text.innerHTML = `Max Time ($ t $): ${max_time.value}, ` +
+        `Initial Prey Pop ($ F(0) $): ${init_prey_pop.value}, ` + 
+        `Initial Predator Pop ($ M(0) $): ${init_predator_pop.value}<br/>` + 
+        `Prey Birth Rate ($ r $): ${prey_birth_rate.value}, ` + 
+        `Predator Death Rate ($ s $): ${predator_death_rate.value}, ` + 
+        `Hunting Meetings ($ a $): ${hunting_meetings.value}, ` +
+        `Hunt Offsprings ($ b $): ${hunt_offsprings.value}`;
+    MathJax.typeset();
+    const end = performance.now();
+    status.innerText = `Rendered in ${Math.ceil(end - start)}ms`;	
+}
+
+/** Parameters of the page, with the algorithm of the chosen solver. */
+function buildParams() {
     var params = Params.builder();
     var chosen_solver = "";
-    if (solver.value == "ssa") {
+    if (solver.value == "ssa" || solver.value == "nrm" || solver.value == "tau") {
         chosen_solver = "ssa";
+        params = params
+            .ssa_method(solver.value == "ssa" ? "direct" : solver.value)
+            .ssa_seed(seed.value);
+    }
+    else if (solver.value == "cle") {
+        chosen_solver = "cle";
         params = params.ssa_seed(seed.value);
     }
+    else if (solver.value == "compare") {
+        chosen_solver = "compare";
+        params = params
+            .solver("rk4")
+            .ssa_seed(seed.value);
+    }
+    else if (solver.value == "ensemble") {
+        chosen_solver = "ensemble";
+        params = params
+            .solver("rk4")
+            .ssa_seed(seed.value)
+            .ssa_replicas(Number(replicas.value));
+    }
     else {
         chosen_solver = "ode";
         params = params.solver(solver.value);
@@ -82,15 +126,43 @@ function updatePlot() {
         .predator_death_rate(Number(predator_death_rate.value))
         .hunting_meetings(Number(hunting_meetings.value))
         .hunt_offsprings(Number(hunt_offsprings.value));
-    chart = Model.draw(canvas, chosen_solver, params);
-    canvas_text.innerHTML = `Max Time ($ t $): ${max_time.value}, ` +
-        `Initial Prey Pop ($ F(0) $): ${init_prey_pop.value}, ` + 
-        `Initial Predator Pop ($ M(0) $): ${init_predator_pop.value}<br/>` + 
-        `Prey Birth Rate ($ r $): ${prey_birth_rate.value}, ` + 
-        `Predator Death Rate ($ s $): ${predator_death_rate.value}, ` + 
-        `Hunting Meetings ($ a $): ${hunting_meetings.value}, ` +
-        `Hunt Offsprings ($ b $): ${hunt_offsprings.value}`;
-    MathJax.typeset();
-    const end = performance.now();
-    status.innerText = `Rendered in ${Math.ceil(end - start)}ms`;	
+
+    return [chosen_solver, params];
+}
+
+/** Animate the chosen trajectory, computing it a frame at a time. */
+function animateFn() {
+    stopAnimation();
+
+    const [chosen_solver, params] = buildParams();
+    if (chosen_solver == "compare" || chosen_solver == "ensemble") {
+        status.innerText = "Only a single trajectory can be animated";
+        return;
+    }
+
+    stream = Stream.build(chosen_solver, params);
+    const time_step = Number(max_time.value) / 300;
+    const frame = () => {
+        stream.advance_time(time_step);
+        stream.draw(canvas);
+        status.innerText = `Animating, t = ${stream.time().toFixed(3)}`;
+
+        if (stream.finished()) {
+            stopAnimation();
+        } else {
+            animation = requestAnimationFrame(frame);
+        }
+    };
+    animation = requestAnimationFrame(frame);
+}
+
+function stopAnimation() {
+    if (animation != null) {
+        cancelAnimationFrame(animation);
+        animation = null;
+    }
+    if (stream != null) {
+        stream.free();
+        stream = null;
+    }
 }
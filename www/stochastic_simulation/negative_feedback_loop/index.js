@@ -1,18 +1,25 @@
 class Model {}
 class Params {}
+class Stream {}
 
 const canvas = document.getElementById("canvas");
 const status = document.getElementById("status");
 const canvas_text = document.getElementById("canvas_text");
+const animate = document.getElementById("animate");
 const solver = document.getElementById("solver");
 
 const init_g1_pop = document.getElementById("init_g1_pop");
 const init_g2_pop = document.getElementById("init_g2_pop");
 const init_g3_pop = document.getElementById("init_g3_pop");
+const production_delay = document.getElementById("production_delay");
+const delay_shape = document.getElementById("delay_shape");
 const seed = document.getElementById("seed");
+const replicas = document.getElementById("replicas");
 const max_time = document.getElementById("max_time");
 
 let chart = null;
+let stream = null;
+let animation = null;
 
 /** Main entry point */
 export function main() {
@@ -21,20 +28,25 @@ export function main() {
 }
 
 /** This function is used in `bootstrap.js` to setup imports. */
-export function setup(WasmModel, WasmParams) {
+export function setup(WasmModel, WasmParams, WasmStream) {
     Model = WasmModel;
     Params = WasmParams;
+    Stream = WasmStream;
 }
 
 /** Add event listeners. */
 function setupUI() {
     status.innerText = "WebAssembly loaded!";
     window.addEventListener("resize", setupCanvas);
+    animate.addEventListener("click", animateFn);
 	solver.addEventListener("input", updatePlot);
 	seed.addEventListener("input", updatePlot);
+	replicas.addEventListener("input", updatePlot);
 	init_g1_pop.addEventListener("input", updatePlot);
 	init_g2_pop.addEventListener("input", updatePlot);
 	init_g3_pop.addEventListener("input", updatePlot);
+	production_delay.addEventListener("input", updatePlot);
+	delay_shape.addEventListener("input", updatePlot);
     max_time.addEventListener("input", updatePlot);
 }
 
@@ -57,12 +69,46 @@ function updatePlot() {
     status.innerText = "Rendering...";
     const start = performance.now();
     
+    stopAnimation();
+    const [chosen_solver, params] = buildParams();
+    chart = Model.draw(canvas, chosen_solver, params);
+    canvas_text.innerHTML = `Max Time ($ t $): ${max_time.value}, ` +
+        `Initial $ \\ce{g1} $: ${init_g1_pop.value}, ` + 
+        `Initial $ \\ce{g2} $: ${init_g2_pop.value}, ` + 
+        `Initial $ \\ce{g3} $: ${init_g3_pop.value}, ` + 
+        `Production Delay ($ \\tau $): ${production_delay.value}`;
+    MathJax.typeset();
+    const end = performance.now();
+    status.innerText = `Rendered in ${Math.ceil(end - start)}ms`;	
+}
+
+/** Parameters of the page, with the algorithm of the chosen solver. */
+function buildParams() {
     var params = Params.builder();
     var chosen_solver = "";
-    if (solver.value == "ssa") {
+    if (solver.value == "ssa" || solver.value == "nrm" || solver.value == "tau") {
         chosen_solver = "ssa";
+        params = params
+            .ssa_method(solver.value == "ssa" ? "direct" : solver.value)
+            .ssa_seed(seed.value);
+    }
+    else if (solver.value == "cle") {
+        chosen_solver = "cle";
         params = params.ssa_seed(seed.value);
     }
+    else if (solver.value == "compare") {
+        chosen_solver = "compare";
+        params = params
+            .solver("rk4")
+            .ssa_seed(seed.value);
+    }
+    else if (solver.value == "ensemble") {
+        chosen_solver = "ensemble";
+        params = params
+            .solver("rk4")
+            .ssa_seed(seed.value)
+            .ssa_replicas(Number(replicas.value));
+    }
     else {
         chosen_solver = "ode";
         params = params.solver(solver.value);
@@ -74,13 +120,46 @@ function updatePlot() {
         .production_rates(10, 10000, 10)
         .binding_rates(10, 0.1, 10)
         .unbinding_rates(2, 20, 20)
-        .decay_rates(1, 100, 1);
-    chart = Model.draw(canvas, chosen_solver, params);
-    canvas_text.innerHTML = `Max Time ($ t $): ${max_time.value}, ` +
-        `Initial $ \\ce{g1} $: ${init_g1_pop.value}, ` + 
-        `Initial $ \\ce{g2} $: ${init_g2_pop.value}, ` + 
-        `Initial $ \\ce{g3} $: ${init_g3_pop.value}`;
-    MathJax.typeset();
-    const end = performance.now();
-    status.innerText = `Rendered in ${Math.ceil(end - start)}ms`;	
+        .decay_rates(1, 100, 1)
+        .production_delay(Number(production_delay.value))
+        .delay_shape(Number(delay_shape.value));
+
+    return [chosen_solver, params];
+}
+
+/** Animate the chosen trajectory, computing it a frame at a time. */
+function animateFn() {
+    stopAnimation();
+
+    const [chosen_solver, params] = buildParams();
+    if (chosen_solver == "compare" || chosen_solver == "ensemble") {
+        status.innerText = "Only a single trajectory can be animated";
+        return;
+    }
+
+    stream = Stream.build(chosen_solver, params);
+    const time_step = Number(max_time.value) / 300;
+    const frame = () => {
+        stream.advance_time(time_step);
+        stream.draw(canvas);
+        status.innerText = `Animating, t = ${stream.time().toFixed(3)}`;
+
+        if (stream.finished()) {
+            stopAnimation();
+        } else {
+            animation = requestAnimationFrame(frame);
+        }
+    };
+    animation = requestAnimationFrame(frame);
+}
+
+function stopAnimation() {
+    if (animation != null) {
+        cancelAnimationFrame(animation);
+        animation = null;
+    }
+    if (stream != null) {
+        stream.free();
+        stream = null;
+    }
 }